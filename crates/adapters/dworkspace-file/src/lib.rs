@@ -0,0 +1,390 @@
+//! A repository which keeps the event log in a local file.
+//!
+//! Each record is the length of its payload, the CRC32 of the length, and the CRC32 of the payload
+//! in little endian, followed by the payload, which is an `EventEntry` encoded by bincode.
+
+use std::{
+    collections::HashSet,
+    ffi::OsString,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use dworkspace::repository::{NotOwnerError, Repository, RepositoryError};
+use dworkspace_codebase::{
+    event::{Event, EventEntry},
+    user::UserId,
+};
+use thiserror::Error;
+
+const HEADER_LEN: usize = 12;
+
+pub struct FileRepository {
+    user_id: UserId,
+    path: PathBuf,
+    log: File,
+    // Locked while the repository is open.
+    _lock: File,
+    entries: Vec<EventEntry>,
+    /// The number of entries already polled.
+    polled: usize,
+    index: usize,
+    /// Users who can compact this repository, which drops the history of the space.
+    pub owners: HashSet<UserId>,
+}
+
+#[derive(Error, Debug)]
+pub enum FileRepositoryError {
+    #[error("{0} is opened by another process")]
+    Locked(PathBuf),
+    #[error("the record at {offset} is corrupted")]
+    Corrupted { offset: usize },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Bincode(#[from] bincode::Error),
+}
+
+impl FileRepository {
+    /// Opens the log at the path, or creates it if it does not exist.
+    pub fn open(user_id: UserId, path: impl Into<PathBuf>) -> Result<Self, FileRepositoryError> {
+        let path = path.into();
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(with_suffix(&path, ".lock"))?;
+        lock.try_lock().map_err(|err| match err {
+            TryLockError::WouldBlock => FileRepositoryError::Locked(path.clone()),
+            TryLockError::Error(err) => err.into(),
+        })?;
+        let mut log = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        let mut bytes = vec![];
+        log.read_to_end(&mut bytes)?;
+        let (entries, len) = read_records(&bytes)?;
+        if len < bytes.len() {
+            // A crash while appending leaves a torn record at the end.
+            log.set_len(len as u64)?;
+            log.sync_all()?;
+        }
+        Ok(Self {
+            user_id,
+            path,
+            log,
+            _lock: lock,
+            index: entries.last().map(|entry| entry.index + 1).unwrap_or(0),
+            entries,
+            polled: 0,
+            owners: HashSet::new(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn append(&mut self, entry: &EventEntry) -> Result<(), FileRepositoryError> {
+        let record = encode(entry)?;
+        let len = self.log.metadata()?.len();
+        // An event is committed only after it reaches the disk.
+        let result = self
+            .log
+            .write_all(&record)
+            .and_then(|_| self.log.sync_data());
+        if let Err(err) = result {
+            // A partial record would be taken as corrupted once another record follows it.
+            self.log.set_len(len)?;
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    /// Replaces the log with the entries from the position, so a crash leaves either of the logs.
+    fn rewrite_from(&mut self, position: usize) -> Result<(), FileRepositoryError> {
+        let compacted = with_suffix(&self.path, ".compact");
+        let mut file = File::create(&compacted)?;
+        for entry in &self.entries[position..] {
+            file.write_all(&encode(entry)?)?;
+        }
+        file.sync_all()?;
+        fs::rename(&compacted, &self.path)?;
+        #[cfg(unix)]
+        {
+            let dir = match self.path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            File::open(dir)?.sync_all()?;
+        }
+        self.log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&self.path)?;
+        self.entries.drain(..position);
+        self.polled -= position;
+        Ok(())
+    }
+}
+
+impl Repository for FileRepository {
+    fn user_id(&self) -> UserId {
+        self.user_id.clone()
+    }
+
+    fn poll(&mut self) -> Vec<EventEntry> {
+        let entries = self.entries[self.polled..].to_vec();
+        self.polled = self.entries.len();
+        entries
+    }
+
+    fn commit(&mut self, event: Event) -> Result<(), RepositoryError> {
+        let entry = EventEntry {
+            index: self.index,
+            user_id: self.user_id.clone(),
+            event,
+        };
+        self.append(&entry)?;
+        self.entries.push(entry);
+        self.index += 1;
+        Ok(())
+    }
+
+    fn add_owner(&mut self, user_id: UserId) {
+        self.owners.insert(user_id);
+    }
+
+    fn remove_owner(&mut self, user_id: UserId) {
+        self.owners.remove(&user_id);
+    }
+
+    fn compact(&mut self, index: usize) -> Result<(), RepositoryError> {
+        // Compaction is not an event, so workspaces cannot audit it.
+        if !self.owners.is_empty() && !self.owners.contains(&self.user_id) {
+            return Err(NotOwnerError(self.user_id.clone()).into());
+        }
+        // Only snapshots which a workspace applied can replace the events before them.
+        let position = self.entries[..self.polled].iter().position(|entry| {
+            entry.index == index && matches!(entry.event, Event::AddSnapshot { .. })
+        });
+        if let Some(position) = position {
+            self.rewrite_from(position)?;
+        }
+        Ok(())
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    path.into()
+}
+
+fn encode(entry: &EventEntry) -> Result<Vec<u8>, FileRepositoryError> {
+    let payload = bincode::serialize(entry)?;
+    let len = (payload.len() as u32).to_le_bytes();
+    let mut record = Vec::with_capacity(HEADER_LEN + payload.len());
+    record.extend(len);
+    record.extend(crc32fast::hash(&len).to_le_bytes());
+    record.extend(crc32fast::hash(&payload).to_le_bytes());
+    record.extend(payload);
+    Ok(record)
+}
+
+/// Entries in the bytes and the length of the valid records.
+fn read_records(bytes: &[u8]) -> Result<(Vec<EventEntry>, usize), FileRepositoryError> {
+    let mut entries = vec![];
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let rest = &bytes[offset..];
+        // A header which doesn't fit is the last bytes of the file.
+        if rest.len() < HEADER_LEN {
+            break;
+        }
+        if crc32fast::hash(&rest[0..4]) != u32::from_le_bytes(rest[4..8].try_into().unwrap()) {
+            return Err(FileRepositoryError::Corrupted { offset });
+        }
+        let len = u32::from_le_bytes(rest[0..4].try_into().unwrap()) as usize;
+        let crc = u32::from_le_bytes(rest[8..12].try_into().unwrap());
+        // The length is checked, so a payload which doesn't fit is the last record.
+        let Some(payload) = rest.get(HEADER_LEN..HEADER_LEN + len) else {
+            break;
+        };
+        if crc32fast::hash(payload) != crc {
+            // Only the last record can be torn.
+            if offset + HEADER_LEN + len == bytes.len() {
+                break;
+            }
+            return Err(FileRepositoryError::Corrupted { offset });
+        }
+        entries.push(bincode::deserialize(payload)?);
+        offset += HEADER_LEN + len;
+    }
+    Ok((entries, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use dworkspace::Workspace;
+    use dworkspace_codebase::{
+        rules::{Rules, SpaceOperation},
+        snapshot::Snapshot,
+    };
+
+    use super::*;
+
+    struct TempLog(PathBuf);
+
+    impl TempLog {
+        fn new() -> Self {
+            Self(std::env::temp_dir().join(format!("dworkspace-file-{}", uuid::Uuid::new_v4())))
+        }
+
+        fn open(&self) -> FileRepository {
+            FileRepository::open(UserId("a".into()), &self.0).unwrap()
+        }
+    }
+
+    impl Drop for TempLog {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+            let _ = fs::remove_file(with_suffix(&self.0, ".lock"));
+        }
+    }
+
+    fn add_owner() -> Event {
+        Event::AddOwner {
+            user_id: UserId("a".into()),
+        }
+    }
+
+    fn update_space_rules() -> Event {
+        Event::UpdateSpaceRules {
+            rules: Rules {
+                default: [SpaceOperation::CreateNode].into_iter().collect(),
+                users: Default::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn keeps_entries_across_restarts() {
+        let log = TempLog::new();
+        let mut repository = log.open();
+        repository.commit(add_owner()).unwrap();
+        repository.commit(update_space_rules()).unwrap();
+        drop(repository);
+
+        let mut repository = log.open();
+        repository.commit(add_owner()).unwrap();
+        let entries = repository.poll();
+        assert_eq!(
+            entries.iter().map(|entry| entry.index).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(entries[1].event, update_space_rules());
+    }
+
+    #[test]
+    fn truncates_torn_record() {
+        let log = TempLog::new();
+        let mut repository = log.open();
+        repository.commit(add_owner()).unwrap();
+        drop(repository);
+        let len = fs::metadata(&log.0).unwrap().len();
+        let record = encode(&EventEntry {
+            index: 1,
+            user_id: UserId("a".into()),
+            event: update_space_rules(),
+        })
+        .unwrap();
+        let mut file = OpenOptions::new().append(true).open(&log.0).unwrap();
+        file.write_all(&record[..record.len() - 1]).unwrap();
+        drop(file);
+
+        let mut repository = log.open();
+        assert_eq!(repository.poll().len(), 1);
+        assert_eq!(fs::metadata(&log.0).unwrap().len(), len);
+    }
+
+    #[test]
+    fn rejects_corrupted_record() {
+        let log = TempLog::new();
+        let mut repository = log.open();
+        repository.commit(add_owner()).unwrap();
+        repository.commit(add_owner()).unwrap();
+        drop(repository);
+        let mut bytes = fs::read(&log.0).unwrap();
+        bytes[HEADER_LEN] ^= 0xff;
+        fs::write(&log.0, bytes).unwrap();
+
+        assert!(matches!(
+            FileRepository::open(UserId("a".into()), &log.0),
+            Err(FileRepositoryError::Corrupted { offset: 0 })
+        ));
+    }
+
+    #[test]
+    fn rejects_corrupted_length() {
+        let log = TempLog::new();
+        let mut repository = log.open();
+        repository.commit(add_owner()).unwrap();
+        repository.commit(add_owner()).unwrap();
+        drop(repository);
+        let mut bytes = fs::read(&log.0).unwrap();
+        // The payload of the first record would run past the end of the file.
+        bytes[3] ^= 0xff;
+        fs::write(&log.0, bytes).unwrap();
+
+        assert!(matches!(
+            FileRepository::open(UserId("a".into()), &log.0),
+            Err(FileRepositoryError::Corrupted { offset: 0 })
+        ));
+    }
+
+    #[test]
+    fn locks_file() {
+        let log = TempLog::new();
+        let _repository = log.open();
+        assert!(matches!(
+            FileRepository::open(UserId("b".into()), &log.0),
+            Err(FileRepositoryError::Locked(_))
+        ));
+    }
+
+    #[test]
+    fn loads_compacted_log() {
+        let log = TempLog::new();
+        let mut snapshot = Snapshot::default();
+        snapshot.handle_event(&add_owner());
+        let mut repository = log.open();
+        repository.commit(add_owner()).unwrap();
+        repository
+            .commit(Event::AddSnapshot {
+                index: 0,
+                snapshot: Box::new(snapshot),
+            })
+            .unwrap();
+        repository.commit(update_space_rules()).unwrap();
+        repository.poll();
+        repository.compact(1).unwrap();
+        drop(repository);
+
+        let repository = log.open();
+        assert_eq!(repository.entries.len(), 2);
+        let mut workspace = Workspace::new(repository);
+        workspace.process();
+        assert_eq!(
+            workspace.snapshot.owners,
+            [UserId("a".into())].into_iter().collect()
+        );
+        assert_eq!(
+            workspace.snapshot.rules.default,
+            [SpaceOperation::CreateNode].into_iter().collect()
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use dworkspace::repository::Repository;
+use std::collections::HashSet;
+
+use dworkspace::repository::{NotOwnerError, Repository, RepositoryError};
 use dworkspace_codebase::{
     event::{Event, EventEntry},
     user::UserId,
@@ -8,37 +10,181 @@ pub struct InMemoryRepository {
     user_id: UserId,
     index: usize,
     pub entries: Vec<EventEntry>,
+    /// The number of entries already polled.
+    polled: usize,
+    /// Users who can compact this repository, which drops the history of the space.
+    pub owners: HashSet<UserId>,
 }
 
 impl InMemoryRepository {
     pub fn new(user_id: UserId) -> Self {
+        Self::load(user_id, Vec::new())
+    }
+
+    /// A repository with a log, which may be compacted to start with a snapshot.
+    pub fn load(user_id: UserId, entries: Vec<EventEntry>) -> Self {
         Self {
             user_id,
-            index: 0,
-            entries: Vec::new(),
+            index: entries.last().map(|entry| entry.index + 1).unwrap_or(0),
+            entries,
+            polled: 0,
+            owners: HashSet::new(),
         }
     }
 }
 
 impl Repository for InMemoryRepository {
+    fn user_id(&self) -> UserId {
+        self.user_id.clone()
+    }
+
     fn poll(&mut self) -> Vec<EventEntry> {
-        self.entries.drain(..).collect()
+        let entries = self.entries[self.polled..].to_vec();
+        self.polled = self.entries.len();
+        entries
     }
 
-    fn commit(&mut self, event: Event) {
+    fn commit(&mut self, event: Event) -> Result<(), RepositoryError> {
         self.entries.push(EventEntry {
             index: self.index,
             user_id: self.user_id.clone(),
             event,
         });
         self.index += 1;
+        Ok(())
+    }
+
+    fn add_owner(&mut self, user_id: UserId) {
+        self.owners.insert(user_id);
+    }
+
+    fn remove_owner(&mut self, user_id: UserId) {
+        self.owners.remove(&user_id);
     }
 
-    fn add_owner(&mut self, _user_id: UserId) {
-        todo!()
+    fn compact(&mut self, index: usize) -> Result<(), RepositoryError> {
+        // Compaction is not an event, so workspaces cannot audit it.
+        if !self.owners.is_empty() && !self.owners.contains(&self.user_id) {
+            return Err(NotOwnerError(self.user_id.clone()).into());
+        }
+        // Only snapshots which a workspace applied can replace the events before them.
+        let position = self.entries[..self.polled].iter().position(|entry| {
+            entry.index == index && matches!(entry.event, Event::AddSnapshot { .. })
+        });
+        if let Some(position) = position {
+            self.entries.drain(..position);
+            self.polled -= position;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dworkspace::Workspace;
+    use dworkspace_codebase::{
+        rules::{Rules, SpaceOperation},
+        snapshot::Snapshot,
+    };
+
+    use super::*;
+
+    #[test]
+    fn loads_compacted_log() {
+        let user_id = UserId("a".into());
+        let add_owner = Event::AddOwner {
+            user_id: user_id.clone(),
+        };
+        let mut snapshot = Snapshot::default();
+        snapshot.handle_event(&add_owner);
+        let mut repository = InMemoryRepository::new(user_id.clone());
+        repository.commit(add_owner).unwrap();
+        repository
+            .commit(Event::AddSnapshot {
+                index: 0,
+                snapshot: Box::new(snapshot),
+            })
+            .unwrap();
+        repository
+            .commit(Event::UpdateSpaceRules {
+                rules: Rules {
+                    default: [SpaceOperation::CreateNode].into_iter().collect(),
+                    users: Default::default(),
+                },
+            })
+            .unwrap();
+        let mut replayed = Workspace::new(InMemoryRepository::load(
+            user_id.clone(),
+            repository.entries.clone(),
+        ));
+        replayed.process();
+
+        repository.poll();
+        repository.compact(1).unwrap();
+        assert_eq!(repository.entries.len(), 2);
+        let mut loaded = Workspace::new(InMemoryRepository::load(user_id, repository.entries));
+        loaded.process();
+        assert_eq!(loaded.snapshot, replayed.snapshot);
+    }
+
+    #[test]
+    fn keeps_log_without_snapshot_at_index() {
+        let mut repository = InMemoryRepository::new(UserId("a".into()));
+        repository
+            .commit(Event::AddOwner {
+                user_id: UserId("a".into()),
+            })
+            .unwrap();
+        repository
+            .commit(Event::AddSnapshot {
+                index: 0,
+                snapshot: Default::default(),
+            })
+            .unwrap();
+        repository.poll();
+        repository.compact(0).unwrap();
+        assert_eq!(repository.entries.len(), 2);
+    }
+
+    #[test]
+    fn only_owners_compact() {
+        let user_a = UserId("a".into());
+        let mut repository = InMemoryRepository::new(user_a.clone());
+        repository
+            .commit(Event::AddOwner {
+                user_id: user_a.clone(),
+            })
+            .unwrap();
+        repository
+            .commit(Event::AddSnapshot {
+                index: 0,
+                snapshot: Default::default(),
+            })
+            .unwrap();
+        repository.poll();
+        repository.add_owner(UserId("b".into()));
+        assert_eq!(
+            repository
+                .compact(1)
+                .unwrap_err()
+                .downcast_ref::<NotOwnerError>(),
+            Some(&NotOwnerError(user_a.clone()))
+        );
+        assert_eq!(repository.entries.len(), 2);
+
+        repository.add_owner(user_a);
+        repository.compact(1).unwrap();
+        assert_eq!(repository.entries.len(), 1);
     }
 
-    fn remove_owner(&mut self, _user_id: UserId) {
-        todo!()
+    #[test]
+    fn manages_owners() {
+        let user_a = UserId("a".into());
+        let user_b = UserId("b".into());
+        let mut repository = InMemoryRepository::new(user_a.clone());
+        repository.add_owner(user_a.clone());
+        repository.add_owner(user_b.clone());
+        repository.remove_owner(user_a);
+        assert_eq!(repository.owners, [user_b].into_iter().collect());
     }
 }
@@ -3,6 +3,9 @@ use bevy::prelude::*;
 use desk_window::window::{DefaultWindow, Window};
 use dworkspace::Workspace;
 use dworkspace_codebase::{event::Event, user::UserId};
+#[cfg(not(target_arch = "wasm32"))]
+use dworkspace_file::FileRepository;
+#[cfg(target_arch = "wasm32")]
 use dworkspace_in_memory::InMemoryRepository;
 
 pub struct WindowsPlugin;
@@ -15,11 +18,34 @@ impl Plugin for WindowsPlugin {
 
 pub fn setup(mut commands: Commands) {
     let user_id = UserId("me".into());
-    let mut kernel = Workspace::new(InMemoryRepository::new(user_id.clone()));
-    kernel.commit(Event::AddOwner { user_id });
+    let kernel = workspace(user_id);
     commands
         .spawn()
         .insert(DefaultWindow)
         .insert(Window::<egui::Context>::default())
         .insert(kernel);
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+fn workspace(user_id: UserId) -> Workspace {
+    let path = std::env::var_os("DESK_WORKSPACE").unwrap_or_else(|| "workspace.desk".into());
+    let repository =
+        FileRepository::open(user_id.clone(), path).expect("failed to open the workspace");
+    let is_new = repository.is_empty();
+    let mut kernel = Workspace::new(repository);
+    if is_new {
+        kernel
+            .commit(Event::AddOwner { user_id })
+            .expect("failed to add the owner");
+    }
+    kernel
+}
+
+#[cfg(target_arch = "wasm32")]
+fn workspace(user_id: UserId) -> Workspace {
+    let mut kernel = Workspace::new(InMemoryRepository::new(user_id.clone()));
+    kernel
+        .commit(Event::AddOwner { user_id })
+        .expect("failed to add the owner");
+    kernel
+}
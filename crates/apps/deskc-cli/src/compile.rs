@@ -0,0 +1,186 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use ast::{expr::Expr, span::WithSpan};
+use dprocess::{
+    interpreter_builder::InterpreterBuilder, interpreter_output::InterpreterOutput, value::Value,
+};
+use hir::meta::WithMeta;
+use hirgen::HirGen;
+use ids::LinkName;
+use mir::{artifact::Artifact, mir::Mir};
+use textual_diagnostics::TextualDiagnostics;
+use thir::TypedHir;
+use tokens::Tokens;
+use typeinfer::ctx::Ctx;
+
+pub struct Hir {
+    pub hirgen: HirGen,
+    pub entrypoint: WithMeta<hir::expr::Expr>,
+    pub cards: Vec<(ids::CardId, WithMeta<hir::expr::Expr>)>,
+}
+
+pub struct Typed {
+    pub hir: Hir,
+    pub ctx: Ctx,
+}
+
+pub fn tokens(input: &str) -> Result<Tokens, TextualDiagnostics> {
+    Ok(lexer::scan(input)?)
+}
+
+pub fn ast(input: &str) -> Result<WithSpan<Expr>, TextualDiagnostics> {
+    Ok(parser::parse(tokens(input)?)?)
+}
+
+pub fn hir(input: &str) -> Result<Hir, TextualDiagnostics> {
+    let (hirgen, hir) = hirgen::gen_cards(&ast(input)?)?;
+    let entrypoint = hir.entrypoint.ok_or_else(|| TextualDiagnostics {
+        title: "No entrypoint".into(),
+        reports: vec![],
+    })?;
+    Ok(Hir {
+        hirgen,
+        entrypoint,
+        cards: hir.cards,
+    })
+}
+
+pub fn typed(input: &str) -> Result<Typed, TextualDiagnostics> {
+    let hir = hir(input)?;
+    let ctx = synth(hir.hirgen.next_id(), &hir.entrypoint)?;
+    Ok(Typed { hir, ctx })
+}
+
+/// Types the expression and reports all type errors at once.
+fn synth(next_id: usize, hir: &WithMeta<hir::expr::Expr>) -> Result<Ctx, TextualDiagnostics> {
+    let (ctx, _ty, errors) = typeinfer::synth_with_recovery(next_id, hir);
+    if errors.0.is_empty() {
+        Ok(ctx)
+    } else {
+        Err(errors.into())
+    }
+}
+
+pub fn thir(input: &str) -> Result<TypedHir, TextualDiagnostics> {
+    let Typed { hir, ctx } = typed(input)?;
+    thirgen::exhaustiveness::check_matches(&ctx.get_types(), &hir.entrypoint)?;
+    Ok(thirgen::gen_typed_hir(
+        ctx.next_id(),
+        ctx.get_types(),
+        &hir.entrypoint,
+    ))
+}
+
+fn gen_thir(
+    next_id: usize,
+    hir: &WithMeta<hir::expr::Expr>,
+) -> Result<TypedHir, TextualDiagnostics> {
+    let ctx = synth(next_id, hir)?;
+    thirgen::exhaustiveness::check_matches(&ctx.get_types(), hir)?;
+    Ok(thirgen::gen_typed_hir(ctx.next_id(), ctx.get_types(), hir))
+}
+
+fn gen_mir(thir: &TypedHir, hir: &WithMeta<hir::expr::Expr>) -> Result<Mir, TextualDiagnostics> {
+    mirgen::gen_mir(thir).map_err(|error| error.with_spans(&hir::helper::spans(hir)).into())
+}
+
+/// Compiles the entrypoint and links the cards in the same file.
+pub fn mir(input: &str) -> Result<Mir, TextualDiagnostics> {
+    Ok(link(input)?.1)
+}
+
+/// The THIR of the entrypoint and the linked MIR.
+fn link(input: &str) -> Result<(TypedHir, Mir), TextualDiagnostics> {
+    let hir = hir(input)?;
+    let next_id = hir.hirgen.next_id();
+    let thir = gen_thir(next_id, &hir.entrypoint)?;
+    let entrypoint = gen_mir(&thir, &hir.entrypoint)?;
+    let cards = hir
+        .cards
+        .iter()
+        .map(|(id, card)| {
+            let mir = gen_mir(&gen_thir(next_id, card)?, card)?;
+            Ok((LinkName::Card(id.0), mir))
+        })
+        .collect::<Result<_, TextualDiagnostics>>()?;
+    let mir = linker::link(entrypoint, &cards).map_err(|errors| {
+        let spans = std::iter::once(&hir.entrypoint)
+            .chain(hir.cards.iter().map(|(_, card)| card))
+            .flat_map(hir::helper::link_spans)
+            .collect();
+        errors.with_spans(&spans)
+    })?;
+    Ok((thir, mir))
+}
+
+pub fn artifact(input: &str) -> Result<Artifact, TextualDiagnostics> {
+    let (thir, mir) = link(input)?;
+    Ok(Artifact::new(mir, thir::hash::types_hash(&thir)))
+}
+
+pub fn run(builder: &dyn InterpreterBuilder) -> Result<Value> {
+    let mut miri = builder.build();
+    loop {
+        match miri.reduce(&Duration::from_secs(1))? {
+            InterpreterOutput::Returned(value) => return Ok(value),
+            InterpreterOutput::Performed { input, effect } => {
+                return Err(anyhow!("unhandled effect {:?} with {:?}", effect, input))
+            }
+            InterpreterOutput::Running => continue,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dprocess::value::Number;
+    use miri::MiriBuilder;
+
+    use super::*;
+
+    #[test]
+    fn runs_source() {
+        let mir = mir("> \\ 'number, 'number -> @sum 'number ~ 1, 2").unwrap();
+        assert_eq!(
+            run(&MiriBuilder::new(mir)).unwrap(),
+            Value::Number(Number::Integer(3))
+        );
+    }
+
+    #[test]
+    fn runs_artifact() {
+        let bytes = artifact("> \\ 'number, 'number -> @sum 'number ~ 1, 2")
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+        assert_eq!(
+            run(&MiriBuilder::from_bytes(&bytes).unwrap()).unwrap(),
+            Value::Number(Number::Integer(3))
+        );
+    }
+
+    #[test]
+    fn artifact_has_types_hash_of_source() {
+        let source = "> \\ 'number, 'number -> @sum 'number ~ 1, 2";
+        let artifact = artifact(source).unwrap();
+        assert!(artifact
+            .check_types_hash(thir::hash::types_hash(&thir(source).unwrap()))
+            .is_ok());
+        assert!(artifact
+            .check_types_hash(thir::hash::types_hash(&thir("1").unwrap()))
+            .is_err());
+    }
+
+    #[test]
+    fn reports_parser_error() {
+        let diagnostics = ast("$ 1").err().unwrap();
+        assert_eq!(diagnostics.title, "Parser error");
+    }
+
+    #[test]
+    fn reports_type_error() {
+        let diagnostics = typed("> \\ 'number -> 'number ~ \"a\"").err().unwrap();
+        assert_eq!(diagnostics.title, "Typeinfer error");
+    }
+}
@@ -0,0 +1,53 @@
+use ariadne::{Label, Report, ReportKind, Source};
+use textual_diagnostics::{Severity, TextualDiagnostics};
+
+pub fn print_diagnostics(input: &str, diagnostics: TextualDiagnostics) {
+    // Diagnostics like "No entrypoint" have only a title.
+    if diagnostics.reports.is_empty() {
+        Report::<std::ops::Range<usize>>::build(ReportKind::Error, (), 0)
+            .with_message(diagnostics.title)
+            .finish()
+            .eprint(Source::from(input))
+            .unwrap();
+        return;
+    }
+    for report in diagnostics.reports {
+        let kind = match report.severity {
+            Severity::Error => ReportKind::Error,
+            Severity::Warning => ReportKind::Warning,
+            Severity::Information | Severity::Hint => ReportKind::Advice,
+        };
+        let builder = Report::build(kind, (), report.span.start)
+            .with_code(report.code)
+            .with_message(&diagnostics.title)
+            .with_label(Label::new(report.span).with_message(report.text));
+        let builder = report.labels.into_iter().fold(builder, |builder, label| {
+            builder.with_label(Label::new(label.span).with_message(label.text))
+        });
+        // ariadne shows only one note and one help.
+        let builder = if report.notes.is_empty() {
+            builder
+        } else {
+            builder.with_note(report.notes.join("\n"))
+        };
+        let builder = if report.suggestions.is_empty() {
+            builder
+        } else {
+            builder.with_help(
+                report
+                    .suggestions
+                    .iter()
+                    .map(|suggestion| {
+                        format!("{}: `{}`", suggestion.text, suggestion.replacement.trim())
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        };
+        builder.finish().eprint(Source::from(input)).unwrap();
+    }
+}
+
+pub fn print_json_diagnostics(diagnostics: TextualDiagnostics) {
+    println!("{}", diagnostics.to_json());
+}
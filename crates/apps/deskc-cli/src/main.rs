@@ -1,3 +1,156 @@
-fn main() {
-    println!("Hello, world!");
+mod compile;
+mod diagnostics;
+
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use diagnostics::{print_diagnostics, print_json_diagnostics};
+use miri::MiriBuilder;
+use textual_diagnostics::TextualDiagnostics;
+
+#[derive(Parser)]
+#[clap(name = "deskc", about = "Compiles and runs Desk-lang sources")]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+    /// How diagnostics are printed.
+    #[clap(long, value_enum, global = true, default_value = "human")]
+    message_format: MessageFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MessageFormat {
+    /// Prints to stderr with the source code.
+    Human,
+    /// Prints one JSON object per line to stdout.
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Type checks the file.
+    Check { file: PathBuf },
+    /// Compiles the file into an artifact.
+    Build {
+        file: PathBuf,
+        /// Defaults to the file with `dskc` extension.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Compiles the file and runs it on miri.
+    Run { file: PathBuf },
+    /// Runs the artifact on miri.
+    Exec { artifact: PathBuf },
+    /// Prints the intermediate representation of the given stage.
+    Dump {
+        file: PathBuf,
+        #[clap(long, value_enum)]
+        stage: Stage,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Stage {
+    Tokens,
+    Ast,
+    Hir,
+    Thir,
+    Mir,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let file = match &cli.command {
+        Command::Check { file }
+        | Command::Build { file, .. }
+        | Command::Run { file }
+        | Command::Dump { file, .. } => file,
+        // An artifact is not a source code.
+        Command::Exec { artifact } => return exec(artifact, cli.message_format),
+    };
+    let input = match fs::read_to_string(file) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("failed to read {}: {}", file.display(), err);
+            return ExitCode::FAILURE;
+        }
+    };
+    match execute(&cli.command, &input) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(diagnostics) => {
+            report(cli.message_format, &input, diagnostics);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn report(format: MessageFormat, input: &str, diagnostics: TextualDiagnostics) {
+    match format {
+        MessageFormat::Human => print_diagnostics(input, diagnostics),
+        MessageFormat::Json => print_json_diagnostics(diagnostics),
+    }
+}
+
+fn execute(command: &Command, input: &str) -> Result<(), TextualDiagnostics> {
+    match command {
+        Command::Check { .. } => {
+            compile::typed(input)?;
+        }
+        Command::Build { file, output } => {
+            let bytes = compile::artifact(input)?
+                .to_bytes()
+                .map_err(|err| error("E0002", "Artifact error", err))?;
+            let output = output
+                .clone()
+                .unwrap_or_else(|| file.with_extension("dskc"));
+            fs::write(&output, bytes).map_err(|err| error("E0002", "Artifact error", err))?;
+        }
+        Command::Run { .. } => {
+            let mir = compile::mir(input)?;
+            let value = compile::run(&MiriBuilder::new(mir))
+                .map_err(|err| error("E0003", "Runtime error", err))?;
+            println!("{:?}", value);
+        }
+        Command::Exec { .. } => unreachable!("artifact is executed without reading source"),
+        Command::Dump { stage, .. } => match stage {
+            Stage::Tokens => println!("{:#?}", compile::tokens(input)?),
+            Stage::Ast => println!("{:#?}", compile::ast(input)?),
+            Stage::Hir => println!("{:#?}", compile::hir(input)?.entrypoint),
+            Stage::Thir => println!("{:#?}", compile::thir(input)?),
+            Stage::Mir => println!("{:#?}", compile::mir(input)?),
+        },
+    }
+    Ok(())
+}
+
+fn exec(artifact: &PathBuf, format: MessageFormat) -> ExitCode {
+    let result = fs::read(artifact)
+        .map_err(|err| error("E0002", "Artifact error", err))
+        .and_then(|bytes| {
+            MiriBuilder::from_bytes(&bytes).map_err(|err| error("E0002", "Artifact error", err))
+        })
+        .and_then(|builder| {
+            compile::run(&builder).map_err(|err| error("E0003", "Runtime error", err))
+        });
+    match result {
+        Ok(value) => {
+            println!("{:?}", value);
+            ExitCode::SUCCESS
+        }
+        Err(diagnostics) => {
+            report(format, "", diagnostics);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn error(code: &'static str, title: &str, err: impl ToString) -> TextualDiagnostics {
+    TextualDiagnostics {
+        title: title.into(),
+        reports: vec![textual_diagnostics::Report::error(
+            code,
+            0..0,
+            err.to_string(),
+        )],
+    }
 }
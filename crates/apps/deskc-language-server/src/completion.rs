@@ -0,0 +1,26 @@
+use deskc::card::CardQueries;
+use lsp_types::{CompletionItem, CompletionItemKind, Url};
+
+use crate::documents::Documents;
+
+pub fn completion(documents: &Documents, uri: &Url) -> Vec<CompletionItem> {
+    let hir = match documents
+        .get(uri)
+        .and_then(|document| documents.compiler.hir(document.card_id.clone()).ok())
+    {
+        Some(hir) => hir,
+        None => return vec![],
+    };
+    let mut items: Vec<_> = hir
+        .type_aliases
+        .iter()
+        .map(|(alias, ty)| CompletionItem {
+            label: alias.clone(),
+            kind: Some(CompletionItemKind::TYPE_PARAMETER),
+            detail: Some(ty.to_string()),
+            ..Default::default()
+        })
+        .collect();
+    items.sort_by(|a, b| a.label.cmp(&b.label));
+    items
+}
@@ -0,0 +1,18 @@
+use deskc::card::CardQueries;
+use lsp_types::{Location, Position, Url};
+
+use crate::{
+    documents::Documents,
+    nodes::{card_reference_at, cards},
+};
+
+pub fn definition(documents: &Documents, uri: &Url, position: Position) -> Option<Location> {
+    let document = documents.get(uri)?;
+    let ast = documents.compiler.ast(document.card_id.clone()).ok()?;
+    let uuid = card_reference_at(&ast, document.line_index.offset(position))?;
+    documents.iter().find_map(|(uri, document)| {
+        let ast = documents.compiler.ast(document.card_id.clone()).ok()?;
+        let span = cards(&ast).remove(&uuid)?;
+        Some(Location::new(uri.clone(), document.line_index.range(&span)))
+    })
+}
@@ -0,0 +1,64 @@
+use deskc::card::CardQueries;
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString, Url,
+};
+use textual_diagnostics::{Severity, TextualDiagnostics};
+
+use crate::documents::Documents;
+
+pub fn diagnostics(documents: &Documents, uri: &Url) -> Vec<Diagnostic> {
+    let document = match documents.get(uri) {
+        Some(document) => document,
+        None => return vec![],
+    };
+    // Errors of the former stages are propagated by queries.
+    // Holes are expected while code is being written, so they are reported as information.
+    let TextualDiagnostics { title, reports } =
+        match documents.compiler.typeinfer(document.card_id.clone()) {
+            Ok(result) => result.holes.clone().into(),
+            Err(error) => (&error).into(),
+        };
+    reports
+        .into_iter()
+        .map(|report| {
+            let related_information = report
+                .labels
+                .iter()
+                .map(|label| DiagnosticRelatedInformation {
+                    location: Location::new(uri.clone(), document.line_index.range(&label.span)),
+                    message: label.text.clone(),
+                })
+                .chain(
+                    report
+                        .notes
+                        .iter()
+                        .map(|note| DiagnosticRelatedInformation {
+                            location: Location::new(
+                                uri.clone(),
+                                document.line_index.range(&report.span),
+                            ),
+                            message: note.clone(),
+                        }),
+                )
+                .collect::<Vec<_>>();
+            Diagnostic {
+                range: document.line_index.range(&report.span),
+                severity: Some(match report.severity {
+                    Severity::Error => DiagnosticSeverity::ERROR,
+                    Severity::Warning => DiagnosticSeverity::WARNING,
+                    Severity::Information => DiagnosticSeverity::INFORMATION,
+                    Severity::Hint => DiagnosticSeverity::HINT,
+                }),
+                code: Some(NumberOrString::String(report.code.into())),
+                source: Some("deskc".into()),
+                message: format!("{}: {}", title, report.text),
+                related_information: (!related_information.is_empty())
+                    .then_some(related_information),
+                // Suggestions are kept for code actions.
+                data: (!report.suggestions.is_empty())
+                    .then(|| serde_json::to_value(&report.suggestions).unwrap()),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
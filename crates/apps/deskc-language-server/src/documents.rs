@@ -0,0 +1,71 @@
+use std::{collections::HashMap, sync::Arc};
+
+use codebase::code::{Code, SyntaxKind};
+use deskc::card::{CardQueries, CardsCompiler};
+use ids::CardId;
+use lsp_types::Url;
+
+use crate::line_index::LineIndex;
+
+pub struct Document {
+    pub card_id: CardId,
+    pub line_index: LineIndex,
+}
+
+/// Open documents, each of them compiled as a card.
+#[derive(Default)]
+pub struct Documents {
+    pub compiler: CardsCompiler,
+    documents: HashMap<Url, Document>,
+}
+
+impl Documents {
+    pub fn open(&mut self, uri: Url, text: String) {
+        let card_id = CardId::new();
+        self.documents.insert(
+            uri.clone(),
+            Document {
+                card_id,
+                line_index: LineIndex::new(&text),
+            },
+        );
+        self.update_card_ids();
+        self.change(uri, text);
+    }
+
+    pub fn change(&mut self, uri: Url, text: String) {
+        if let Some(document) = self.documents.get_mut(&uri) {
+            document.line_index = LineIndex::new(&text);
+            self.compiler.set_code(
+                document.card_id.clone(),
+                Code::SourceCode {
+                    syntax: SyntaxKind::Hacker,
+                    source: Arc::new(text),
+                },
+            );
+        }
+    }
+
+    pub fn close(&mut self, uri: &Url) {
+        self.documents.remove(uri);
+        self.update_card_ids();
+    }
+
+    // Open documents import each other.
+    fn update_card_ids(&mut self) {
+        let card_ids = self
+            .documents
+            .values()
+            .map(|document| document.card_id.clone())
+            .collect();
+        self.compiler.set_card_ids(Arc::new(card_ids));
+    }
+
+    pub fn get(&self, uri: &Url) -> Option<&Document> {
+        self.documents.get(uri)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Url, &Document)> {
+        self.documents.iter()
+    }
+}
@@ -0,0 +1,25 @@
+use deskc::card::CardQueries;
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Url};
+
+use crate::{documents::Documents, nodes::exprs_at};
+
+pub fn hover(documents: &Documents, uri: &Url, position: Position) -> Option<Hover> {
+    let document = documents.get(uri)?;
+    let ast = documents.compiler.ast(document.card_id.clone()).ok()?;
+    let typeinfer = documents
+        .compiler
+        .typeinfer(document.card_id.clone())
+        .ok()?;
+    let offset = document.line_index.offset(position);
+    let (ty, span) = exprs_at(&ast, offset)
+        .into_iter()
+        .rev()
+        .find_map(|(id, span)| typeinfer.types.get(&id).map(|ty| (ty.clone(), span)))?;
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("```\n{}\n```", ty),
+        }),
+        range: Some(document.line_index.range(&span)),
+    })
+}
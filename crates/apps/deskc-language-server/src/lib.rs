@@ -0,0 +1,417 @@
+mod completion;
+mod definition;
+mod diagnostics;
+mod documents;
+mod hover;
+mod line_index;
+mod nodes;
+
+use anyhow::Result;
+use documents::Documents;
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as NotificationTrait, PublishDiagnostics,
+    },
+    request::{Completion, GotoDefinition, HoverRequest, Request as RequestTrait},
+    CompletionOptions, GotoDefinitionResponse, HoverProviderCapability, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    Url,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["'".into()]),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Runs the language server until the client requests shutdown.
+pub fn run(connection: Connection) -> Result<()> {
+    connection.initialize(serde_json::to_value(server_capabilities())?)?;
+    let mut server = Server::default();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                connection
+                    .sender
+                    .send(Message::Response(server.handle_request(request)))?;
+            }
+            Message::Notification(notification) => {
+                for notification in server.handle_notification(notification)? {
+                    connection
+                        .sender
+                        .send(Message::Notification(notification))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Server {
+    documents: Documents,
+}
+
+impl Server {
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => handle::<HoverRequest>(request, |params| {
+                let position = params.text_document_position_params;
+                hover::hover(
+                    &self.documents,
+                    &position.text_document.uri,
+                    position.position,
+                )
+            }),
+            GotoDefinition::METHOD => handle::<GotoDefinition>(request, |params| {
+                let position = params.text_document_position_params;
+                definition::definition(
+                    &self.documents,
+                    &position.text_document.uri,
+                    position.position,
+                )
+                .map(GotoDefinitionResponse::Scalar)
+            }),
+            Completion::METHOD => handle::<Completion>(request, |params| {
+                Some(
+                    completion::completion(
+                        &self.documents,
+                        &params.text_document_position.text_document.uri,
+                    )
+                    .into(),
+                )
+            }),
+            method => {
+                return Response::new_err(
+                    id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("unknown method {}", method),
+                )
+            }
+        };
+        match result {
+            Ok(result) => Response {
+                id,
+                result: Some(result),
+                error: None,
+            },
+            Err(err) => Response::new_err(
+                id,
+                lsp_server::ErrorCode::InvalidParams as i32,
+                err.to_string(),
+            ),
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<Vec<Notification>> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = params::<DidOpenTextDocument>(notification)?;
+                let uri = params.text_document.uri;
+                self.documents.open(uri.clone(), params.text_document.text);
+                uri
+            }
+            DidChangeTextDocument::METHOD => {
+                let mut params = params::<DidChangeTextDocument>(notification)?;
+                let uri = params.text_document.uri;
+                // Only full sync is supported.
+                if let Some(change) = params.content_changes.pop() {
+                    self.documents.change(uri.clone(), change.text);
+                }
+                uri
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = params::<DidCloseTextDocument>(notification)?;
+                self.documents.close(&params.text_document.uri);
+                return Ok(vec![publish_diagnostics(params.text_document.uri, vec![])]);
+            }
+            _ => return Ok(vec![]),
+        };
+        let diagnostics = diagnostics::diagnostics(&self.documents, &uri);
+        Ok(vec![publish_diagnostics(uri, diagnostics)])
+    }
+}
+
+fn handle<R: RequestTrait>(
+    request: Request,
+    f: impl FnOnce(R::Params) -> R::Result,
+) -> Result<serde_json::Value>
+where
+    R::Params: DeserializeOwned,
+    R::Result: Serialize,
+{
+    let params = serde_json::from_value(request.params)?;
+    Ok(serde_json::to_value(f(params))?)
+}
+
+fn params<N: NotificationTrait>(notification: Notification) -> Result<N::Params>
+where
+    N::Params: DeserializeOwned,
+{
+    Ok(serde_json::from_value(notification.params)?)
+}
+
+fn publish_diagnostics(uri: Url, diagnostics: Vec<lsp_types::Diagnostic>) -> Notification {
+    Notification::new(
+        PublishDiagnostics::METHOD.into(),
+        PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::JoinHandle;
+
+    use lsp_server::RequestId;
+    use lsp_types::{
+        CompletionResponse, DiagnosticSeverity, DidOpenTextDocumentParams, GotoDefinitionParams,
+        Hover, HoverContents, HoverParams, InitializeParams, NumberOrString, Position,
+        TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams,
+    };
+
+    use super::*;
+
+    struct TestClient {
+        connection: Connection,
+        server: Option<JoinHandle<Result<()>>>,
+        next_id: i32,
+    }
+
+    impl TestClient {
+        fn start() -> Self {
+            let (server, connection) = Connection::memory();
+            let server = std::thread::spawn(move || run(server));
+            let mut client = Self {
+                connection,
+                server: Some(server),
+                next_id: 0,
+            };
+            client.request::<lsp_types::request::Initialize>(InitializeParams::default());
+            client.notify::<lsp_types::notification::Initialized>(lsp_types::InitializedParams {});
+            client
+        }
+
+        fn request<R: RequestTrait>(&mut self, params: R::Params) -> R::Result {
+            self.next_id += 1;
+            let id = RequestId::from(self.next_id);
+            self.connection
+                .sender
+                .send(Message::Request(Request::new(
+                    id.clone(),
+                    R::METHOD.into(),
+                    params,
+                )))
+                .unwrap();
+            loop {
+                if let Message::Response(response) = self.connection.receiver.recv().unwrap() {
+                    assert_eq!(response.id, id);
+                    return serde_json::from_value(response.result.unwrap()).unwrap();
+                }
+            }
+        }
+
+        fn notify<N: NotificationTrait>(&self, params: N::Params) {
+            self.connection
+                .sender
+                .send(Message::Notification(Notification::new(
+                    N::METHOD.into(),
+                    params,
+                )))
+                .unwrap();
+        }
+
+        fn open(&self, uri: &Url, text: &str) -> PublishDiagnosticsParams {
+            self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem::new(uri.clone(), "desk".into(), 0, text.into()),
+            });
+            match self.connection.receiver.recv().unwrap() {
+                Message::Notification(notification) => {
+                    assert_eq!(notification.method, PublishDiagnostics::METHOD);
+                    serde_json::from_value(notification.params).unwrap()
+                }
+                message => panic!("unexpected message {:?}", message),
+            }
+        }
+
+        fn position(uri: &Url, line: u32, character: u32) -> TextDocumentPositionParams {
+            TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(uri.clone()),
+                Position::new(line, character),
+            )
+        }
+
+        fn shutdown(mut self) {
+            self.request::<lsp_types::request::Shutdown>(());
+            self.notify::<lsp_types::notification::Exit>(());
+            self.server.take().unwrap().join().unwrap().unwrap();
+        }
+    }
+
+    fn uri(name: &str) -> Url {
+        Url::parse(&format!("file:///{}", name)).unwrap()
+    }
+
+    #[test]
+    fn publishes_no_diagnostics_for_valid_code() {
+        let client = TestClient::start();
+        let diagnostics = client.open(&uri("a.desk"), "1");
+        assert_eq!(diagnostics.diagnostics, vec![]);
+        client.shutdown();
+    }
+
+    #[test]
+    fn publishes_parser_diagnostics() {
+        let client = TestClient::start();
+        let diagnostics = client.open(&uri("a.desk"), "$ 1");
+        assert_eq!(diagnostics.diagnostics.len(), 1);
+        assert!(diagnostics.diagnostics[0]
+            .message
+            .starts_with("Parser error"));
+        client.shutdown();
+    }
+
+    #[test]
+    fn publishes_typeinfer_diagnostics() {
+        let client = TestClient::start();
+        let diagnostics = client.open(&uri("a.desk"), "> \\ 'number -> 'number ~ \"a\"");
+        assert_eq!(diagnostics.diagnostics.len(), 1);
+        assert!(diagnostics.diagnostics[0]
+            .message
+            .starts_with("Typeinfer error"));
+        assert_eq!(
+            diagnostics.diagnostics[0].code,
+            Some(NumberOrString::String("E0402".into()))
+        );
+        client.shutdown();
+    }
+
+    #[test]
+    fn publishes_all_typeinfer_diagnostics() {
+        let client = TestClient::start();
+        let diagnostics = client.open(
+            &uri("a.desk"),
+            "* > \\ 'number -> 'number ~ \"a\", > \\ 'number -> 'number ~ \"b\"",
+        );
+        assert_eq!(diagnostics.diagnostics.len(), 2);
+        client.shutdown();
+    }
+
+    #[test]
+    fn publishes_holes() {
+        let client = TestClient::start();
+        let diagnostics = client.open(&uri("a.desk"), "^ ?: 'number");
+        assert_eq!(diagnostics.diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics.diagnostics[0].severity,
+            Some(DiagnosticSeverity::INFORMATION)
+        );
+        assert_eq!(
+            diagnostics.diagnostics[0].message,
+            "Typed holes: expected 'number"
+        );
+        client.shutdown();
+    }
+
+    #[test]
+    fn hovers_inferred_type() {
+        let mut client = TestClient::start();
+        let uri = uri("a.desk");
+        client.open(&uri, "$ 1 ~\n\"a\"");
+        let hover = client
+            .request::<HoverRequest>(HoverParams {
+                text_document_position_params: TestClient::position(&uri, 1, 1),
+                work_done_progress_params: Default::default(),
+            })
+            .unwrap();
+        assert_eq!(
+            hover,
+            Hover {
+                contents: HoverContents::Markup(lsp_types::MarkupContent {
+                    kind: lsp_types::MarkupKind::Markdown,
+                    value: "```\n'string\n```".into(),
+                }),
+                range: Some(lsp_types::Range::new(
+                    Position::new(1, 0),
+                    Position::new(1, 3)
+                )),
+            }
+        );
+        client.shutdown();
+    }
+
+    #[test]
+    fn goes_to_card_definition() {
+        let mut client = TestClient::start();
+        let uuid = "a5c1d4a2-0e3f-4c4a-9b7e-4f3a7b6d1e20";
+        let definition = uri("definition.desk");
+        let reference = uri("reference.desk");
+        client.open(&definition, &format!("'card 'uuid {} 1", uuid));
+        client.open(
+            &reference,
+            &format!("> \\ 'number -> 'number 'card 'uuid {} ~ 1", uuid),
+        );
+        let location = client.request::<GotoDefinition>(GotoDefinitionParams {
+            text_document_position_params: TestClient::position(&reference, 0, 2),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        assert_eq!(
+            location,
+            Some(GotoDefinitionResponse::Scalar(lsp_types::Location::new(
+                definition,
+                lsp_types::Range::new(Position::new(0, 0), Position::new(0, 50))
+            )))
+        );
+        client.shutdown();
+    }
+
+    #[test]
+    fn completes_type_aliases() {
+        let mut client = TestClient::start();
+        let uri = uri("a.desk");
+        client.open(
+            &uri,
+            "'type point * 'number, 'number ~\n'type name 'string ~\n1",
+        );
+        let completion = client.request::<Completion>(lsp_types::CompletionParams {
+            text_document_position: TestClient::position(&uri, 2, 0),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        });
+        let items: Vec<_> = match completion.unwrap() {
+            CompletionResponse::Array(items) => items
+                .into_iter()
+                .map(|item| (item.label, item.detail.unwrap()))
+                .collect(),
+            CompletionResponse::List(_) => panic!(),
+        };
+        assert_eq!(
+            items,
+            vec![
+                ("name".to_string(), "'string".to_string()),
+                ("point".to_string(), "* 'number, 'number".to_string())
+            ]
+        );
+        client.shutdown();
+    }
+}
@@ -0,0 +1,85 @@
+use std::ops::Range;
+
+use lsp_types::Position;
+
+/// Converts char offsets used by spans into LSP positions (UTF-16 based) and vice versa.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+    lines: Vec<Vec<char>>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        Self {
+            lines: text
+                .split('\n')
+                .map(|line| line.chars().collect())
+                .collect(),
+        }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let mut rest = offset;
+        for (line, chars) in self.lines.iter().enumerate() {
+            if rest <= chars.len() {
+                return Position::new(line as u32, utf16_len(&chars[..rest]));
+            }
+            // +1 for the newline
+            rest -= chars.len() + 1;
+        }
+        let last = self.lines.len() - 1;
+        Position::new(last as u32, utf16_len(&self.lines[last]))
+    }
+
+    pub fn range(&self, span: &Range<usize>) -> lsp_types::Range {
+        lsp_types::Range::new(self.position(span.start), self.position(span.end))
+    }
+
+    pub fn offset(&self, position: Position) -> usize {
+        let line = (position.line as usize).min(self.lines.len() - 1);
+        let preceding: usize = self.lines[..line].iter().map(|chars| chars.len() + 1).sum();
+        let mut column = 0;
+        let mut utf16_column = 0;
+        for c in &self.lines[line] {
+            if utf16_column >= position.character {
+                break;
+            }
+            utf16_column += c.len_utf16() as u32;
+            column += 1;
+        }
+        preceding + column
+    }
+}
+
+fn utf16_len(chars: &[char]) -> u32 {
+    chars.iter().map(|c| c.len_utf16() as u32).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_offset_to_position() {
+        let index = LineIndex::new("ab\ncd\n");
+        assert_eq!(index.position(0), Position::new(0, 0));
+        assert_eq!(index.position(2), Position::new(0, 2));
+        assert_eq!(index.position(3), Position::new(1, 0));
+        assert_eq!(index.position(6), Position::new(2, 0));
+        assert_eq!(index.position(100), Position::new(2, 0));
+    }
+
+    #[test]
+    fn converts_position_to_offset() {
+        let index = LineIndex::new("ab\ncd\n");
+        assert_eq!(index.offset(Position::new(0, 1)), 1);
+        assert_eq!(index.offset(Position::new(1, 1)), 4);
+    }
+
+    #[test]
+    fn counts_utf16() {
+        let index = LineIndex::new("\"𝄞\" 1");
+        assert_eq!(index.position(3), Position::new(0, 4));
+        assert_eq!(index.offset(Position::new(0, 4)), 3);
+    }
+}
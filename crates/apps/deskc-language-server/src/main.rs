@@ -1,3 +1,8 @@
-fn main() {
-    println!("Hello, world!");
+use lsp_server::Connection;
+
+fn main() -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    deskc_language_server::run(connection)?;
+    io_threads.join()?;
+    Ok(())
 }
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use ast::{
+    expr::Expr,
+    span::{Span, WithSpan},
+    visitor::ExprVisitorMut,
+};
+use ids::{LinkName, NodeId};
+use uuid::Uuid;
+
+/// Expressions whose span contains the offset, from outermost to innermost.
+pub fn exprs_at(ast: &WithSpan<Expr>, offset: usize) -> Vec<(NodeId, Span)> {
+    let mut finder = ExprsAt {
+        offset,
+        found: vec![],
+    };
+    finder.visit_expr(&mut ast.clone());
+    finder.found
+}
+
+/// `'card` definitions in the AST.
+pub fn cards(ast: &WithSpan<Expr>) -> HashMap<Uuid, Span> {
+    let mut collector = Cards::default();
+    collector.visit_expr(&mut ast.clone());
+    collector.cards
+}
+
+/// The innermost application linked to a card at the offset.
+pub fn card_reference_at(ast: &WithSpan<Expr>, offset: usize) -> Option<Uuid> {
+    let mut finder = CardReferenceAt {
+        offset,
+        found: None,
+    };
+    finder.visit_expr(&mut ast.clone());
+    finder.found
+}
+
+struct ExprsAt {
+    offset: usize,
+    found: Vec<(NodeId, Span)>,
+}
+
+impl ExprVisitorMut for ExprsAt {
+    fn visit_expr(&mut self, expr: &mut WithSpan<Expr>) {
+        if expr.span.contains(&self.offset) {
+            self.found.push((expr.id.clone(), expr.span.clone()));
+        }
+        self.super_visit_expr(expr);
+    }
+}
+
+#[derive(Default)]
+struct Cards {
+    cards: HashMap<Uuid, Span>,
+}
+
+impl ExprVisitorMut for Cards {
+    fn visit_expr(&mut self, expr: &mut WithSpan<Expr>) {
+        if let Expr::Card { uuid, .. } = &expr.value {
+            self.cards.insert(*uuid, expr.span.clone());
+        }
+        self.super_visit_expr(expr);
+    }
+}
+
+struct CardReferenceAt {
+    offset: usize,
+    found: Option<Uuid>,
+}
+
+impl ExprVisitorMut for CardReferenceAt {
+    fn visit_expr(&mut self, expr: &mut WithSpan<Expr>) {
+        if let Expr::Apply {
+            link_name: LinkName::Card(uuid),
+            ..
+        } = &expr.value
+        {
+            if expr.span.contains(&self.offset) {
+                self.found = Some(*uuid);
+            }
+        }
+        self.super_visit_expr(expr);
+    }
+}
@@ -8,7 +8,9 @@ pub enum Token {
     Ident(String),
     Int(i64),
     Str(String),
-    // TODO: Float(i64, i64),
+    Rational(i64, i64),
+    // Kept as written because f64 is not `Eq`, but it's always finite.
+    Float(String),
     Uuid(Uuid),
     Divide,
     Let,
@@ -40,6 +42,7 @@ pub enum Token {
     Import,
     Export,
     Brands,
+    Unbrand,
     Type,
     NumberType,
     StringType,
@@ -67,7 +67,7 @@ pub enum Expr {
     Set(Vec<WithSpan<Self>>),
     Import {
         ty: WithSpan<Type>,
-        uuid: Option<Uuid>,
+        link_name: LinkName,
     },
     Export {
         ty: WithSpan<Type>,
@@ -84,6 +84,9 @@ pub enum Expr {
         label: String,
         item: Box<WithSpan<Self>>,
     },
+    Unbrand {
+        item: Box<WithSpan<Self>>,
+    },
     NewType {
         ident: String,
         ty: WithSpan<Type>,
@@ -93,6 +93,9 @@ pub fn remove_span(WithSpan { id, span, value }: &mut WithSpan<Expr>) {
         Expr::Label { label: _, item } => {
             remove_span(item);
         }
+        Expr::Unbrand { item } => {
+            remove_span(item);
+        }
         Expr::NewType { ident: _, ty, expr } => {
             remove_span_ty(ty);
             remove_span(expr);
@@ -124,6 +127,9 @@ pub fn remove_span_ty(WithSpan { id, span, value }: &mut WithSpan<Type>) {
         Type::Brand { brand: _, item } => {
             remove_span_ty(item);
         }
+        Type::Brands { brands: _, item } => {
+            remove_span_ty(item);
+        }
         Type::Number => {}
         Type::String => {}
         Type::Trait(types) => {
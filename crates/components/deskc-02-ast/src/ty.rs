@@ -12,6 +12,10 @@ pub enum Type {
         brand: String,
         item: Box<WithSpan<Type>>,
     },
+    Brands {
+        brands: Vec<String>,
+        item: Box<WithSpan<Type>>,
+    },
     Number,
     String,
     Trait(Vec<WithSpan<Self>>),
@@ -34,11 +34,12 @@ pub trait ExprVisitorMut {
             Expr::Function { parameters, body } => self.visit_function(parameters, body),
             Expr::Vector(exprs) => self.visit_vector(exprs),
             Expr::Set(exprs) => self.visit_set(exprs),
-            Expr::Import { ty, uuid } => self.visit_import(ty, uuid),
+            Expr::Import { ty, link_name } => self.visit_import(ty, link_name),
             Expr::Export { ty } => self.visit_export(ty),
             Expr::Attribute { attr, item } => self.visit_attribute(attr, item),
             Expr::Brand { brands, item } => self.visit_brand(brands, item),
             Expr::Label { label, item } => self.visit_label(label, item),
+            Expr::Unbrand { item } => self.visit_unbrand(item),
             Expr::NewType { ident, ty, expr } => self.visit_new_type(ident, ty, expr),
             Expr::Comment {
                 position,
@@ -127,7 +128,7 @@ pub trait ExprVisitorMut {
             self.visit_expr(expr);
         }
     }
-    fn visit_import(&mut self, ty: &mut WithSpan<Type>, _uuid: &mut Option<Uuid>) {
+    fn visit_import(&mut self, ty: &mut WithSpan<Type>, _link_name: &mut LinkName) {
         self.visit_type(ty);
     }
     fn visit_export(&mut self, ty: &mut WithSpan<Type>) {
@@ -143,6 +144,9 @@ pub trait ExprVisitorMut {
     fn visit_label(&mut self, _label: &mut String, item: &mut WithSpan<Expr>) {
         self.visit_expr(item);
     }
+    fn visit_unbrand(&mut self, item: &mut WithSpan<Expr>) {
+        self.visit_expr(item);
+    }
     fn visit_new_type(
         &mut self,
         _ident: &mut String,
@@ -181,6 +185,7 @@ pub trait TypeVisitorMut {
     fn super_visit_type(&mut self, ty: &mut WithSpan<Type>) {
         match &mut ty.value {
             Type::Brand { brand, item } => self.visit_brand(brand, item),
+            Type::Brands { brands, item } => self.visit_brands(brands, item),
             Type::Number => self.visit_number(),
             Type::String => self.visit_string(),
             Type::Trait(types) => self.visit_trait(types),
@@ -208,6 +213,9 @@ pub trait TypeVisitorMut {
     fn visit_brand(&mut self, _brand: &mut String, item: &mut WithSpan<Type>) {
         self.visit_type(item);
     }
+    fn visit_brands(&mut self, _brands: &mut Vec<String>, item: &mut WithSpan<Type>) {
+        self.visit_type(item);
+    }
     fn visit_number(&mut self) {}
     fn visit_string(&mut self) {}
     fn visit_trait(&mut self, types: &mut Vec<WithSpan<Type>>) {
@@ -243,7 +251,7 @@ pub trait TypeVisitorMut {
     fn visit_set(&mut self, ty: &mut WithSpan<Type>) {
         self.visit_type(ty);
     }
-    fn visit_import(&mut self, ty: &mut WithSpan<Type>, _uuid: &mut Option<Uuid>) {
+    fn visit_import(&mut self, ty: &mut WithSpan<Type>, _link_name: &mut LinkName) {
         self.visit_type(ty);
     }
     fn visit_export(&mut self, ty: &mut WithSpan<Type>) {
@@ -0,0 +1,99 @@
+use std::fmt::{Display, Formatter};
+
+use ast::span::{dummy_span, WithSpan};
+
+use crate::{
+    meta::WithMeta,
+    ty::{Effect, EffectExpr, Type},
+};
+
+// Types are printed in Desk syntax by converting them to AST.
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", deskc_fmt::format_type(&to_ast(self)))
+    }
+}
+
+fn to_ast(ty: &Type) -> WithSpan<ast::ty::Type> {
+    use ast::ty::Type as Ast;
+    let boxed = |ty: &WithMeta<Type>| Box::new(to_ast(&ty.value));
+    let list = |types: &[WithMeta<Type>]| types.iter().map(|ty| to_ast(&ty.value)).collect();
+    dummy_span(match ty {
+        Type::Number => Ast::Number,
+        Type::String => Ast::String,
+        Type::Trait(types) => Ast::Trait(list(types)),
+        Type::Effectful { ty, effects } => Ast::Effectful {
+            ty: boxed(ty),
+            effects: effect_expr_to_ast(&effects.value),
+        },
+        Type::Infer => Ast::Infer,
+        Type::This => Ast::This,
+        Type::Product(types) => Ast::Product(list(types)),
+        Type::Sum(types) => Ast::Sum(list(types)),
+        Type::Function { parameters, body } => Ast::Function {
+            parameters: list(parameters),
+            body: boxed(body),
+        },
+        Type::Vector(item) => Ast::Vector(boxed(item)),
+        Type::Set(item) => Ast::Set(boxed(item)),
+        Type::Let { variable, body } => Ast::Let {
+            variable: variable.clone(),
+            body: boxed(body),
+        },
+        Type::Variable(ident) => Ast::Variable(ident.clone()),
+        Type::BoundedVariable { bound, identifier } => Ast::BoundedVariable {
+            bound: boxed(bound),
+            identifier: identifier.clone(),
+        },
+        // Brands are declared in place to distinguish them from labels.
+        Type::Brand { brand, item } => Ast::Brands {
+            brands: vec![brand.clone()],
+            item: Box::new(dummy_span(Ast::Brand {
+                brand: brand.clone(),
+                item: boxed(item),
+            })),
+        },
+        Type::Label { label, item } => Ast::Brand {
+            brand: label.clone(),
+            item: boxed(item),
+        },
+    })
+}
+
+fn effect_expr_to_ast(expr: &EffectExpr) -> WithSpan<ast::ty::EffectExpr> {
+    use ast::ty::EffectExpr as Ast;
+    dummy_span(match expr {
+        EffectExpr::Effects(effects) => Ast::Effects(
+            effects
+                .iter()
+                .map(|effect| {
+                    let Effect { input, output } = &effect.value;
+                    dummy_span(ast::ty::Effect {
+                        input: to_ast(&input.value),
+                        output: to_ast(&output.value),
+                    })
+                })
+                .collect(),
+        ),
+        EffectExpr::Add(exprs) => Ast::Add(
+            exprs
+                .iter()
+                .map(|expr| effect_expr_to_ast(&expr.value))
+                .collect(),
+        ),
+        EffectExpr::Sub {
+            minuend,
+            subtrahend,
+        } => Ast::Sub {
+            minuend: Box::new(effect_expr_to_ast(&minuend.value)),
+            subtrahend: Box::new(effect_expr_to_ast(&subtrahend.value)),
+        },
+        EffectExpr::Apply {
+            function,
+            arguments,
+        } => Ast::Apply {
+            function: Box::new(to_ast(&function.value)),
+            arguments: arguments.iter().map(|ty| to_ast(&ty.value)).collect(),
+        },
+    })
+}
@@ -1,8 +1,9 @@
 pub use ids::LinkName;
 
 use crate::{meta::WithMeta, ty::Type};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Literal {
     String(String),
     Integer(i64),
@@ -14,14 +15,14 @@ pub enum Literal {
 // Literal::Float should not be NaN
 impl Eq for Literal {}
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Handler {
     pub input: WithMeta<Type>,
     pub output: WithMeta<Type>,
     pub handler: WithMeta<Expr>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Expr {
     Literal(Literal),
     Let {
@@ -69,9 +70,12 @@ pub enum Expr {
         brand: String,
         item: Box<WithMeta<Self>>,
     },
+    Unbrand {
+        item: Box<WithMeta<Self>>,
+    },
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MatchCase {
     pub ty: WithMeta<Type>,
     pub expr: WithMeta<Expr>,
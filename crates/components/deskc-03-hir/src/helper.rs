@@ -1,14 +1,18 @@
+use std::{collections::HashMap, ops::Range};
+
+use ids::{LinkName, NodeId};
+
 use crate::{
     expr::{Expr, Handler, MatchCase},
     meta::{dummy_meta, WithMeta},
     ty::Type,
 };
 
-fn remove_meta_ty(ty: WithMeta<Type>) -> WithMeta<Type> {
+pub fn remove_meta_ty(ty: WithMeta<Type>) -> WithMeta<Type> {
     let value = match ty.value {
         Type::Number => ty.value,
         Type::String => ty.value,
-        Type::Trait(_) => todo!(),
+        Type::Trait(types) => Type::Trait(types.into_iter().map(remove_meta_ty).collect()),
         Type::Effectful { ty, effects } => Type::Effectful {
             ty: Box::new(remove_meta_ty(*ty)),
             effects,
@@ -118,8 +122,72 @@ pub fn remove_meta(expr: WithMeta<Expr>) -> WithMeta<Expr> {
             brand,
             item: Box::new(remove_meta(*body)),
         },
+        Expr::Unbrand { item } => Expr::Unbrand {
+            item: Box::new(remove_meta(*item)),
+        },
     };
     let mut with_meta = dummy_meta(value);
     with_meta.meta.attrs = expr.meta.attrs;
     with_meta
 }
+
+/// Direct subexpressions in the order of appearance.
+pub fn children(expr: &WithMeta<Expr>) -> Vec<&WithMeta<Expr>> {
+    match &expr.value {
+        Expr::Literal(_) => vec![],
+        Expr::Let {
+            definition,
+            expression,
+            ..
+        } => vec![definition, expression],
+        Expr::Perform { input, .. } => vec![input],
+        Expr::Continue { input, .. } => vec![input],
+        Expr::Handle { handlers, expr } => std::iter::once(expr.as_ref())
+            .chain(handlers.iter().map(|handler| &handler.handler))
+            .collect(),
+        Expr::Apply { arguments, .. } => arguments.iter().collect(),
+        Expr::Product(exprs) | Expr::Vector(exprs) | Expr::Set(exprs) => exprs.iter().collect(),
+        Expr::Match { of, cases } => std::iter::once(of.as_ref())
+            .chain(cases.iter().map(|case| &case.expr))
+            .collect(),
+        Expr::Typed { item, .. }
+        | Expr::Label { item, .. }
+        | Expr::Brand { item, .. }
+        | Expr::Unbrand { item } => vec![item],
+        Expr::Function { body, .. } => vec![body],
+    }
+}
+
+/// Spans of the first reference to each card.
+pub fn link_spans(expr: &WithMeta<Expr>) -> HashMap<LinkName, Range<usize>> {
+    fn collect(expr: &WithMeta<Expr>, spans: &mut HashMap<LinkName, Range<usize>>) {
+        if let (Expr::Apply { link_name, .. }, Some(span)) = (&expr.value, &expr.meta.span) {
+            if *link_name != LinkName::None {
+                spans
+                    .entry(link_name.clone())
+                    .or_insert_with(|| span.clone());
+            }
+        }
+        for child in children(expr) {
+            collect(child, spans);
+        }
+    }
+    let mut spans = HashMap::new();
+    collect(expr, &mut spans);
+    spans
+}
+
+/// Spans of the expressions by their node ids.
+pub fn spans(expr: &WithMeta<Expr>) -> HashMap<NodeId, Range<usize>> {
+    fn collect(expr: &WithMeta<Expr>, spans: &mut HashMap<NodeId, Range<usize>>) {
+        if let Some(span) = &expr.meta.span {
+            spans.insert(expr.id.clone(), span.clone());
+        }
+        for child in children(expr) {
+            collect(child, spans);
+        }
+    }
+    let mut spans = HashMap::new();
+    collect(expr, &mut spans);
+    spans
+}
@@ -2,6 +2,7 @@ use expr::Expr;
 use ids::CardId;
 use meta::WithMeta;
 
+mod display;
 pub mod expr;
 pub mod helper;
 pub mod meta;
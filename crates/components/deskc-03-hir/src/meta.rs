@@ -1,17 +1,18 @@
 use std::ops::Range;
 
 use ids::NodeId;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::expr::Expr;
 
-#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Meta {
     pub attrs: Vec<Expr>,
     pub span: Option<Range<usize>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WithMeta<T> {
     pub id: NodeId,
     pub meta: Meta,
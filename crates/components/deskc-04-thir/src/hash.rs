@@ -0,0 +1,124 @@
+use ids::NodeId;
+use types::Type;
+use uuid::Uuid;
+
+use crate::{
+    visitor::{TypedHirVisitor, TypedHirVisitorMut},
+    TypedHir,
+};
+
+/// The namespace of the UUIDs made from card hashes.
+pub const CARD_HASH_NAMESPACE: Uuid = Uuid::from_u128(0x8d6b_8e9a_4f57_4b0c_9a2e_2f1e_0c5d_6a11);
+
+pub struct RemoveIdVisitor;
+
+impl TypedHirVisitorMut for RemoveIdVisitor {
+    fn visit(&mut self, hir: &mut TypedHir) {
+        hir.id = NodeId::default();
+        self.super_visit(hir);
+    }
+}
+
+/// Removes everything that depends on how the card was written, so that the same code has the same THIR.
+pub fn normalize(mut thir: TypedHir) -> TypedHir {
+    RemoveIdVisitor.visit(&mut thir);
+    thir
+}
+
+/// Returns the content address of a card, which is used as `LinkName::Version`.
+///
+/// The hash doesn't change when someone re-creates a card with the same code.
+pub fn card_hash(thir: &TypedHir) -> Uuid {
+    let normalized = normalize(thir.clone());
+    // bincode is deterministic for THIR because it has no maps.
+    let bytes = bincode::serialize(&normalized).expect("THIR must be serializable");
+    Uuid::new_v5(&CARD_HASH_NAMESPACE, &bytes)
+}
+
+#[derive(Default)]
+struct TypesVisitor {
+    types: Vec<Type>,
+}
+
+impl TypedHirVisitor for TypesVisitor {
+    fn visit(&mut self, hir: &TypedHir) {
+        self.types.push(hir.ty.clone());
+        self.super_visit(hir);
+    }
+    fn visit_type(&mut self, ty: &Type) {
+        self.types.push(ty.clone());
+    }
+}
+
+/// Hashes the types in a card with FNV-1a, so that a compiled card can be checked against the types
+/// a consumer expects.
+///
+/// `DefaultHasher` is not used because it may differ between builds.
+pub fn types_hash(thir: &TypedHir) -> u64 {
+    let mut visitor = TypesVisitor::default();
+    visitor.visit(thir);
+    // Types don't contain maps, so the encoding is deterministic.
+    let bytes = bincode::serialize(&visitor.types).expect("types must be serializable");
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Expr, Literal};
+
+    use super::*;
+
+    fn int(value: i64) -> TypedHir {
+        TypedHir {
+            id: NodeId::new(),
+            ty: Type::Number,
+            expr: Expr::Literal(Literal::Int(value)),
+        }
+    }
+
+    fn product(items: Vec<TypedHir>) -> TypedHir {
+        TypedHir {
+            id: NodeId::new(),
+            ty: Type::Product(vec![Type::Number; items.len()]),
+            expr: Expr::Product(items),
+        }
+    }
+
+    #[test]
+    fn ignores_node_ids() {
+        assert_eq!(
+            card_hash(&product(vec![int(1), int(2)])),
+            card_hash(&product(vec![int(1), int(2)]))
+        );
+    }
+
+    #[test]
+    fn differs_by_content() {
+        assert_ne!(
+            card_hash(&product(vec![int(1), int(2)])),
+            card_hash(&product(vec![int(2), int(1)]))
+        );
+    }
+
+    #[test]
+    fn types_hash_depends_on_types() {
+        assert_eq!(
+            types_hash(&product(vec![int(1), int(2)])),
+            types_hash(&product(vec![int(2), int(1)]))
+        );
+        assert_ne!(
+            types_hash(&product(vec![int(1), int(2)])),
+            types_hash(&product(vec![int(1)]))
+        );
+    }
+
+    #[test]
+    fn is_stable() {
+        assert_eq!(
+            card_hash(&int(1)),
+            "302fd98f-52aa-5ecc-91a7-247c92963b72".parse().unwrap()
+        );
+    }
+}
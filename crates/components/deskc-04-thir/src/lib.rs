@@ -1,12 +1,16 @@
+pub mod hash;
 pub mod visitor;
 
+use std::hash::{Hash, Hasher};
+
 pub use ids::LinkName;
 use ids::NodeId;
+use serde::{Deserialize, Serialize};
 use types::{Effect, Type};
 
 pub type Id = usize;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Literal {
     String(String),
     Int(i64),
@@ -17,26 +21,38 @@ pub enum Literal {
 // Literal::Float should not be NaN
 impl Eq for Literal {}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl Hash for Literal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        match self {
+            Literal::String(string) => string.hash(state),
+            Literal::Int(int) => int.hash(state),
+            Literal::Float(float) => float.to_bits().hash(state),
+            Literal::Rational(a, b) => (a, b).hash(state),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct MatchCase {
     pub ty: Type,
     pub expr: TypedHir,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TypedHir {
     pub id: NodeId,
     pub ty: Type,
     pub expr: Expr,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Handler {
     pub effect: Effect,
     pub handler: TypedHir,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Expr {
     Literal(Literal),
     Match {
@@ -48,6 +64,8 @@ pub enum Expr {
         body: Box<TypedHir>,
     },
     Perform(Box<TypedHir>),
+    /// Resumes the continuation captured by the nearest handler with the input.
+    Continue(Box<TypedHir>),
     Handle {
         handlers: Vec<Handler>,
         expr: Box<TypedHir>,
@@ -72,9 +90,11 @@ pub enum Expr {
         label: String,
         item: Box<TypedHir>,
     },
+    /// Makes a trait object from a value of the type that implements the trait.
+    TraitObject(Box<TypedHir>),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BuiltinOp {
     Add,
     Sub,
@@ -88,6 +108,8 @@ pub enum BuiltinOp {
     Le,
     Gt,
     Ge,
+    /// Returns `@greater *`, `@less *` or `@equal *`.
+    Cmp,
     And,
     Or,
     Not,
@@ -98,4 +120,14 @@ pub enum BuiltinOp {
     BitNot,
     Shl,
     Shr,
+    Length,
+    Index,
+    Push,
+    /// Lowered to a loop in MIR because it applies a closure to each item.
+    Map,
+    /// Lowered to a loop in MIR because it applies a closure to each item.
+    Fold,
+    Member,
+    Union,
+    Intersection,
 }
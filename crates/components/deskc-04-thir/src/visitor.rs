@@ -2,11 +2,15 @@ use crate::TypedHir;
 
 pub trait TypedHirVisitor {
     fn visit(&mut self, hir: &TypedHir) {
+        self.super_visit(hir);
+    }
+    fn super_visit(&mut self, hir: &TypedHir) {
         match &hir.expr {
             crate::Expr::Literal(literal) => self.visit_literal(literal),
             crate::Expr::Match { input, cases } => self.visit_match(input, cases),
             crate::Expr::Let { definition, body } => self.visit_let(definition, body),
             crate::Expr::Perform(perform) => self.visit_perform(perform),
+            crate::Expr::Continue(input) => self.visit_continue(input),
             crate::Expr::Handle { handlers, expr } => self.visit_handle(handlers, expr),
             crate::Expr::Op { op, operands } => self.visit_op(op, operands),
             crate::Expr::Apply {
@@ -19,6 +23,7 @@ pub trait TypedHirVisitor {
             crate::Expr::Vector(exprs) => self.visit_vector(exprs),
             crate::Expr::Set(exprs) => self.visit_set(exprs),
             crate::Expr::Label { label, item } => self.visit_label(label, item),
+            crate::Expr::TraitObject(value) => self.visit_trait_object(value),
         }
     }
 
@@ -36,6 +41,9 @@ pub trait TypedHirVisitor {
     fn visit_perform(&mut self, perform: &crate::TypedHir) {
         self.visit(perform);
     }
+    fn visit_continue(&mut self, input: &crate::TypedHir) {
+        self.visit(input);
+    }
     fn visit_handle(&mut self, handlers: &[crate::Handler], expr: &crate::TypedHir) {
         for handler in handlers {
             self.visit_handler(&handler.effect, &handler.handler);
@@ -95,6 +103,9 @@ pub trait TypedHirVisitor {
     fn visit_label(&mut self, _label: &str, item: &crate::TypedHir) {
         self.visit(item);
     }
+    fn visit_trait_object(&mut self, value: &crate::TypedHir) {
+        self.visit(value);
+    }
 }
 
 pub trait TypedHirVisitorMut {
@@ -107,6 +118,7 @@ pub trait TypedHirVisitorMut {
             crate::Expr::Match { input, cases } => self.visit_match(input, cases),
             crate::Expr::Let { definition, body } => self.visit_let(definition, body),
             crate::Expr::Perform(perform) => self.visit_perform(perform),
+            crate::Expr::Continue(input) => self.visit_continue(input),
             crate::Expr::Handle { handlers, expr } => self.visit_handle(handlers, expr),
             crate::Expr::Op { op, operands } => self.visit_op(op, operands),
             crate::Expr::Apply {
@@ -119,6 +131,7 @@ pub trait TypedHirVisitorMut {
             crate::Expr::Vector(exprs) => self.visit_vector(exprs),
             crate::Expr::Set(exprs) => self.visit_set(exprs),
             crate::Expr::Label { label, item } => self.visit_label(label, item),
+            crate::Expr::TraitObject(value) => self.visit_trait_object(value),
         }
     }
 
@@ -136,6 +149,9 @@ pub trait TypedHirVisitorMut {
     fn visit_perform(&mut self, perform: &mut crate::TypedHir) {
         self.visit(perform);
     }
+    fn visit_continue(&mut self, input: &mut crate::TypedHir) {
+        self.visit(input);
+    }
     fn visit_handle(&mut self, handlers: &mut [crate::Handler], expr: &mut crate::TypedHir) {
         for handler in handlers {
             self.visit_handler(&mut handler.effect, &mut handler.handler);
@@ -195,4 +211,7 @@ pub trait TypedHirVisitorMut {
     fn visit_label(&mut self, _label: &mut str, item: &mut crate::TypedHir) {
         self.visit(item);
     }
+    fn visit_trait_object(&mut self, value: &mut crate::TypedHir) {
+        self.visit(value);
+    }
 }
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::mir::Mir;
+
+/// Bumped when the layout of MIR is changed.
+pub const ARTIFACT_VERSION: u32 = 2;
+
+const MAGIC: [u8; 4] = *b"DSKC";
+
+/// A compiled card which can be shipped without recompiling.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub version: u32,
+    /// A hash of the types of the source card, such as `thir::hash::types_hash`.
+    pub types_hash: u64,
+    pub mir: Mir,
+}
+
+#[derive(Debug, Error)]
+pub enum ArtifactError {
+    #[error("not a desk artifact")]
+    InvalidMagic,
+    #[error("unsupported artifact version {0}, expected {ARTIFACT_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("types hash mismatch: expected {expected:x}, actual {actual:x}")]
+    TypesHashMismatch { expected: u64, actual: u64 },
+    #[error(transparent)]
+    Encoding(#[from] bincode::Error),
+}
+
+impl Artifact {
+    pub fn new(mir: Mir, types_hash: u64) -> Self {
+        Self {
+            version: ARTIFACT_VERSION,
+            types_hash,
+            mir,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ArtifactError> {
+        let mut bytes = MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ArtifactError> {
+        let body = bytes
+            .strip_prefix(&MAGIC)
+            .ok_or(ArtifactError::InvalidMagic)?;
+        // The version is the first field, so it can be read before the MIR of other versions.
+        let version: u32 = bincode::deserialize(body)?;
+        if version != ARTIFACT_VERSION {
+            return Err(ArtifactError::UnsupportedVersion(version));
+        }
+        Ok(bincode::deserialize(body)?)
+    }
+
+    /// Rejects an artifact compiled from a card whose types differ from the expected ones.
+    pub fn check_types_hash(&self, expected: u64) -> Result<(), ArtifactError> {
+        if self.types_hash != expected {
+            return Err(ArtifactError::TypesHashMismatch {
+                expected,
+                actual: self.types_hash,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::Type;
+
+    use crate::{
+        block::BasicBlock,
+        mir::{ControlFlowGraph, ControlFlowGraphId},
+        scope::{Scope, ScopeId},
+        stmt::{Const, Stmt, StmtBind, Terminator},
+        var::{Var, VarId, Vars},
+    };
+
+    use super::*;
+
+    fn mir(output: Type) -> Mir {
+        Mir {
+            entrypoint: ControlFlowGraphId(0),
+            cfgs: vec![ControlFlowGraph {
+                parameters: vec![],
+                captured: vec![],
+                output: output.clone(),
+                vars: Vars(vec![Var {
+                    ty: output,
+                    scope: ScopeId(0),
+                }]),
+                scopes: vec![Scope { super_scope: None }],
+                blocks: vec![BasicBlock {
+                    stmts: vec![StmtBind {
+                        var: VarId(0),
+                        stmt: Stmt::Const(Const::Int(1)),
+                    }],
+                    terminator: Terminator::Return(VarId(0)),
+                }],
+                links: vec![],
+            }],
+            links: Default::default(),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let artifact = Artifact::new(mir(Type::Number), 0);
+        let bytes = artifact.to_bytes().unwrap();
+        assert_eq!(Artifact::from_bytes(&bytes).unwrap(), artifact);
+    }
+
+    #[test]
+    fn rejects_other_types() {
+        let artifact = Artifact::new(mir(Type::Number), 1);
+        assert!(artifact.check_types_hash(1).is_ok());
+        assert!(matches!(
+            artifact.check_types_hash(2),
+            Err(ArtifactError::TypesHashMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut artifact = Artifact::new(mir(Type::Number), 0);
+        artifact.version = ARTIFACT_VERSION + 1;
+        let bytes = artifact.to_bytes().unwrap();
+        assert!(matches!(
+            Artifact::from_bytes(&bytes),
+            Err(ArtifactError::UnsupportedVersion(version)) if version == ARTIFACT_VERSION + 1
+        ));
+        assert!(matches!(
+            Artifact::from_bytes(b"desk"),
+            Err(ArtifactError::InvalidMagic)
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod artifact;
 pub mod block;
 pub mod mir;
 pub mod scope;
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use types::Type;
 
@@ -20,10 +22,12 @@ pub struct ControlFlowGraph {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ControlFlowGraphId(pub usize);
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Mir {
     pub entrypoint: ControlFlowGraphId,
     pub cfgs: Vec<ControlFlowGraph>,
+    /// Entrypoints of the linked cards, which is empty before linking.
+    pub links: HashMap<LinkId, ControlFlowGraphId>,
 }
 
 impl ControlFlowGraph {
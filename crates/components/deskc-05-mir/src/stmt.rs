@@ -20,6 +20,9 @@ pub enum Stmt {
     Set(Vec<VarId>),
     Fn(FnRef),
     Perform(VarId),
+    /// Resumes the continuation captured by the nearest handler, and binds the result of the handled expression.
+    Continue(VarId),
+    /// Binds the value of the variable as is, such as the result of a case of a match.
     MatchResult(VarId),
     Apply {
         function: VarId,
@@ -35,11 +38,22 @@ pub enum Stmt {
     Parameter,
     Recursion,
     Link(LinkName),
+    /// Makes a trait object with the implementations of the functions in the trait.
+    TraitObject {
+        value: VarId,
+        methods: HashMap<Type, VarId>,
+    },
+    /// Applies the implementation of the method of the trait object.
+    Dispatch {
+        object: VarId,
+        method: Type,
+        arguments: Vec<VarId>,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FnRef {
-    Link(Type),
+    Link(LinkId),
     Closure {
         mir: ControlFlowGraphId,
         /// Caputerd variables
@@ -75,6 +89,7 @@ pub enum Op {
     Le,
     Gt,
     Ge,
+    Cmp,
     Not,
     Neg,
     Pos,
@@ -84,6 +99,12 @@ pub enum Op {
     BitOr,
     BitXor,
     BitNot,
+    Length,
+    Index,
+    Push,
+    Member,
+    Union,
+    Intersection,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -14,6 +16,17 @@ impl Default for LinkName {
     }
 }
 
+// Printed as the pins of imports.
+impl Display for LinkName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkName::None => write!(f, "(none)"),
+            LinkName::Version(uuid) => write!(f, "'uuid {uuid}"),
+            LinkName::Card(uuid) => write!(f, "'card 'uuid {uuid}"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Hash, Eq, Serialize, Deserialize)]
 pub struct LinkId<Type> {
     pub ty: Type,
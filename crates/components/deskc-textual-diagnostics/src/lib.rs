@@ -1,11 +1,155 @@
 use std::ops::Range;
 
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TextualDiagnostics {
     pub title: String,
     pub reports: Vec<Report>,
 }
 
+impl TextualDiagnostics {
+    /// Machine-readable rendering for tools like CI bots and the language server.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("diagnostics are always serializable")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Report {
+    /// A stable code like `E0401`, which is not changed even if the text is changed.
+    ///
+    /// The first two digits are the compiler stage which reports it, and `00` is for the others.
+    pub code: &'static str,
+    pub severity: Severity,
+    pub text: String,
+    pub span: Range<usize>,
+    /// Other spans which are related to the report.
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Label {
+    pub span: Range<usize>,
+    pub text: String,
+}
+
+/// A fix which replaces the span with the replacement.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Suggestion {
     pub text: String,
     pub span: Range<usize>,
+    pub replacement: String,
+}
+
+impl Report {
+    pub fn error(code: &'static str, span: Range<usize>, text: impl Into<String>) -> Self {
+        Self::new(code, Severity::Error, span, text)
+    }
+
+    pub fn new(
+        code: &'static str,
+        severity: Severity,
+        span: Range<usize>,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            code,
+            severity,
+            text: text.into(),
+            span,
+            labels: vec![],
+            notes: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    pub fn with_label(mut self, span: Range<usize>, text: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            text: text.into(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_suggestion(
+        mut self,
+        text: impl Into<String>,
+        span: Range<usize>,
+        replacement: impl Into<String>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            text: text.into(),
+            span,
+            replacement: replacement.into(),
+        });
+        self
+    }
+}
+
+/// Text of a syntax error, which is shared by the lexers and the parsers.
+pub fn unexpected_text(found: Option<String>, mut expected: Vec<String>) -> String {
+    let found = found.unwrap_or_else(|| "end of input".into());
+    // Expected inputs are not ordered by parsers.
+    expected.sort();
+    match expected.len() {
+        0 => format!("unexpected {}", found),
+        1 => format!("unexpected {}, expected {}", found, expected[0]),
+        _ => format!(
+            "unexpected {}, expected one of {}",
+            found,
+            expected.join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_json() {
+        let diagnostics = TextualDiagnostics {
+            title: "Import error".into(),
+            reports: vec![Report::error("E0313", 0..7, "ambiguous import")
+                .with_label(10..12, "exported here")
+                .with_note("pin the card")
+                .with_suggestion("pin the first card", 7..7, " 'uuid a")],
+        };
+        assert_eq!(
+            diagnostics.to_json(),
+            concat!(
+                r#"{"title":"Import error","reports":[{"code":"E0313","severity":"error","#,
+                r#""text":"ambiguous import","span":{"start":0,"end":7},"#,
+                r#""labels":[{"span":{"start":10,"end":12},"text":"exported here"}],"#,
+                r#""notes":["pin the card"],"#,
+                r#""suggestions":[{"text":"pin the first card","span":{"start":7,"end":7},"replacement":" 'uuid a"}]}]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn unexpected() {
+        assert_eq!(
+            unexpected_text(Some("'a'".into()), vec!["'b'".into(), "'c'".into()]),
+            "unexpected 'a', expected one of 'b', 'c'"
+        );
+        assert_eq!(unexpected_text(None, vec![]), "unexpected end of input");
+    }
 }
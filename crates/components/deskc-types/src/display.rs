@@ -0,0 +1,117 @@
+use std::fmt::{Display, Formatter};
+
+use ast::span::{dummy_span, WithSpan};
+
+use crate::{Effect, EffectExpr, Type};
+
+// Types are printed in Desk syntax by converting them to AST.
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", deskc_fmt::format_type(&to_ast(self)))
+    }
+}
+
+impl Display for Effect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} => {}", self.input, self.output)
+    }
+}
+
+fn to_ast(ty: &Type) -> WithSpan<ast::ty::Type> {
+    use ast::ty::Type as Ast;
+    let boxed = |ty: &Type| Box::new(to_ast(ty));
+    dummy_span(match ty {
+        Type::Number => Ast::Number,
+        Type::String => Ast::String,
+        Type::Product(types) => Ast::Product(types.iter().map(to_ast).collect()),
+        Type::Sum(types) => Ast::Sum(types.iter().map(to_ast).collect()),
+        Type::Function { parameters, body } => Ast::Function {
+            parameters: parameters.iter().map(to_ast).collect(),
+            body: boxed(body),
+        },
+        Type::Vector(item) => Ast::Vector(boxed(item)),
+        Type::Set(item) => Ast::Set(boxed(item)),
+        Type::Variable(ident) => Ast::Variable(ident.clone()),
+        Type::ForAll { variable, body } => Ast::Let {
+            variable: variable.clone(),
+            body: boxed(body),
+        },
+        Type::Effectful { ty, effects } => Ast::Effectful {
+            ty: boxed(ty),
+            effects: effect_expr_to_ast(effects),
+        },
+        // Brands are declared in place to distinguish them from labels.
+        Type::Brand { brand, item } => Ast::Brands {
+            brands: vec![brand.clone()],
+            item: Box::new(dummy_span(Ast::Brand {
+                brand: brand.clone(),
+                item: boxed(item),
+            })),
+        },
+        Type::Label { label, item } => Ast::Brand {
+            brand: label.clone(),
+            item: boxed(item),
+        },
+        Type::Trait(types) => Ast::Trait(types.iter().map(to_ast).collect()),
+        Type::This => Ast::This,
+        Type::Error => Ast::Infer,
+    })
+}
+
+fn effect_expr_to_ast(expr: &EffectExpr) -> WithSpan<ast::ty::EffectExpr> {
+    use ast::ty::EffectExpr as Ast;
+    dummy_span(match expr {
+        EffectExpr::Effects(effects) => Ast::Effects(
+            effects
+                .iter()
+                .map(|Effect { input, output }| {
+                    dummy_span(ast::ty::Effect {
+                        input: to_ast(input),
+                        output: to_ast(output),
+                    })
+                })
+                .collect(),
+        ),
+        EffectExpr::Add(exprs) => Ast::Add(exprs.iter().map(effect_expr_to_ast).collect()),
+        EffectExpr::Sub {
+            minuend,
+            subtrahend,
+        } => Ast::Sub {
+            minuend: Box::new(effect_expr_to_ast(minuend)),
+            subtrahend: Box::new(effect_expr_to_ast(subtrahend)),
+        },
+        EffectExpr::Apply {
+            function,
+            arguments,
+        } => Ast::Apply {
+            function: Box::new(to_ast(function)),
+            arguments: arguments.iter().map(to_ast).collect(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displays_in_desk_syntax() {
+        let ty = Type::Function {
+            parameters: vec![Type::Number, Type::Variable("a".into())],
+            body: Box::new(Type::Effectful {
+                ty: Box::new(Type::label(
+                    "sum",
+                    Type::Product(vec![Type::String, Type::Vector(Box::new(Type::Number))]),
+                )),
+                effects: EffectExpr::Effects(vec![Effect {
+                    input: Type::String,
+                    output: Type::Number,
+                }]),
+            }),
+        };
+        assert_eq!(
+            ty.to_string(),
+            r"\ 'number, a -> ! @sum * 'string, ['number] {'string => 'number}"
+        );
+    }
+}
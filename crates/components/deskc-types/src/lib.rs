@@ -1,3 +1,5 @@
+mod display;
+
 use std::collections::HashMap;
 
 use ids::NodeId;
@@ -40,6 +42,11 @@ pub enum Type {
         label: String,
         item: Box<Self>,
     },
+    /// A set of function signatures in which `This` is a type that implements the trait.
+    Trait(Vec<Self>),
+    This,
+    /// Only appears in the result of the error recovering type inference.
+    Error,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
@@ -80,6 +87,11 @@ impl Type {
             Type::Sum(types)
         }
     }
+    pub fn trait_(mut types: Vec<Self>) -> Self {
+        types.sort();
+        types.dedup();
+        Type::Trait(types)
+    }
     pub fn function(mut parameters: Vec<Self>, body: Self) -> Self {
         parameters.sort();
         Type::Function {
@@ -87,17 +99,214 @@ impl Type {
             body: Box::new(body),
         }
     }
+    /// Replaces `This` with the given type.
+    ///
+    /// A nested trait has its own `This`, so it's not replaced.
+    pub fn replace_this(&self, this: &Self) -> Self {
+        let replace = |ty: &Self| ty.replace_this(this);
+        match self {
+            Type::This => this.clone(),
+            Type::Number | Type::String | Type::Variable(_) | Type::Trait(_) | Type::Error => {
+                self.clone()
+            }
+            Type::Product(types) => Type::product(types.iter().map(replace).collect()),
+            Type::Sum(types) => Type::sum(types.iter().map(replace).collect()),
+            Type::Function { parameters, body } => {
+                Type::function(parameters.iter().map(replace).collect(), replace(body))
+            }
+            Type::Vector(ty) => Type::Vector(Box::new(replace(ty))),
+            Type::Set(ty) => Type::Set(Box::new(replace(ty))),
+            Type::ForAll { variable, body } => Type::ForAll {
+                variable: variable.clone(),
+                body: Box::new(replace(body)),
+            },
+            Type::Effectful { ty, effects } => Type::Effectful {
+                ty: Box::new(replace(ty)),
+                effects: effects.replace_this(this),
+            },
+            Type::Brand { brand, item } => Type::Brand {
+                brand: brand.clone(),
+                item: Box::new(replace(item)),
+            },
+            Type::Label { label, item } => Type::label(label.clone(), replace(item)),
+        }
+    }
+    /// Removes the quantifiers.
+    ///
+    /// A polymorphic function is typed with the same variables as its quantified type, so variables
+    /// found by the unquantified types are the same.
+    pub fn without_quantifiers(&self) -> Self {
+        let strip = |ty: &Self| ty.without_quantifiers();
+        match self {
+            Type::ForAll { variable: _, body } => strip(body),
+            Type::Number | Type::String | Type::This | Type::Error | Type::Variable(_) => {
+                self.clone()
+            }
+            Type::Product(types) => Type::Product(types.iter().map(strip).collect()),
+            Type::Sum(types) => Type::Sum(types.iter().map(strip).collect()),
+            Type::Trait(types) => Type::Trait(types.iter().map(strip).collect()),
+            Type::Function { parameters, body } => Type::Function {
+                parameters: parameters.iter().map(strip).collect(),
+                body: Box::new(strip(body)),
+            },
+            Type::Vector(ty) => Type::Vector(Box::new(strip(ty))),
+            Type::Set(ty) => Type::Set(Box::new(strip(ty))),
+            Type::Effectful { ty, effects } => Type::Effectful {
+                ty: Box::new(strip(ty)),
+                effects: effects.without_quantifiers(),
+            },
+            Type::Brand { brand, item } => Type::Brand {
+                brand: brand.clone(),
+                item: Box::new(strip(item)),
+            },
+            Type::Label { label, item } => Type::Label {
+                label: label.clone(),
+                item: Box::new(strip(item)),
+            },
+        }
+    }
+    /// Returns true if a type variable appears in the type.
+    pub fn has_variable(&self) -> bool {
+        match self {
+            Type::Variable(_) => true,
+            Type::Number | Type::String | Type::This | Type::Error => false,
+            Type::Product(types) | Type::Sum(types) | Type::Trait(types) => {
+                types.iter().any(Type::has_variable)
+            }
+            Type::Function { parameters, body } => {
+                parameters.iter().any(Type::has_variable) || body.has_variable()
+            }
+            Type::Vector(ty)
+            | Type::Set(ty)
+            | Type::ForAll { body: ty, .. }
+            | Type::Effectful { ty, .. }
+            | Type::Brand { item: ty, .. }
+            | Type::Label { item: ty, .. } => ty.has_variable(),
+        }
+    }
+}
+
+impl EffectExpr {
+    fn without_quantifiers(&self) -> Self {
+        match self {
+            EffectExpr::Effects(effects) => EffectExpr::Effects(
+                effects
+                    .iter()
+                    .map(|Effect { input, output }| Effect {
+                        input: input.without_quantifiers(),
+                        output: output.without_quantifiers(),
+                    })
+                    .collect(),
+            ),
+            EffectExpr::Add(exprs) => {
+                EffectExpr::Add(exprs.iter().map(EffectExpr::without_quantifiers).collect())
+            }
+            EffectExpr::Sub {
+                minuend,
+                subtrahend,
+            } => EffectExpr::Sub {
+                minuend: Box::new(minuend.without_quantifiers()),
+                subtrahend: Box::new(subtrahend.without_quantifiers()),
+            },
+            EffectExpr::Apply {
+                function,
+                arguments,
+            } => EffectExpr::Apply {
+                function: Box::new(function.without_quantifiers()),
+                arguments: arguments.iter().map(Type::without_quantifiers).collect(),
+            },
+        }
+    }
+    fn replace_this(&self, this: &Type) -> Self {
+        match self {
+            EffectExpr::Effects(effects) => EffectExpr::Effects(
+                effects
+                    .iter()
+                    .map(|Effect { input, output }| Effect {
+                        input: input.replace_this(this),
+                        output: output.replace_this(this),
+                    })
+                    .collect(),
+            ),
+            EffectExpr::Add(exprs) => {
+                EffectExpr::Add(exprs.iter().map(|expr| expr.replace_this(this)).collect())
+            }
+            EffectExpr::Sub {
+                minuend,
+                subtrahend,
+            } => EffectExpr::Sub {
+                minuend: Box::new(minuend.replace_this(this)),
+                subtrahend: Box::new(subtrahend.replace_this(this)),
+            },
+            EffectExpr::Apply {
+                function,
+                arguments,
+            } => EffectExpr::Apply {
+                function: Box::new(function.replace_this(this)),
+                arguments: arguments.iter().map(|ty| ty.replace_this(this)).collect(),
+            },
+        }
+    }
+}
+
+impl Effect {
+    /// The effect performed to halt the process with the reason.
+    ///
+    /// The output is the empty sum because it never continues, so runtimes handle it with a halt.
+    pub fn halt(reason: Type) -> Self {
+        Effect {
+            input: Type::label("halt", reason),
+            output: Type::Sum(vec![]),
+        }
+    }
+
+    /// The halt effect performed when a hole is evaluated.
+    pub fn hole() -> Self {
+        Effect::halt(Type::label("hole", Type::unit()))
+    }
+
+    /// The effect performed with the dividend when a number is divided by zero.
+    pub fn division_by_zero() -> Self {
+        Effect {
+            input: Type::label("division by zero", Type::Number),
+            output: Type::Number,
+        }
+    }
+
+    /// The effect performed with the approximated result when an integer overflows.
+    pub fn overflow() -> Self {
+        Effect {
+            input: Type::label("overflow", Type::Number),
+            output: Type::Number,
+        }
+    }
+
+    /// The effect performed with the index when it's out of bounds of a vector.
+    pub fn index_out_of_bounds(item: Type) -> Self {
+        Effect {
+            input: Type::label("index out of bounds", Type::Number),
+            output: item,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct Types {
     pub types: HashMap<NodeId, Type>,
+    /// Parameter types to which arguments are applied, keyed by the arguments.
+    ///
+    /// Parameters of a flattened function type are sorted, so this is the only way to know which
+    /// type variable an argument is passed to.
+    pub parameters: HashMap<NodeId, Type>,
 }
 
 impl Types {
     pub fn get(&self, id: &NodeId) -> Option<&Type> {
         self.types.get(id)
     }
+    pub fn get_parameter(&self, argument: &NodeId) -> Option<&Type> {
+        self.parameters.get(argument)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use types::Type;
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 /// Sendable value between processes.
 ///
 /// Closure is not sendable in the following reasons:
@@ -20,12 +20,47 @@ pub enum Value {
         value: Box<Value>,
     },
     Vector(Vec<Self>),
+    /// Items are unique and in the order of insertion.
+    Set(Vec<Self>),
     TraitObject {
         ty: Type,
         value: Box<Value>,
     },
 }
 
+// Sets are equal regardless of the order of insertion.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Unit, Value::Unit) => true,
+            (Value::Number(lhs), Value::Number(rhs)) => lhs == rhs,
+            (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
+            (Value::Product(lhs), Value::Product(rhs)) => lhs == rhs,
+            (
+                Value::Variant { ty, value },
+                Value::Variant {
+                    ty: ty2,
+                    value: value2,
+                },
+            ) => ty == ty2 && value == value2,
+            (Value::Vector(lhs), Value::Vector(rhs)) => lhs == rhs,
+            (Value::Set(lhs), Value::Set(rhs)) => {
+                lhs.len() == rhs.len() && lhs.iter().all(|value| rhs.contains(value))
+            }
+            (
+                Value::TraitObject { ty, value },
+                Value::TraitObject {
+                    ty: ty2,
+                    value: value2,
+                },
+            ) => ty == ty2 && value == value2,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Number {
     Integer(i64),
@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
 use ast::{expr::Expr, span::WithSpan};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 /// A unit of code in a codebase.
 ///
@@ -15,10 +17,14 @@ pub enum Code {
 }
 
 // Some syntax are not supported yet.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum SyntaxKind {
     Hacker,
     TypeScriptLike,
     OCamlLike,
     RustLike,
 }
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{0:?} syntax is not supported yet")]
+pub struct UnsupportedSyntax(pub SyntaxKind);
@@ -1,9 +1,10 @@
 use deskc_ids::LinkName;
+use serde::{Deserialize, Serialize};
 use types::Type;
 
 use crate::code::SyntaxKind;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Content {
     SourceCode { syntax: SyntaxKind, source: String },
     String(String),
@@ -16,7 +17,7 @@ pub enum Content {
 // Content::Float should not be NaN
 impl Eq for Content {}
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ContentKind {
     SourceCode,
     String,
@@ -5,17 +5,18 @@ use crate::{
     user::UserId,
 };
 use deskc_ids::NodeId;
+use serde::{Deserialize, Serialize};
 
 use crate::snapshot::Snapshot;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EventEntry {
     pub index: usize,
     pub user_id: UserId,
     pub event: Event,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Event {
     AddOwner {
         user_id: UserId,
@@ -54,8 +55,8 @@ pub enum Event {
         rules: Rules<NodeOperation>,
     },
     AddSnapshot {
-        /// current index of events
-        /// if the index of this event is not index+1, this event must be
+        /// The index of the last event which the snapshot includes.
+        /// If it is not the last event handled, this event must be rejected.
         index: usize,
         snapshot: Box<Snapshot>,
     },
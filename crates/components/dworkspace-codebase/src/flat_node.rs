@@ -2,18 +2,19 @@ use std::collections::HashMap;
 
 use deskc_ids::NodeId;
 use hir::expr::Expr;
+use serde::{Deserialize, Serialize};
 use types::Type;
 
 use crate::{
     content::Content,
-    patch::{AttributePatch, ContentPatch, OperandPatch},
+    patch::{AttributePatch, ContentPatch, OperandPatch, PatchError},
     rules::{NodeOperation, Rules},
 };
 
 pub type Operands = Vec<NodeId>;
 pub type Attributes = HashMap<Type, Expr>;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FlatNode {
     pub content: Content,
     pub operands: Operands,
@@ -33,11 +34,57 @@ impl FlatNode {
         }
     }
 
-    pub fn patch_content(&mut self, patch: &ContentPatch) {
-        match patch {
-            ContentPatch::Replace(content) => self.content = content.clone(),
-            _ => todo!(),
-        }
+    /// Applies the patch, or leaves the content unchanged if the patch does not apply.
+    pub fn patch_content(&mut self, patch: &ContentPatch) -> Result<(), PatchError> {
+        let content = match (patch, &self.content) {
+            (ContentPatch::Replace(content), _) => content.clone(),
+            // The source is already converted by the issuer of the patch.
+            (
+                ContentPatch::ChangeSourceCodeSyntax { syntax, source },
+                Content::SourceCode { .. },
+            ) => Content::SourceCode {
+                syntax: syntax.clone(),
+                source: source.clone(),
+            },
+            (ContentPatch::PatchSourceCode(patch), Content::SourceCode { syntax, source }) => {
+                Content::SourceCode {
+                    syntax: syntax.clone(),
+                    source: patch.apply(source)?,
+                }
+            }
+            (ContentPatch::PatchString(patch), Content::String(string)) => {
+                Content::String(patch.apply(string)?)
+            }
+            (ContentPatch::UpdateInteger(integer), Content::Integer(_)) => {
+                Content::Integer(to_integer(*integer)?)
+            }
+            (ContentPatch::UpdateFloat(float), Content::Float(_)) => {
+                if !float.is_finite() {
+                    return Err(PatchError::NonFiniteFloat);
+                }
+                Content::Float(*float)
+            }
+            (ContentPatch::UpdateRational(numerator, denominator), Content::Rational(_, _)) => {
+                if *denominator == 0 {
+                    return Err(PatchError::ZeroDenominator);
+                }
+                Content::Rational(to_integer(*numerator)?, to_integer(*denominator)?)
+            }
+            (ContentPatch::UpdateApply { ty, link_name }, Content::Apply { .. }) => {
+                Content::Apply {
+                    ty: ty.clone(),
+                    link_name: link_name.clone(),
+                }
+            }
+            (patch, content) => {
+                return Err(PatchError::ContentKindMismatch {
+                    expected: patch.content_kind().unwrap(),
+                    actual: content.kind(),
+                })
+            }
+        };
+        self.content = content;
+        Ok(())
     }
 
     pub fn patch_attribute(&mut self, patch: &AttributePatch) {
@@ -88,12 +135,126 @@ impl FlatNode {
     }
 }
 
+fn to_integer(value: u64) -> Result<i64, PatchError> {
+    i64::try_from(value).map_err(|_| PatchError::OutOfRange(value))
+}
+
 #[cfg(test)]
 mod tests {
     use hir::expr::Literal;
 
+    use crate::{
+        code::SyntaxKind,
+        content::ContentKind,
+        patch::{diff_match_patch::DiffMatchPatchError, StringPatch},
+    };
+
     use super::*;
 
+    #[test]
+    fn change_source_code_syntax() {
+        let mut flat_node = FlatNode::new(Content::SourceCode {
+            syntax: SyntaxKind::Hacker,
+            source: "&'number".into(),
+        });
+        flat_node
+            .patch_content(&ContentPatch::ChangeSourceCodeSyntax {
+                syntax: SyntaxKind::RustLike,
+                source: "&number".into(),
+            })
+            .unwrap();
+        assert_eq!(
+            flat_node.content,
+            Content::SourceCode {
+                syntax: SyntaxKind::RustLike,
+                source: "&number".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn patch_source_code() {
+        let mut flat_node = FlatNode::new(Content::SourceCode {
+            syntax: SyntaxKind::Hacker,
+            source: "* 1, 2".into(),
+        });
+        // Another user edited the source after the patch was made.
+        let patch = StringPatch::diff("* 1, 2", "* 1, 3");
+        flat_node
+            .patch_content(&ContentPatch::PatchSourceCode(StringPatch::diff(
+                "* 1, 2", "* 0, 2",
+            )))
+            .unwrap();
+        flat_node
+            .patch_content(&ContentPatch::PatchSourceCode(patch))
+            .unwrap();
+        assert_eq!(
+            flat_node.content,
+            Content::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: "* 0, 3".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn patch_string_fails() {
+        let mut flat_node = FlatNode::new(Content::String("hello world".into()));
+        let patch = StringPatch::diff(
+            "The quick brown fox jumps over the lazy dog.",
+            "That quick brown fox jumped over a lazy dog.",
+        );
+        assert_eq!(
+            flat_node.patch_content(&ContentPatch::PatchString(patch)),
+            Err(PatchError::DiffMatchPatch(
+                DiffMatchPatchError::NotApplied { index: 0 }
+            ))
+        );
+        assert_eq!(flat_node.content, Content::String("hello world".into()));
+    }
+
+    #[test]
+    fn update_numbers() {
+        let mut flat_node = FlatNode::new(Content::Integer(1));
+        flat_node
+            .patch_content(&ContentPatch::UpdateInteger(2))
+            .unwrap();
+        assert_eq!(flat_node.content, Content::Integer(2));
+        assert_eq!(
+            flat_node.patch_content(&ContentPatch::UpdateInteger(u64::MAX)),
+            Err(PatchError::OutOfRange(u64::MAX))
+        );
+
+        let mut flat_node = FlatNode::new(Content::Rational(1, 2));
+        flat_node
+            .patch_content(&ContentPatch::UpdateRational(3, 4))
+            .unwrap();
+        assert_eq!(flat_node.content, Content::Rational(3, 4));
+        assert_eq!(
+            flat_node.patch_content(&ContentPatch::UpdateRational(1, 0)),
+            Err(PatchError::ZeroDenominator)
+        );
+
+        let mut flat_node = FlatNode::new(Content::Float(1.0));
+        assert_eq!(
+            flat_node.patch_content(&ContentPatch::UpdateFloat(f64::NAN)),
+            Err(PatchError::NonFiniteFloat)
+        );
+        assert_eq!(flat_node.content, Content::Float(1.0));
+    }
+
+    #[test]
+    fn content_kind_mismatch() {
+        let mut flat_node = FlatNode::new(Content::String("a".into()));
+        assert_eq!(
+            flat_node.patch_content(&ContentPatch::UpdateInteger(1)),
+            Err(PatchError::ContentKindMismatch {
+                expected: ContentKind::Integer,
+                actual: ContentKind::String,
+            })
+        );
+    }
+
     #[test]
     fn update() {
         let mut flat_node = FlatNode::new(Content::String("a".into()));
@@ -1,17 +1,26 @@
-#[derive(Debug, Clone, PartialEq, Eq)]
+//! A port of the diff, match and patch algorithms of google/diff-match-patch.
+//!
+//! Offsets and lengths are in chars.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Operation {
     Delete,
     Insert,
     Equal,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StringDiff {
     pub operation: Operation,
     pub text: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Patch {
     pub diffs: Vec<StringDiff>,
     pub start1: i32,
@@ -19,3 +28,777 @@ pub struct Patch {
     pub length1: i32,
     pub length2: i32,
 }
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DiffMatchPatchError {
+    /// The context of the patch is not found near the expected location.
+    #[error("patch {index} does not apply")]
+    NotApplied { index: usize },
+}
+
+/// How far a match may be from the expected location, in chars.
+const MATCH_DISTANCE: f64 = 1000.0;
+/// 0.0 is a perfect match, and 1.0 matches anything.
+const MATCH_THRESHOLD: f64 = 0.5;
+/// The bitap algorithm matches patterns up to this length.
+const MATCH_MAX_BITS: usize = 32;
+/// How much of a long patch's context may differ from the text, relative to its length.
+const PATCH_DELETE_THRESHOLD: f64 = 0.5;
+/// Chars of context around a change.
+const PATCH_MARGIN: usize = 4;
+
+type Diff = (Operation, Vec<char>);
+
+/// Diffs which turn the text1 into the text2.
+pub fn diff(text1: &str, text2: &str) -> Vec<StringDiff> {
+    let text1: Vec<_> = text1.chars().collect();
+    let text2: Vec<_> = text2.chars().collect();
+    diff_main(&text1, &text2)
+        .into_iter()
+        .map(|(operation, text)| StringDiff {
+            operation,
+            text: text.into_iter().collect(),
+        })
+        .collect()
+}
+
+/// Patches which turn the text1 into the text2, for example before and after an edit.
+pub fn make_patches(text1: &str, text2: &str) -> Vec<Patch> {
+    let text1: Vec<_> = text1.chars().collect();
+    let text2: Vec<_> = text2.chars().collect();
+    let diffs = diff_main(&text1, &text2);
+    patch_make(&text1, &diffs)
+        .into_iter()
+        .map(Patch::from)
+        .collect()
+}
+
+/// Applies the patches to the text, which may be changed from the text the patches are made from.
+///
+/// Fails if any of the patches does not apply, instead of applying only some of them.
+pub fn apply_patches(patches: &[Patch], text: &str) -> Result<String, DiffMatchPatchError> {
+    if patches.is_empty() {
+        return Ok(text.into());
+    }
+    let mut patches: Vec<_> = patches.iter().map(CharPatch::from).collect();
+    let padding = add_padding(&mut patches);
+    let mut text: Vec<char> = padding
+        .iter()
+        .cloned()
+        .chain(text.chars())
+        .chain(padding.iter().cloned())
+        .collect();
+
+    // The difference between the expected and actual locations of the previous patch.
+    let mut delta: isize = 0;
+    for (index, patch) in patches.iter().enumerate() {
+        let expected_loc = (patch.start2 as isize + delta).max(0) as usize;
+        let text1 = diff_text1(&patch.diffs);
+        let mut end_loc = None;
+        let start_loc = if text1.len() > MATCH_MAX_BITS {
+            // Long patterns are matched by their start and end.
+            match_main(&text, &text1[..MATCH_MAX_BITS], expected_loc).and_then(|start_loc| {
+                let end = match_main(
+                    &text,
+                    &text1[text1.len() - MATCH_MAX_BITS..],
+                    expected_loc + text1.len() - MATCH_MAX_BITS,
+                )?;
+                end_loc = Some(end);
+                (start_loc < end).then_some(start_loc)
+            })
+        } else {
+            match_main(&text, &text1, expected_loc)
+        };
+        let start_loc = start_loc.ok_or(DiffMatchPatchError::NotApplied { index })?;
+        delta = start_loc as isize - expected_loc as isize;
+        let end = match end_loc {
+            Some(end_loc) => end_loc + MATCH_MAX_BITS,
+            None => start_loc + text1.len(),
+        }
+        .min(text.len());
+        let text2 = text[start_loc..end].to_vec();
+        if text1 == text2 {
+            text.splice(start_loc..start_loc + text1.len(), diff_text2(&patch.diffs));
+            continue;
+        }
+        // The context is matched fuzzily, so the changes are mapped onto the actual text.
+        let diffs = diff_main(&text1, &text2);
+        if text1.len() > MATCH_MAX_BITS
+            && levenshtein(&diffs) as f64 / text1.len() as f64 > PATCH_DELETE_THRESHOLD
+        {
+            return Err(DiffMatchPatchError::NotApplied { index });
+        }
+        let mut index1 = 0;
+        for (operation, chars) in &patch.diffs {
+            let index2 = x_index(&diffs, index1);
+            match operation {
+                Operation::Insert => {
+                    let at = start_loc + index2;
+                    text.splice(at..at, chars.iter().cloned());
+                }
+                Operation::Delete => {
+                    let end = start_loc + x_index(&diffs, index1 + chars.len());
+                    text.drain(start_loc + index2..end);
+                }
+                Operation::Equal => {}
+            }
+            if *operation != Operation::Delete {
+                index1 += chars.len();
+            }
+        }
+    }
+    Ok(text[padding.len()..text.len() - padding.len()]
+        .iter()
+        .collect())
+}
+
+#[derive(Debug, Clone, Default)]
+struct CharPatch {
+    diffs: Vec<Diff>,
+    start1: usize,
+    start2: usize,
+    length1: usize,
+    length2: usize,
+}
+
+impl From<&Patch> for CharPatch {
+    fn from(patch: &Patch) -> Self {
+        CharPatch {
+            diffs: patch
+                .diffs
+                .iter()
+                .map(|diff| (diff.operation.clone(), diff.text.chars().collect()))
+                .collect(),
+            start1: patch.start1.max(0) as usize,
+            start2: patch.start2.max(0) as usize,
+            length1: patch.length1.max(0) as usize,
+            length2: patch.length2.max(0) as usize,
+        }
+    }
+}
+
+impl From<CharPatch> for Patch {
+    fn from(patch: CharPatch) -> Self {
+        Patch {
+            diffs: patch
+                .diffs
+                .into_iter()
+                .map(|(operation, text)| StringDiff {
+                    operation,
+                    text: text.into_iter().collect(),
+                })
+                .collect(),
+            start1: patch.start1 as i32,
+            start2: patch.start2 as i32,
+            length1: patch.length1 as i32,
+            length2: patch.length2 as i32,
+        }
+    }
+}
+
+fn diff_main(text1: &[char], text2: &[char]) -> Vec<Diff> {
+    if text1 == text2 {
+        return if text1.is_empty() {
+            vec![]
+        } else {
+            vec![(Operation::Equal, text1.to_vec())]
+        };
+    }
+    let prefix = common_prefix(text1, text2);
+    let (rest1, rest2) = (&text1[prefix..], &text2[prefix..]);
+    let suffix = common_suffix(rest1, rest2);
+    let mut diffs = vec![];
+    if prefix > 0 {
+        diffs.push((Operation::Equal, text1[..prefix].to_vec()));
+    }
+    diffs.extend(diff_compute(
+        &rest1[..rest1.len() - suffix],
+        &rest2[..rest2.len() - suffix],
+    ));
+    if suffix > 0 {
+        diffs.push((Operation::Equal, rest1[rest1.len() - suffix..].to_vec()));
+    }
+    cleanup_merge(diffs)
+}
+
+/// Diffs of texts which have no common prefix or suffix.
+fn diff_compute(text1: &[char], text2: &[char]) -> Vec<Diff> {
+    if text1.is_empty() {
+        return vec![(Operation::Insert, text2.to_vec())];
+    }
+    if text2.is_empty() {
+        return vec![(Operation::Delete, text1.to_vec())];
+    }
+    let (long, short, operation) = if text1.len() > text2.len() {
+        (text1, text2, Operation::Delete)
+    } else {
+        (text2, text1, Operation::Insert)
+    };
+    if let Some(index) = find(long, short, 0) {
+        return vec![
+            (operation.clone(), long[..index].to_vec()),
+            (Operation::Equal, short.to_vec()),
+            (operation, long[index + short.len()..].to_vec()),
+        ];
+    }
+    if short.len() == 1 {
+        return vec![
+            (Operation::Delete, text1.to_vec()),
+            (Operation::Insert, text2.to_vec()),
+        ];
+    }
+    diff_bisect(text1, text2)
+}
+
+/// Finds the middle snake of Myers' diff algorithm, and diffs both sides of it.
+fn diff_bisect(text1: &[char], text2: &[char]) -> Vec<Diff> {
+    let (len1, len2) = (text1.len() as isize, text2.len() as isize);
+    let max_d = (len1 + len2 + 1) / 2;
+    let v_offset = max_d;
+    let v_length = 2 * max_d;
+    let mut v1 = vec![-1isize; v_length as usize];
+    let mut v2 = vec![-1isize; v_length as usize];
+    v1[v_offset as usize + 1] = 0;
+    v2[v_offset as usize + 1] = 0;
+    let delta = len1 - len2;
+    // If the total number of chars is odd, the front path collides with the reverse path.
+    let front = delta % 2 != 0;
+    let (mut k1start, mut k1end, mut k2start, mut k2end) = (0, 0, 0, 0);
+    for d in 0..max_d {
+        let mut k1 = -d + k1start;
+        while k1 <= d - k1end {
+            let k1_offset = (v_offset + k1) as usize;
+            let mut x1 = if k1 == -d || (k1 != d && v1[k1_offset - 1] < v1[k1_offset + 1]) {
+                v1[k1_offset + 1]
+            } else {
+                v1[k1_offset - 1] + 1
+            };
+            let mut y1 = x1 - k1;
+            while x1 < len1 && y1 < len2 && text1[x1 as usize] == text2[y1 as usize] {
+                x1 += 1;
+                y1 += 1;
+            }
+            v1[k1_offset] = x1;
+            if x1 > len1 {
+                k1end += 2;
+            } else if y1 > len2 {
+                k1start += 2;
+            } else if front {
+                let k2_offset = v_offset + delta - k1;
+                if k2_offset >= 0 && k2_offset < v_length && v2[k2_offset as usize] != -1 {
+                    let x2 = len1 - v2[k2_offset as usize];
+                    if x1 >= x2 {
+                        return diff_bisect_split(text1, text2, x1 as usize, y1 as usize);
+                    }
+                }
+            }
+            k1 += 2;
+        }
+        let mut k2 = -d + k2start;
+        while k2 <= d - k2end {
+            let k2_offset = (v_offset + k2) as usize;
+            let mut x2 = if k2 == -d || (k2 != d && v2[k2_offset - 1] < v2[k2_offset + 1]) {
+                v2[k2_offset + 1]
+            } else {
+                v2[k2_offset - 1] + 1
+            };
+            let mut y2 = x2 - k2;
+            while x2 < len1
+                && y2 < len2
+                && text1[(len1 - x2 - 1) as usize] == text2[(len2 - y2 - 1) as usize]
+            {
+                x2 += 1;
+                y2 += 1;
+            }
+            v2[k2_offset] = x2;
+            if x2 > len1 {
+                k2end += 2;
+            } else if y2 > len2 {
+                k2start += 2;
+            } else if !front {
+                let k1_offset = v_offset + delta - k2;
+                if k1_offset >= 0 && k1_offset < v_length && v1[k1_offset as usize] != -1 {
+                    let x1 = v1[k1_offset as usize];
+                    let y1 = v_offset + x1 - k1_offset;
+                    if x1 >= len1 - x2 {
+                        return diff_bisect_split(text1, text2, x1 as usize, y1 as usize);
+                    }
+                }
+            }
+            k2 += 2;
+        }
+    }
+    // No common chars.
+    vec![
+        (Operation::Delete, text1.to_vec()),
+        (Operation::Insert, text2.to_vec()),
+    ]
+}
+
+fn diff_bisect_split(text1: &[char], text2: &[char], x: usize, y: usize) -> Vec<Diff> {
+    let mut diffs = diff_main(&text1[..x], &text2[..y]);
+    diffs.extend(diff_main(&text1[x..], &text2[y..]));
+    diffs
+}
+
+/// Merges adjacent diffs of the same operation, and factors out common chars of edits.
+fn cleanup_merge(diffs: Vec<Diff>) -> Vec<Diff> {
+    fn push(result: &mut Vec<Diff>, operation: Operation, text: Vec<char>) {
+        if text.is_empty() {
+            return;
+        }
+        match result.last_mut() {
+            Some((last, last_text)) if *last == operation => last_text.extend(text),
+            _ => result.push((operation, text)),
+        }
+    }
+    fn flush(result: &mut Vec<Diff>, mut delete: Vec<char>, mut insert: Vec<char>) {
+        let prefix = common_prefix(&insert, &delete);
+        push(result, Operation::Equal, insert[..prefix].to_vec());
+        insert.drain(..prefix);
+        delete.drain(..prefix);
+        let suffix = common_suffix(&insert, &delete);
+        let common = insert[insert.len() - suffix..].to_vec();
+        insert.truncate(insert.len() - suffix);
+        delete.truncate(delete.len() - suffix);
+        push(result, Operation::Delete, delete);
+        push(result, Operation::Insert, insert);
+        push(result, Operation::Equal, common);
+    }
+    let mut result = vec![];
+    let (mut delete, mut insert) = (vec![], vec![]);
+    for (operation, text) in diffs {
+        match operation {
+            Operation::Delete => delete.extend(text),
+            Operation::Insert => insert.extend(text),
+            Operation::Equal => {
+                flush(
+                    &mut result,
+                    std::mem::take(&mut delete),
+                    std::mem::take(&mut insert),
+                );
+                push(&mut result, Operation::Equal, text);
+            }
+        }
+    }
+    flush(&mut result, delete, insert);
+    result
+}
+
+fn patch_make(text1: &[char], diffs: &[Diff]) -> Vec<CharPatch> {
+    let mut patches = vec![];
+    let mut patch = CharPatch::default();
+    let (mut char_count1, mut char_count2) = (0, 0);
+    // The text before and after the current patch, to which the context refers.
+    let mut prepatch_text = text1.to_vec();
+    let mut postpatch_text = text1.to_vec();
+    for (index, (operation, text)) in diffs.iter().enumerate() {
+        if patch.diffs.is_empty() && *operation != Operation::Equal {
+            patch.start1 = char_count1;
+            patch.start2 = char_count2;
+        }
+        match operation {
+            Operation::Insert => {
+                patch.diffs.push((operation.clone(), text.clone()));
+                patch.length2 += text.len();
+                postpatch_text.splice(char_count2..char_count2, text.iter().cloned());
+            }
+            Operation::Delete => {
+                patch.diffs.push((operation.clone(), text.clone()));
+                patch.length1 += text.len();
+                postpatch_text.drain(char_count2..char_count2 + text.len());
+            }
+            Operation::Equal => {
+                if text.len() <= 2 * PATCH_MARGIN
+                    && !patch.diffs.is_empty()
+                    && index != diffs.len() - 1
+                {
+                    // A small equality inside a patch.
+                    patch.diffs.push((operation.clone(), text.clone()));
+                    patch.length1 += text.len();
+                    patch.length2 += text.len();
+                } else if text.len() >= 2 * PATCH_MARGIN && !patch.diffs.is_empty() {
+                    // A large equality ends the patch.
+                    add_context(&mut patch, &prepatch_text);
+                    patches.push(std::mem::take(&mut patch));
+                    prepatch_text = postpatch_text.clone();
+                    char_count1 = char_count2;
+                }
+            }
+        }
+        if *operation != Operation::Insert {
+            char_count1 += text.len();
+        }
+        if *operation != Operation::Delete {
+            char_count2 += text.len();
+        }
+    }
+    if !patch.diffs.is_empty() {
+        add_context(&mut patch, &prepatch_text);
+        patches.push(patch);
+    }
+    patches
+}
+
+/// Surrounds the patch with enough context to be located uniquely.
+fn add_context(patch: &mut CharPatch, text: &[char]) {
+    if text.is_empty() {
+        return;
+    }
+    let context = |padding: usize| {
+        &text[patch.start2.saturating_sub(padding)
+            ..(patch.start2 + patch.length1 + padding).min(text.len())]
+    };
+    let mut padding = 0;
+    let mut pattern = context(padding);
+    while find(text, pattern, 0) != rfind(text, pattern, text.len())
+        && pattern.len() < MATCH_MAX_BITS - 2 * PATCH_MARGIN
+    {
+        padding += PATCH_MARGIN;
+        pattern = context(padding);
+    }
+    // One more chunk of context.
+    padding += PATCH_MARGIN;
+    let prefix = text[patch.start2.saturating_sub(padding)..patch.start2].to_vec();
+    let suffix_start = (patch.start2 + patch.length1).min(text.len());
+    let suffix = text[suffix_start..(suffix_start + padding).min(text.len())].to_vec();
+    patch.start1 -= prefix.len();
+    patch.start2 -= prefix.len();
+    patch.length1 += prefix.len() + suffix.len();
+    patch.length2 += prefix.len() + suffix.len();
+    if !prefix.is_empty() {
+        patch.diffs.insert(0, (Operation::Equal, prefix));
+    }
+    if !suffix.is_empty() {
+        patch.diffs.push((Operation::Equal, suffix));
+    }
+}
+
+/// Pads both ends of the text with chars which never appear in it, so edits at the ends are matched.
+fn add_padding(patches: &mut [CharPatch]) -> Vec<char> {
+    let padding: Vec<char> = (1..=PATCH_MARGIN as u8).map(char::from).collect();
+    for patch in patches.iter_mut() {
+        patch.start1 += PATCH_MARGIN;
+        patch.start2 += PATCH_MARGIN;
+    }
+    let first = &mut patches[0];
+    match first.diffs.first_mut() {
+        Some((Operation::Equal, text)) if text.len() < PATCH_MARGIN => {
+            let extra = PATCH_MARGIN - text.len();
+            text.splice(0..0, padding[text.len()..].iter().cloned());
+            first.start1 -= extra;
+            first.start2 -= extra;
+            first.length1 += extra;
+            first.length2 += extra;
+        }
+        Some((Operation::Equal, _)) => {}
+        _ => {
+            first.diffs.insert(0, (Operation::Equal, padding.clone()));
+            first.start1 -= PATCH_MARGIN;
+            first.start2 -= PATCH_MARGIN;
+            first.length1 += PATCH_MARGIN;
+            first.length2 += PATCH_MARGIN;
+        }
+    }
+    let last = patches.last_mut().unwrap();
+    match last.diffs.last_mut() {
+        Some((Operation::Equal, text)) if text.len() < PATCH_MARGIN => {
+            let extra = PATCH_MARGIN - text.len();
+            text.extend(&padding[..extra]);
+            last.length1 += extra;
+            last.length2 += extra;
+        }
+        Some((Operation::Equal, _)) => {}
+        _ => {
+            last.diffs.push((Operation::Equal, padding.clone()));
+            last.length1 += PATCH_MARGIN;
+            last.length2 += PATCH_MARGIN;
+        }
+    }
+    padding
+}
+
+/// Finds the best match of the pattern near the location.
+fn match_main(text: &[char], pattern: &[char], loc: usize) -> Option<usize> {
+    let loc = loc.min(text.len());
+    if text == pattern {
+        Some(0)
+    } else if text.is_empty() {
+        None
+    } else if loc + pattern.len() <= text.len() && text[loc..loc + pattern.len()] == *pattern {
+        Some(loc)
+    } else {
+        match_bitap(text, pattern, loc)
+    }
+}
+
+/// Fuzzy matching by the bitap algorithm, which allows errors in the pattern.
+fn match_bitap(text: &[char], pattern: &[char], loc: usize) -> Option<usize> {
+    let mut alphabet: HashMap<char, u64> = HashMap::new();
+    for (index, c) in pattern.iter().enumerate() {
+        *alphabet.entry(*c).or_default() |= 1 << (pattern.len() - index - 1);
+    }
+    // Errors per char plus distance from the location.
+    let score = |errors: usize, x: usize| {
+        let accuracy = errors as f64 / pattern.len() as f64;
+        let proximity = (loc as isize - x as isize).unsigned_abs() as f64;
+        accuracy + proximity / MATCH_DISTANCE
+    };
+    let mut score_threshold = MATCH_THRESHOLD;
+    if let Some(best) = find(text, pattern, loc) {
+        score_threshold = score(0, best).min(score_threshold);
+        if let Some(best) = rfind(text, pattern, loc + pattern.len()) {
+            score_threshold = score(0, best).min(score_threshold);
+        }
+    }
+    let match_mask = 1u64 << (pattern.len() - 1);
+    let mut best_loc = None;
+    let mut bin_max = pattern.len() + text.len();
+    let mut last_rd: Vec<u64> = vec![];
+    for d in 0..pattern.len() {
+        // Binary search for how far from the location the match can be with this many errors.
+        let mut bin_min = 0;
+        let mut bin_mid = bin_max;
+        while bin_min < bin_mid {
+            if score(d, loc + bin_mid) <= score_threshold {
+                bin_min = bin_mid;
+            } else {
+                bin_max = bin_mid;
+            }
+            bin_mid = (bin_max - bin_min) / 2 + bin_min;
+        }
+        bin_max = bin_mid;
+        let mut start = (loc as isize - bin_mid as isize + 1).max(1) as usize;
+        let finish = (loc + bin_mid).min(text.len()) + pattern.len();
+        let mut rd = vec![0u64; finish + 2];
+        rd[finish + 1] = (1 << d) - 1;
+        let mut j = finish;
+        while j >= start {
+            let char_match = text
+                .get(j - 1)
+                .and_then(|c| alphabet.get(c))
+                .copied()
+                .unwrap_or(0);
+            rd[j] = if d == 0 {
+                ((rd[j + 1] << 1) | 1) & char_match
+            } else {
+                (((rd[j + 1] << 1) | 1) & char_match)
+                    | (((last_rd[j + 1] | last_rd[j]) << 1) | 1)
+                    | last_rd[j + 1]
+            };
+            if rd[j] & match_mask != 0 {
+                let score = score(d, j - 1);
+                if score <= score_threshold {
+                    score_threshold = score;
+                    best_loc = Some(j - 1);
+                    if j - 1 > loc {
+                        // Matches on the left side are still better.
+                        start = (2 * loc as isize - (j - 1) as isize).max(1) as usize;
+                    } else {
+                        break;
+                    }
+                }
+            }
+            j -= 1;
+        }
+        if score(d + 1, loc) > score_threshold {
+            // More errors never score better.
+            break;
+        }
+        last_rd = rd;
+    }
+    best_loc
+}
+
+fn levenshtein(diffs: &[Diff]) -> usize {
+    let mut levenshtein = 0;
+    let (mut insertions, mut deletions) = (0, 0);
+    for (operation, text) in diffs {
+        match operation {
+            Operation::Insert => insertions += text.len(),
+            Operation::Delete => deletions += text.len(),
+            Operation::Equal => {
+                levenshtein += insertions.max(deletions);
+                insertions = 0;
+                deletions = 0;
+            }
+        }
+    }
+    levenshtein + insertions.max(deletions)
+}
+
+/// Translates a location in the text1 to the text2 of the diffs.
+fn x_index(diffs: &[Diff], loc: usize) -> usize {
+    let (mut chars1, mut chars2) = (0, 0);
+    let (mut last_chars1, mut last_chars2) = (0, 0);
+    for (operation, text) in diffs {
+        if *operation != Operation::Insert {
+            chars1 += text.len();
+        }
+        if *operation != Operation::Delete {
+            chars2 += text.len();
+        }
+        if chars1 > loc {
+            if *operation == Operation::Delete {
+                // The location is deleted.
+                return last_chars2;
+            }
+            break;
+        }
+        last_chars1 = chars1;
+        last_chars2 = chars2;
+    }
+    last_chars2 + (loc - last_chars1)
+}
+
+fn diff_text1(diffs: &[Diff]) -> Vec<char> {
+    diffs
+        .iter()
+        .filter(|(operation, _)| *operation != Operation::Insert)
+        .flat_map(|(_, text)| text.iter().cloned())
+        .collect()
+}
+
+fn diff_text2(diffs: &[Diff]) -> Vec<char> {
+    diffs
+        .iter()
+        .filter(|(operation, _)| *operation != Operation::Delete)
+        .flat_map(|(_, text)| text.iter().cloned())
+        .collect()
+}
+
+fn common_prefix(text1: &[char], text2: &[char]) -> usize {
+    text1.iter().zip(text2).take_while(|(a, b)| a == b).count()
+}
+
+fn common_suffix(text1: &[char], text2: &[char]) -> usize {
+    text1
+        .iter()
+        .rev()
+        .zip(text2.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// The first index of the pattern at or after the start.
+fn find(text: &[char], pattern: &[char], start: usize) -> Option<usize> {
+    if pattern.len() > text.len() {
+        return None;
+    }
+    (start..=text.len() - pattern.len()).find(|&index| text[index..].starts_with(pattern))
+}
+
+/// The last index of the pattern at or before the start.
+fn rfind(text: &[char], pattern: &[char], start: usize) -> Option<usize> {
+    if pattern.len() > text.len() {
+        return None;
+    }
+    (0..=start.min(text.len() - pattern.len()))
+        .rev()
+        .find(|&index| text[index..].starts_with(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn diffs() {
+        assert_eq!(
+            diff("abc", "ab123c"),
+            vec![
+                StringDiff {
+                    operation: Operation::Equal,
+                    text: "ab".into()
+                },
+                StringDiff {
+                    operation: Operation::Insert,
+                    text: "123".into()
+                },
+                StringDiff {
+                    operation: Operation::Equal,
+                    text: "c".into()
+                },
+            ]
+        );
+        assert_eq!(
+            diff("a", "b"),
+            vec![
+                StringDiff {
+                    operation: Operation::Delete,
+                    text: "a".into()
+                },
+                StringDiff {
+                    operation: Operation::Insert,
+                    text: "b".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_bisects() {
+        let diffs = diff_main(&chars("cat"), &chars("map"));
+        assert_eq!(diff_text1(&diffs), chars("cat"));
+        assert_eq!(diff_text2(&diffs), chars("map"));
+        assert!(diffs.contains(&(Operation::Equal, chars("a"))));
+    }
+
+    #[test]
+    fn matches_fuzzily() {
+        assert_eq!(match_main(&chars("abcdefghijk"), &chars("fgh"), 5), Some(5));
+        assert_eq!(
+            match_main(&chars("abcdefghijk"), &chars("efxhi"), 0),
+            Some(4)
+        );
+        assert_eq!(match_main(&chars("abcdefghijk"), &chars("bxy"), 1), None);
+    }
+
+    #[test]
+    fn round_trips() {
+        let cases = [
+            ("", "abc"),
+            ("abc", ""),
+            (
+                "The quick brown fox jumps over the lazy dog.",
+                "That quick brown fox jumped over a lazy dog.",
+            ),
+            (
+                "$ 1 ~ * &'number, \"あいう\"",
+                "$ 2 ~ * &'number, \"あえう\", 3",
+            ),
+        ];
+        for (text1, text2) in cases {
+            let patches = make_patches(text1, text2);
+            assert_eq!(apply_patches(&patches, text1), Ok(text2.into()));
+        }
+    }
+
+    #[test]
+    fn applies_to_changed_text() {
+        let patches = make_patches(
+            "The quick brown fox jumps over the lazy dog.",
+            "That quick brown fox jumped over a lazy dog.",
+        );
+        assert_eq!(
+            apply_patches(&patches, "The quick red rabbit jumps over the tired tiger."),
+            Ok("That quick red rabbit jumped over a tired tiger.".into())
+        );
+    }
+
+    #[test]
+    fn fails_on_unrelated_text() {
+        let patches = make_patches(
+            "The quick brown fox jumps over the lazy dog.",
+            "That quick brown fox jumped over a lazy dog.",
+        );
+        assert_eq!(
+            apply_patches(&patches, "I am the very model of a modern major general."),
+            Err(DiffMatchPatchError::NotApplied { index: 0 })
+        );
+    }
+}
@@ -1,13 +1,20 @@
-mod diff_match_patch;
+pub mod diff_match_patch;
+
 use deskc_ids::{LinkName, NodeId};
 use hir::expr::Expr;
+use serde::{Deserialize, Serialize};
 use types::Type;
 
-use crate::{code::SyntaxKind, content::Content};
+use thiserror::Error;
+
+use crate::{
+    code::SyntaxKind,
+    content::{Content, ContentKind},
+};
 
-use self::diff_match_patch::Patch;
+use self::diff_match_patch::{DiffMatchPatchError, Patch};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ContentPatch {
     Replace(Content),
     ChangeSourceCodeSyntax { syntax: SyntaxKind, source: String },
@@ -19,23 +26,73 @@ pub enum ContentPatch {
     UpdateApply { ty: Type, link_name: LinkName },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum StringPatch {
     Replace(String),
     DiffMatchPatch(Vec<Patch>),
 }
 
+impl StringPatch {
+    /// A patch for an edit from the before to the after, which can be merged with concurrent edits.
+    pub fn diff(before: &str, after: &str) -> Self {
+        StringPatch::DiffMatchPatch(diff_match_patch::make_patches(before, after))
+    }
+
+    pub fn apply(&self, text: &str) -> Result<String, PatchError> {
+        match self {
+            StringPatch::Replace(string) => Ok(string.clone()),
+            StringPatch::DiffMatchPatch(patches) => {
+                Ok(diff_match_patch::apply_patches(patches, text)?)
+            }
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PatchError {
+    #[error("expected {expected:?} content but found {actual:?}")]
+    ContentKindMismatch {
+        expected: ContentKind,
+        actual: ContentKind,
+    },
+    #[error(transparent)]
+    DiffMatchPatch(#[from] DiffMatchPatchError),
+    #[error("{0} is out of range of integer content")]
+    OutOfRange(u64),
+    #[error("float content must be finite")]
+    NonFiniteFloat,
+    #[error("denominator must not be zero")]
+    ZeroDenominator,
+}
+
+impl ContentPatch {
+    /// The kind of content which the patch applies to, or `None` for any kind.
+    pub fn content_kind(&self) -> Option<ContentKind> {
+        match self {
+            ContentPatch::Replace(_) => None,
+            ContentPatch::ChangeSourceCodeSyntax { .. } | ContentPatch::PatchSourceCode(_) => {
+                Some(ContentKind::SourceCode)
+            }
+            ContentPatch::PatchString(_) => Some(ContentKind::String),
+            ContentPatch::UpdateInteger(_) => Some(ContentKind::Integer),
+            ContentPatch::UpdateFloat(_) => Some(ContentKind::Float),
+            ContentPatch::UpdateRational(_, _) => Some(ContentKind::Rational),
+            ContentPatch::UpdateApply { .. } => Some(ContentKind::Apply),
+        }
+    }
+}
+
 // ContentPatch::AddFloat should not be NaN
 impl Eq for ContentPatch {}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OperandPatch {
     Insert { index: usize, node_id: NodeId },
     Remove { index: usize },
     Move { from: usize, to: usize },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AttributePatch {
     Update { key: Type, value: Box<Expr> },
     Remove { key: Type },
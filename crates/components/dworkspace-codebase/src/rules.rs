@@ -1,10 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
+use serde::{Deserialize, Serialize};
 use types::Type;
 
 use crate::user::UserId;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Rules<Operation: Eq + std::hash::Hash> {
     /// Used if user is not in the map.
     pub default: HashSet<Operation>,
@@ -50,7 +51,7 @@ impl<T: Eq + std::hash::Hash + Clone> Rules<T> {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SpaceOperation {
     AddOwner,
     RemoveOwner,
@@ -58,7 +59,7 @@ pub enum SpaceOperation {
     CreateNode,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum NodeOperation {
     RemoveNode,
     PatchSourceCode,
@@ -5,8 +5,9 @@ use crate::flat_node::FlatNode;
 use crate::rules::{Rules, SpaceOperation};
 use crate::user::UserId;
 use deskc_ids::NodeId;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Snapshot {
     pub owners: HashSet<UserId>,
     // flat nodes are owned by hirs db
@@ -20,7 +21,9 @@ impl Snapshot {
             Event::AddOwner { user_id } => {
                 self.owners.insert(user_id.clone());
             }
-            Event::RemoveOwner { user_id: _ } => todo!(),
+            Event::RemoveOwner { user_id } => {
+                self.owners.remove(user_id);
+            }
             Event::CreateNode { node_id, content } => {
                 self.flat_nodes
                     .insert(node_id.clone(), FlatNode::new(content.clone()));
@@ -29,7 +32,9 @@ impl Snapshot {
                 self.flat_nodes.remove(node_id);
             }
             Event::PatchContent { node_id, patch } => {
-                self.flat_nodes
+                // Patches which do not apply are rejected by the audit, and leave the node unchanged.
+                let _ = self
+                    .flat_nodes
                     .get_mut(node_id)
                     .unwrap()
                     .patch_content(patch);
@@ -46,10 +51,9 @@ impl Snapshot {
                     .unwrap()
                     .patch_attribute(patch);
             }
-            Event::AddSnapshot {
-                index: _,
-                snapshot: _,
-            } => todo!(),
+            Event::AddSnapshot { index: _, snapshot } => {
+                *self = snapshot.as_ref().clone();
+            }
             Event::UpdateSpaceRules { rules } => {
                 self.rules = rules.clone();
             }
@@ -83,6 +87,18 @@ mod tests {
         )
     }
 
+    #[test]
+    fn remove_owner() {
+        let mut snapshot = Snapshot::default();
+        snapshot.handle_event(&Event::AddOwner {
+            user_id: UserId("a".into()),
+        });
+        snapshot.handle_event(&Event::RemoveOwner {
+            user_id: UserId("a".into()),
+        });
+        assert_eq!(snapshot.owners, HashSet::default())
+    }
+
     #[test]
     fn add_node() {
         let mut snapshot = Snapshot::default();
@@ -165,6 +181,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn add_snapshot() {
+        let mut snapshot = Snapshot::default();
+        handle_add_node(&mut snapshot);
+        let mut added = Snapshot::default();
+        let node_id = handle_add_node(&mut added);
+        snapshot.handle_event(&Event::AddSnapshot {
+            index: 1,
+            snapshot: Box::new(added.clone()),
+        });
+
+        assert_eq!(snapshot, added);
+        assert!(snapshot.flat_nodes.contains_key(&node_id));
+    }
+
     fn handle_add_node(snapshot: &mut Snapshot) -> NodeId {
         let node_id = NodeId::new();
         let event = Event::CreateNode {
@@ -1,9 +1,10 @@
+use bevy::log::error;
 use desk_window::ctx::Ctx;
 use desk_window::widget::Widget;
 use deskc_ids::NodeId;
 use dworkspace_codebase::content::Content;
 use dworkspace_codebase::event::Event;
-use dworkspace_codebase::patch::{ContentPatch, OperandPatch};
+use dworkspace_codebase::patch::{ContentPatch, OperandPatch, StringPatch};
 
 use crate::editor_state::EditorState;
 
@@ -18,39 +19,45 @@ impl Widget<egui::Context> for EditorWidget {
             if let Some(node) = ctx.kernel.snapshot.flat_nodes.get(&self.node_id) {
                 match &node.content {
                     dworkspace_codebase::content::Content::SourceCode {
-                        source: original,
-                        syntax,
+                        source: original, ..
                     } => {
                         let mut source = original.clone();
                         ui.text_edit_multiline(&mut source);
                         if *original != source {
-                            ctx.kernel.commit(Event::PatchContent {
+                            if let Err(err) = ctx.kernel.commit(Event::PatchContent {
                                 node_id: self.node_id.clone(),
-                                patch: ContentPatch::Replace(Content::SourceCode {
-                                    source,
-                                    syntax: syntax.clone(),
-                                }),
-                            });
+                                patch: ContentPatch::PatchSourceCode(StringPatch::diff(
+                                    original, &source,
+                                )),
+                            }) {
+                                error!("failed to commit an event: {err}");
+                            }
                         }
                     }
                     dworkspace_codebase::content::Content::String(original) => {
                         let mut string = original.clone();
                         ui.text_edit_singleline(&mut string);
                         if *original != string {
-                            ctx.kernel.commit(Event::PatchContent {
+                            if let Err(err) = ctx.kernel.commit(Event::PatchContent {
                                 node_id: self.node_id.clone(),
-                                patch: ContentPatch::Replace(Content::String(string)),
-                            });
+                                patch: ContentPatch::PatchString(StringPatch::diff(
+                                    original, &string,
+                                )),
+                            }) {
+                                error!("failed to commit an event: {err}");
+                            }
                         }
                     }
                     dworkspace_codebase::content::Content::Integer(original) => {
                         let mut number = *original;
                         ui.add(egui::DragValue::new(&mut number));
                         if *original != number {
-                            ctx.kernel.commit(Event::PatchContent {
+                            if let Err(err) = ctx.kernel.commit(Event::PatchContent {
                                 node_id: self.node_id.clone(),
                                 patch: ContentPatch::Replace(Content::Integer(number)),
-                            });
+                            }) {
+                                error!("failed to commit an event: {err}");
+                            }
                         }
                     }
                     dworkspace_codebase::content::Content::Rational(_a, _b) => todo!(),
@@ -70,7 +77,9 @@ impl Widget<egui::Context> for EditorWidget {
                             });
                         }
                         if let Some(event) = clicked {
-                            ctx.kernel.commit(event);
+                            if let Err(err) = ctx.kernel.commit(event) {
+                                error!("failed to commit an event: {err}");
+                            }
                         }
                         if ui.button("add a node as a child").clicked() {
                             ctx.kernel
@@ -89,13 +98,15 @@ impl Widget<egui::Context> for EditorWidget {
                 .clone()
             {
                 if target != self.node_id && ui.button("Add this as a child").clicked() {
-                    ctx.kernel.commit(Event::PatchOperand {
+                    if let Err(err) = ctx.kernel.commit(Event::PatchOperand {
                         node_id: target,
                         patch: OperandPatch::Insert {
                             index: 0,
                             node_id: self.node_id.clone(),
                         },
-                    });
+                    }) {
+                        error!("failed to commit an event: {err}");
+                    }
                     ctx.kernel
                         .get_state_mut::<EditorState>()
                         .unwrap()
@@ -33,7 +33,9 @@ fn render(
         let mut ctx = Ctx::new(&mut kernel, egui_context.ctx_mut());
         window.render(&mut ctx);
         for event in ctx.events {
-            kernel.commit(event);
+            if let Err(err) = kernel.commit(event) {
+                error!("failed to commit an event: {err}");
+            }
         }
     }
 }
@@ -1,8 +1,8 @@
-use chumsky::prelude::Simple;
-use textual_diagnostics::{Report, TextualDiagnostics};
+use chumsky::error::{Simple, SimpleReason};
+use textual_diagnostics::{unexpected_text, Report, TextualDiagnostics};
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq)]
+#[derive(Error, Clone, Debug, PartialEq)]
 #[error("{0:?}")]
 pub struct LexerError(pub Vec<Simple<char>>);
 
@@ -10,14 +10,45 @@ impl From<LexerError> for TextualDiagnostics {
     fn from(error: LexerError) -> TextualDiagnostics {
         TextualDiagnostics {
             title: "Lexer error".into(),
-            reports: error
-                .0
-                .into_iter()
-                .map(|error| Report {
-                    span: error.span(),
-                    text: format!("{:?}", error),
-                })
-                .collect(),
+            reports: error.0.into_iter().map(report).collect(),
         }
     }
 }
+
+fn report(error: Simple<char>) -> Report {
+    match error.reason() {
+        SimpleReason::Unexpected => Report::error(
+            "E0101",
+            error.span(),
+            unexpected_text(
+                error.found().map(|c| format!("{:?}", c)),
+                error
+                    .expected()
+                    .map(|c| c.map_or("end of input".into(), |c| format!("{:?}", c)))
+                    .collect(),
+            ),
+        ),
+        SimpleReason::Unclosed { span, delimiter } => Report::error(
+            "E0102",
+            error.span(),
+            format!("unclosed delimiter {:?}", delimiter),
+        )
+        .with_label(span.clone(), "opened here"),
+        // Invalid literals like a too large integer.
+        SimpleReason::Custom(text) => Report::error("E0103", error.span(), text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_invalid_literal() {
+        let diagnostics: TextualDiagnostics = crate::scan("1/0").unwrap_err().into();
+        assert_eq!(
+            diagnostics.reports,
+            vec![Report::error("E0103", 0..3, "zero denominator")]
+        );
+    }
+}
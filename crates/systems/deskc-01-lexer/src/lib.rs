@@ -1,4 +1,4 @@
-mod error;
+pub mod error;
 
 use std::ops::Range;
 
@@ -27,8 +27,41 @@ pub fn lexer() -> impl Parser<char, Tokens, Error = Simple<char>> {
     let int = just('-')
         .or_not()
         .chain::<char, _, _>(text::int(10))
+        .collect::<String>();
+    let fraction = just('.').chain::<char, _, _>(text::digits(10));
+    let exponent = one_of("eE")
+        .chain::<char, _, _>(one_of("+-").or_not())
+        .chain::<char, _, _>(text::digits(10));
+    let float = fraction
+        .chain::<char, _, _>(exponent.clone().or_not().flatten())
+        .or(exponent)
         .collect::<String>()
-        .map(|s: String| Token::Int(s.parse().unwrap()));
+        .map(NumberSuffix::Float);
+    let denominator = just('/')
+        .ignore_then(text::int(10))
+        .map(NumberSuffix::Denominator);
+    // Not `int.or(float)` which falls back to an int on a malformed float.
+    let number = int.then(float.or(denominator).or_not()).validate(
+        |(int, suffix), span: Range<usize>, emit| match suffix {
+            None => Token::Int(parse_int(&int, span, emit)),
+            Some(NumberSuffix::Float(suffix)) => {
+                let float = int + &suffix;
+                // NaN and infinity have no syntax, so `Literal::Float` is always `Eq`.
+                if !float.parse::<f64>().is_ok_and(f64::is_finite) {
+                    emit(Simple::custom(span, "float literal out of range"))
+                }
+                Token::Float(float)
+            }
+            Some(NumberSuffix::Denominator(denominator)) => {
+                let numerator = parse_int(&int, span.clone(), &mut *emit);
+                let denominator = parse_int(&denominator, span.clone(), &mut *emit);
+                if denominator == 0 {
+                    emit(Simple::custom(span, "zero denominator"))
+                }
+                Token::Rational(numerator, denominator)
+            }
+        },
+    );
     let escape = just('\\').ignore_then(
         just('\\')
             .or(just('"'))
@@ -40,13 +73,6 @@ pub fn lexer() -> impl Parser<char, Tokens, Error = Simple<char>> {
         .ignore_then(filter(|c| *c != '\\' && *c != '"').or(escape).repeated())
         .then_ignore(just('"'))
         .collect::<String>()
-        .map(|s| {
-            s.replace(r#"\\"#, r#"\"#)
-                .replace(r#"\""#, "\"")
-                .replace(r#"\n"#, "\n")
-                .replace(r#"\r"#, "\r")
-                .replace(r#"\t"#, "\t")
-        })
         .map(Token::Str);
     let symbol = just('$')
         .to(Token::Let)
@@ -83,6 +109,7 @@ pub fn lexer() -> impl Parser<char, Tokens, Error = Simple<char>> {
             "string" => Ok(Token::StringType),
             "alias" => Ok(Token::Alias),
             "brand" => Ok(Token::Brands),
+            "unbrand" => Ok(Token::Unbrand),
             "type" => Ok(Token::Type),
             "this" => Ok(Token::This),
             "handle" => Ok(Token::Handle),
@@ -112,7 +139,7 @@ pub fn lexer() -> impl Parser<char, Tokens, Error = Simple<char>> {
         });
     let token = comment
         .or(uuid)
-        .or(int)
+        .or(number)
         .or(string)
         .or(symbol)
         .or(special)
@@ -131,6 +158,18 @@ pub fn lexer() -> impl Parser<char, Tokens, Error = Simple<char>> {
         .flatten()
 }
 
+enum NumberSuffix {
+    Float(String),
+    Denominator(String),
+}
+
+fn parse_int(int: &str, span: Range<usize>, emit: &mut dyn FnMut(Simple<char>)) -> i64 {
+    int.parse().unwrap_or_else(|_| {
+        emit(Simple::custom(span, "integer overflow"));
+        0
+    })
+}
+
 pub fn ident() -> impl Parser<char, String, Error = Simple<char>> + Clone {
     let assert_not_whitespace = |c: char, span| {
         if c.is_whitespace() {
@@ -254,6 +293,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn string_with_escaped_backslash() {
+        assert_eq!(
+            lexer().parse(r#""\\n""#).unwrap(),
+            vec![(Token::Str("\\n".into()), 0..5)]
+        );
+    }
+
+    #[test]
+    fn float() {
+        assert_eq!(
+            scan("1.5 -0.25e3 2E-2 1.").unwrap(),
+            vec![
+                (Token::Float("1.5".into()), 0..3),
+                (Token::Float("-0.25e3".into()), 4..11),
+                (Token::Float("2E-2".into()), 12..16),
+                (Token::Int(1), 17..18),
+                (Token::Dot, 18..19),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_infinite_float() {
+        assert!(scan("1e400").is_err());
+        assert!(scan("-1e400").is_err());
+    }
+
+    #[test]
+    fn rational() {
+        assert_eq!(
+            scan("3/4 -1/2 1 / 2").unwrap(),
+            vec![
+                (Token::Rational(3, 4), 0..3),
+                (Token::Rational(-1, 2), 4..8),
+                (Token::Int(1), 9..10),
+                (Token::Divide, 11..12),
+                (Token::Int(2), 13..14),
+            ]
+        );
+        assert!(scan("1/0").is_err());
+    }
+
+    #[test]
+    fn rejects_int_overflow() {
+        assert!(scan("9223372036854775808").is_err());
+    }
+
     #[test]
     fn semicolon_to_comma_dot() {
         assert_eq!(
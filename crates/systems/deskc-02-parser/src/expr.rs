@@ -18,16 +18,28 @@ pub fn parser() -> impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + C
             Token::Int(int) => Ok(int),
             _ => Err(Simple::custom(span, "expected int literal")),
         });
-        let rational = int64
+        let rational = filter_map(|span, token| match token {
+            Token::Rational(a, b) => Ok(Expr::Literal(Literal::Rational(a, b))),
+            _ => Err(Simple::custom(span, "expected rational literal")),
+        })
+        .or(int64
             .then_ignore(just(Token::Divide))
             .then(int64)
-            .map(|(a, b)| Expr::Literal(Literal::Rational(a, b)));
+            .map(|(a, b)| Expr::Literal(Literal::Rational(a, b))));
+        let float = filter_map(|span, token| match token {
+            Token::Float(float) => float
+                .parse()
+                .map(|float| Expr::Literal(Literal::Float(float)))
+                .map_err(|_| Simple::custom(span, "invalid float literal")),
+            _ => Err(Simple::custom(span, "expected float literal")),
+        });
         let string = filter_map(|span, token| match token {
             Token::Str(string) => Ok(Expr::Literal(Literal::String(string))),
             _ => Err(Simple::custom(span, "expected string literal")),
         });
         let literal = rational
             .or(int64.map(|int| Expr::Literal(Literal::Integer(int))))
+            .or(float)
             .or(string);
         let type_ = super::ty::parser(expr.clone());
         let let_in = just(Token::Let)
@@ -91,6 +103,8 @@ pub fn parser() -> impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + C
         let card_uuid = just(Token::Card)
             .ignore_then(parse_uuid())
             .map(LinkName::Card)
+            // A uuid without `'card` pins the card by its hash.
+            .or(parse_uuid().map(LinkName::Version))
             .or_not()
             .map(|name| name.unwrap_or(LinkName::None));
         let apply = just(Token::Apply)
@@ -105,7 +119,7 @@ pub fn parser() -> impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + C
             });
         let reference = just(Token::Reference)
             .ignore_then(type_.clone())
-            .then(card_uuid)
+            .then(card_uuid.clone())
             .map(|(reference, link_name)| Expr::Apply {
                 function: reference,
                 link_name,
@@ -164,6 +178,11 @@ pub fn parser() -> impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + C
                 label,
                 item: Box::new(expr),
             });
+        let unbrand = just(Token::Unbrand)
+            .ignore_then(expr.clone())
+            .map(|expr| Expr::Unbrand {
+                item: Box::new(expr),
+            });
         let newtype = just(Token::Type)
             .ignore_then(parse_ident())
             .then(type_.clone())
@@ -181,6 +200,14 @@ pub fn parser() -> impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + C
                 text,
                 item: Box::new(expr),
             });
+        // Same as references, `'card 'uuid` pins the card and `'uuid` pins the hash of the card.
+        let import = just(Token::Import)
+            .ignore_then(type_.clone())
+            .then(card_uuid)
+            .map(|(ty, link_name)| Expr::Import { ty, link_name });
+        let export = just(Token::Export)
+            .ignore_then(type_.clone())
+            .map(|ty| Expr::Export { ty });
         let card = just(Token::Card)
             .ignore_then(parse_uuid())
             .then(expr.clone())
@@ -209,7 +236,10 @@ pub fn parser() -> impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + C
             .or(reference.labelled("reference"))
             .or(handle.labelled("handle"))
             .or(label.labelled("label"))
+            .or(unbrand.labelled("unbrand"))
             .or(newtype.labelled("newtype"))
+            .or(import.labelled("import"))
+            .or(export.labelled("export"))
             .or(card.labelled("card"))
             .map_with_span(|token, span| WithSpan {
                 id: NodeId::new(),
@@ -267,6 +297,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_literal_rational_with_spaces() {
+        assert_eq!(
+            parse("1 / 2").unwrap().value,
+            Expr::Literal(Literal::Rational(1, 2))
+        );
+    }
+
+    #[test]
+    fn parse_literal_float() {
+        assert_eq!(
+            parse("-0.25e3").unwrap().value,
+            Expr::Literal(Literal::Float(-250.0))
+        );
+    }
+
     #[test]
     fn parse_literal_string() {
         assert_eq!(
@@ -343,6 +389,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_reference_with_version() {
+        assert_eq!(
+            parse("& 'number 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa")
+                .unwrap()
+                .value,
+            Expr::Apply {
+                function: dummy_span(Type::Number),
+                link_name: LinkName::Version(
+                    "9883b420-f7be-468d-95f6-aaaaaaaaaaaa".parse().unwrap()
+                ),
+                arguments: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_import() {
+        assert_eq!(
+            parse("'import 'number").unwrap().value,
+            Expr::Import {
+                ty: dummy_span(Type::Number),
+                link_name: LinkName::None,
+            }
+        );
+        assert_eq!(
+            parse("'import 'number 'card 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa")
+                .unwrap()
+                .value,
+            Expr::Import {
+                ty: dummy_span(Type::Number),
+                link_name: LinkName::Card("9883b420-f7be-468d-95f6-aaaaaaaaaaaa".parse().unwrap()),
+            }
+        );
+        assert_eq!(
+            parse("'import 'number 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa")
+                .unwrap()
+                .value,
+            Expr::Import {
+                ty: dummy_span(Type::Number),
+                link_name: LinkName::Version(
+                    "9883b420-f7be-468d-95f6-aaaaaaaaaaaa".parse().unwrap()
+                ),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_export() {
+        assert_eq!(
+            parse("'export 'number").unwrap().value,
+            Expr::Export {
+                ty: dummy_span(Type::Number),
+            }
+        );
+    }
+
     #[test]
     fn parse_product() {
         assert_eq!(
@@ -422,6 +525,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_unbrand() {
+        assert_eq!(
+            parse("'unbrand ?").unwrap().value,
+            Expr::Unbrand {
+                item: Box::new(dummy_span(Expr::Hole)),
+            }
+        );
+    }
+
     #[test]
     fn parse_match() {
         assert_eq!(
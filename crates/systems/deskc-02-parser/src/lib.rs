@@ -1,6 +1,6 @@
 use ast::{expr::Expr, span::WithSpan};
-use chumsky::prelude::Simple;
-use textual_diagnostics::{Report, TextualDiagnostics};
+use chumsky::{error::SimpleReason, prelude::Simple};
+use textual_diagnostics::{unexpected_text, Report, TextualDiagnostics};
 use thiserror::Error;
 use tokens::{Token, Tokens};
 
@@ -20,7 +20,7 @@ pub fn parse(input: Tokens) -> Result<WithSpan<Expr>, ParserError> {
         .map_err(ParserError)
 }
 
-#[derive(Error, Debug, PartialEq)]
+#[derive(Error, Clone, Debug, PartialEq)]
 #[error("{0:?}")]
 pub struct ParserError(pub Vec<Simple<Token>>);
 
@@ -28,14 +28,35 @@ impl From<ParserError> for TextualDiagnostics {
     fn from(error: ParserError) -> TextualDiagnostics {
         TextualDiagnostics {
             title: "Parser error".into(),
-            reports: error
-                .0
-                .into_iter()
-                .map(|error| Report {
-                    span: error.span(),
-                    text: format!("{:?}", error),
-                })
-                .collect(),
+            reports: error.0.into_iter().map(report).collect(),
         }
     }
 }
+
+fn report(error: Simple<Token>) -> Report {
+    match error.reason() {
+        SimpleReason::Unexpected => Report::error(
+            "E0201",
+            error.span(),
+            unexpected_text(
+                error.found().map(|token| format!("{:?}", token)),
+                error
+                    .expected()
+                    .map(|token| {
+                        token
+                            .as_ref()
+                            .map_or("end of input".into(), |token| format!("{:?}", token))
+                    })
+                    .collect(),
+            ),
+        ),
+        SimpleReason::Unclosed { span, delimiter } => Report::error(
+            "E0202",
+            error.span(),
+            format!("unclosed delimiter {:?}", delimiter),
+        )
+        .with_label(span.clone(), "opened here"),
+        // Invalid literals like a float which is not representable.
+        SimpleReason::Custom(text) => Report::error("E0203", error.span(), text),
+    }
+}
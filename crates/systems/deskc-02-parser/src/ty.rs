@@ -76,6 +76,14 @@ pub fn parser(
                 brand,
                 item: Box::new(ty),
             });
+        let brands = just(Token::Brands)
+            .ignore_then(parse_ident().separated_by_comma())
+            .in_()
+            .then(type_.clone())
+            .map(|(brands, ty)| Type::Brands {
+                brands,
+                item: Box::new(ty),
+            });
         let variable = just(Token::A)
             .or_not()
             .ignore_then(parse_ident())
@@ -118,6 +126,7 @@ pub fn parser(
             .or(set)
             .or(function)
             .or(brand)
+            .or(brands)
             .or(attribute)
             .or(bound)
             .or(variable)
@@ -348,6 +357,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_brands() {
+        assert_eq!(
+            parse("'brand added ~ @added 'number").unwrap().value,
+            Type::Brands {
+                brands: vec!["added".into()],
+                item: Box::new(dummy_span(Type::Brand {
+                    brand: "added".into(),
+                    item: Box::new(dummy_span(Type::Number)),
+                })),
+            }
+        );
+    }
+
     #[test]
     fn parse_attribute() {
         assert_eq!(
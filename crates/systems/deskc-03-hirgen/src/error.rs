@@ -1,15 +1,49 @@
-use ids::NodeId;
+use ast::span::Span;
+use textual_diagnostics::{Report, TextualDiagnostics};
 use thiserror::Error;
 use uuid::Uuid;
 
 #[derive(Clone, Debug, PartialEq, Eq, Error)]
 pub enum HirGenError {
     #[error("class expected")]
-    ClassExpected { span: NodeId },
+    ClassExpected { span: Span },
     #[error("unexpected class")]
-    UnexpectedClass { span: NodeId },
-    #[error("unknown type alias {alias} {span:?}")]
-    UnknownTypeAlias { alias: String, span: NodeId },
+    UnexpectedClass { span: Span },
+    #[error("unknown type alias {alias}")]
+    UnknownTypeAlias { alias: String, span: Span },
     #[error("unexpected card {ident}")]
-    UnexpectedCard { ident: Uuid },
+    UnexpectedCard { ident: Uuid, span: Span },
+    #[error("export must be declared as `$ 'export ty ~ expr`")]
+    UnexpectedExport { span: Span },
+}
+
+impl HirGenError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            HirGenError::ClassExpected { .. } => "E0301",
+            HirGenError::UnexpectedClass { .. } => "E0302",
+            HirGenError::UnknownTypeAlias { .. } => "E0303",
+            HirGenError::UnexpectedCard { .. } => "E0304",
+            HirGenError::UnexpectedExport { .. } => "E0305",
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            HirGenError::ClassExpected { span }
+            | HirGenError::UnexpectedClass { span }
+            | HirGenError::UnknownTypeAlias { span, .. }
+            | HirGenError::UnexpectedCard { span, .. }
+            | HirGenError::UnexpectedExport { span } => span.clone(),
+        }
+    }
+}
+
+impl From<HirGenError> for TextualDiagnostics {
+    fn from(error: HirGenError) -> TextualDiagnostics {
+        TextualDiagnostics {
+            title: "Hirgen error".into(),
+            reports: vec![Report::error(error.code(), error.span(), error.to_string())],
+        }
+    }
 }
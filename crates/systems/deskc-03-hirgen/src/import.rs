@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use ast::span::Span;
+use hir::{helper::remove_meta_ty, meta::WithMeta, ty::Type};
+use ids::{CardId, LinkName, NodeId};
+use textual_diagnostics::{Report, TextualDiagnostics};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// An `'import` in a card, which is lowered to a reference to the exporting card.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Import {
+    pub id: NodeId,
+    pub span: Span,
+    pub ty: Type,
+    pub link_name: LinkName,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ImportError {
+    #[error("no card exports {ty}")]
+    Unresolved { ty: Type, span: Span },
+    #[error("{} does not export {ty}", pinned(.link_name))]
+    NotExported {
+        ty: Type,
+        link_name: LinkName,
+        span: Span,
+    },
+    #[error("{ty} is exported by {} cards: {}", candidates.len(), join(candidates))]
+    Ambiguous {
+        ty: Type,
+        candidates: Vec<Uuid>,
+        span: Span,
+    },
+}
+
+impl ImportError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ImportError::Unresolved { .. } => "E0311",
+            ImportError::NotExported { .. } => "E0312",
+            ImportError::Ambiguous { .. } => "E0313",
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            ImportError::Unresolved { span, .. }
+            | ImportError::NotExported { span, .. }
+            | ImportError::Ambiguous { span, .. } => span.clone(),
+        }
+    }
+}
+
+fn pinned(link_name: &LinkName) -> String {
+    match link_name {
+        LinkName::None => "no card".into(),
+        LinkName::Card(uuid) => format!("card {}", uuid),
+        LinkName::Version(hash) => format!("card of hash {}", hash),
+    }
+}
+
+fn join(candidates: &[Uuid]) -> String {
+    candidates
+        .iter()
+        .map(Uuid::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Errors in the order of imports.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("{0:?}")]
+pub struct ImportErrors(pub Vec<ImportError>);
+
+impl From<ImportErrors> for TextualDiagnostics {
+    fn from(errors: ImportErrors) -> TextualDiagnostics {
+        TextualDiagnostics {
+            title: "Import error".into(),
+            reports: errors
+                .0
+                .into_iter()
+                .map(|error| {
+                    let report = Report::error(error.code(), error.span(), error.to_string());
+                    match error {
+                        // The import is pinned by the card after the type.
+                        ImportError::Ambiguous {
+                            candidates, span, ..
+                        } => candidates.iter().fold(report, |report, uuid| {
+                            report.with_suggestion(
+                                format!("import from card {}", uuid),
+                                span.end..span.end,
+                                format!(" 'card 'uuid {}", uuid),
+                            )
+                        }),
+                        _ => report,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Removes the meta from an exported or imported type to compare it by structure.
+pub fn normalize_type(ty: &WithMeta<Type>) -> Type {
+    remove_meta_ty(ty.clone()).value
+}
+
+/// Resolves imports to the cards which export the same type.
+///
+/// An import with `'card` is pinned to the card, and an import with `'uuid` is pinned to the cards
+/// of the hash, which is given by `hash` only for the cards exporting the type.
+/// Otherwise the exporting card must be unique.
+pub fn resolve_imports<'a>(
+    imports: &[Import],
+    exports: impl IntoIterator<Item = (&'a CardId, &'a [Type])>,
+    hash: impl Fn(&CardId) -> Option<Uuid>,
+) -> Result<HashMap<NodeId, LinkName>, ImportErrors> {
+    let exports: Vec<_> = exports.into_iter().collect();
+    let mut resolved = HashMap::new();
+    let mut errors = vec![];
+    for Import {
+        id,
+        span,
+        ty,
+        link_name,
+    } in imports
+    {
+        let mut candidates = exports
+            .iter()
+            .filter(|(card_id, _)| match link_name {
+                LinkName::Card(uuid) => card_id.0 == *uuid,
+                _ => true,
+            })
+            .filter(|(_, types)| types.contains(ty))
+            .filter(|(card_id, _)| match link_name {
+                LinkName::Version(pinned) => hash(card_id) == Some(*pinned),
+                _ => true,
+            })
+            .map(|(card_id, _)| card_id.0)
+            .collect::<Vec<_>>();
+        candidates.sort();
+        match (candidates.as_slice(), link_name) {
+            // Cards of the same hash are identical, so the hash is kept.
+            ([_, ..], LinkName::Version(_)) => {
+                resolved.insert(id.clone(), link_name.clone());
+            }
+            ([card], _) => {
+                resolved.insert(id.clone(), LinkName::Card(*card));
+            }
+            ([], LinkName::None) => errors.push(ImportError::Unresolved {
+                ty: ty.clone(),
+                span: span.clone(),
+            }),
+            ([], _) => errors.push(ImportError::NotExported {
+                ty: ty.clone(),
+                link_name: link_name.clone(),
+                span: span.clone(),
+            }),
+            _ => errors.push(ImportError::Ambiguous {
+                ty: ty.clone(),
+                candidates,
+                span: span.clone(),
+            }),
+        }
+    }
+    if errors.is_empty() {
+        Ok(resolved)
+    } else {
+        Err(ImportErrors(errors))
+    }
+}
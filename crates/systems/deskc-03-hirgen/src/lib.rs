@@ -1,6 +1,7 @@
-mod error;
+pub mod error;
 mod gen_effect_expr;
-use ids::{CardId, NodeId};
+pub mod import;
+use ids::{CardId, LinkName, NodeId};
 
 use std::{
     cell::RefCell,
@@ -15,6 +16,7 @@ use hir::{
     ty::Type,
     Hir,
 };
+use import::{normalize_type, Import};
 
 pub fn gen_cards(src: &WithSpan<ast::expr::Expr>) -> Result<(HirGen, Hir), HirGenError> {
     let mut hirgen = HirGen::default();
@@ -27,7 +29,20 @@ pub fn gen_cards(src: &WithSpan<ast::expr::Expr>) -> Result<(HirGen, Hir), HirGe
 }
 
 pub fn gen_hir(src: &WithSpan<ast::expr::Expr>) -> Result<(HirGen, WithMeta<Expr>), HirGenError> {
-    let hirgen = HirGen::default();
+    gen_hir_with_imports(src, HashMap::new())
+}
+
+/// Generates a card whose imports are linked to the resolved cards.
+///
+/// Exports are collected to `HirGen::exports` instead of being a part of the card.
+pub fn gen_hir_with_imports(
+    src: &WithSpan<ast::expr::Expr>,
+    resolved_imports: HashMap<NodeId, LinkName>,
+) -> Result<(HirGen, WithMeta<Expr>), HirGenError> {
+    let hirgen = HirGen {
+        resolved_imports,
+        ..Default::default()
+    };
     let card = hirgen.gen_card(src)?;
     Ok((hirgen, card))
 }
@@ -41,6 +56,10 @@ pub struct HirGen {
     brands: RefCell<HashSet<String>>,
     cards: Vec<(CardId, WithMeta<Expr>)>,
     entrypoint: Option<WithMeta<Expr>>,
+    pub imports: RefCell<Vec<Import>>,
+    /// Types which the card provides to importers.
+    pub exports: RefCell<Vec<WithMeta<Type>>>,
+    resolved_imports: HashMap<NodeId, LinkName>,
 }
 
 impl HirGen {
@@ -118,6 +137,14 @@ impl HirGen {
                     })
                 }
             }
+            ast::ty::Type::Brands { brands, item } => {
+                // Brands are declared only in the scope.
+                let outer = self.brands.borrow().clone();
+                self.brands.borrow_mut().extend(brands.iter().cloned());
+                let ret = self.gen_type(item);
+                *self.brands.borrow_mut() = outer;
+                ret?
+            }
             ast::ty::Type::Attribute { attr, ty } => {
                 self.pop_span();
                 let mut ret = self.gen_type(ty)?;
@@ -153,11 +180,20 @@ impl HirGen {
                 ty: variable,
                 definition,
                 body: expression,
-            } => self.with_meta(Expr::Let {
-                ty: self.gen_type(variable)?,
-                definition: Box::new(self.gen_card(definition)?),
-                expression: Box::new(self.gen_card(expression)?),
-            }),
+            } => match (&variable.value, &definition.value) {
+                // `$ 'export ty ~ expr` declares an export, so it's just the expression.
+                (ast::ty::Type::Infer, ast::expr::Expr::Export { ty }) => {
+                    self.pop_span();
+                    let ty = self.gen_type(ty)?;
+                    self.exports.borrow_mut().push(ty);
+                    self.gen_card(expression)?
+                }
+                _ => self.with_meta(Expr::Let {
+                    ty: self.gen_type(variable)?,
+                    definition: Box::new(self.gen_card(definition)?),
+                    expression: Box::new(self.gen_card(expression)?),
+                }),
+            },
             ast::expr::Expr::Perform { input, output } => self.with_meta(Expr::Perform {
                 input: Box::new(self.gen_card(input)?),
                 output: self.gen_type(output)?,
@@ -237,8 +273,29 @@ impl HirGen {
                     .map(|item| self.gen_card(item))
                     .collect::<Result<_, _>>()?,
             )),
-            ast::expr::Expr::Import { ty: _, uuid: _ } => todo!(),
-            ast::expr::Expr::Export { ty: _ } => todo!(),
+            ast::expr::Expr::Import { ty, link_name } => {
+                let function = self.gen_type(ty)?;
+                self.imports.borrow_mut().push(Import {
+                    id: id.clone(),
+                    span: span.clone(),
+                    ty: normalize_type(&function),
+                    link_name: link_name.clone(),
+                });
+                // Unresolved imports keep the pinned card or hash.
+                let link_name = self
+                    .resolved_imports
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_else(|| link_name.clone());
+                self.with_meta(Expr::Apply {
+                    function,
+                    link_name,
+                    arguments: vec![],
+                })
+            }
+            ast::expr::Expr::Export { .. } => {
+                return Err(HirGenError::UnexpectedExport { span: span.clone() });
+            }
             ast::expr::Expr::Attribute { attr, item: expr } => {
                 self.pop_span();
                 let mut ret = self.gen_card(expr)?;
@@ -250,10 +307,12 @@ impl HirGen {
                 ret
             }
             ast::expr::Expr::Brand { brands, item: expr } => {
-                brands.iter().for_each(|brand| {
-                    self.brands.borrow_mut().insert(brand.clone());
-                });
-                self.gen_card(expr)?
+                // Brands are declared only in the scope.
+                let outer = self.brands.borrow().clone();
+                self.brands.borrow_mut().extend(brands.iter().cloned());
+                let ret = self.gen_card(expr);
+                *self.brands.borrow_mut() = outer;
+                ret?
             }
             ast::expr::Expr::Match { of, cases } => self.with_meta(Expr::Match {
                 of: Box::new(self.gen_card(of)?),
@@ -280,6 +339,9 @@ impl HirGen {
                     })
                 }
             }
+            ast::expr::Expr::Unbrand { item } => self.with_meta(Expr::Unbrand {
+                item: Box::new(self.gen_card(item)?),
+            }),
             ast::expr::Expr::NewType { ident, ty, expr } => {
                 let ty = self.gen_type(ty)?.value;
                 self.type_aliases.borrow_mut().insert(ident.clone(), ty);
@@ -287,15 +349,46 @@ impl HirGen {
             }
             ast::expr::Expr::Comment { item, .. } => self.gen_card(item)?,
             ast::expr::Expr::Card { uuid, .. } => {
-                return Err(HirGenError::UnexpectedCard { ident: *uuid });
+                return Err(HirGenError::UnexpectedCard {
+                    ident: *uuid,
+                    span: span.clone(),
+                });
             }
         };
         Ok(with_meta)
     }
 
     pub fn gen_hir(&mut self, ast: &WithSpan<ast::expr::Expr>) -> Result<(), HirGenError> {
-        self.entrypoint = Some(self.gen_card(ast)?);
-        Ok(())
+        let mut ast = ast;
+        // Cards are chained by `next`, and the last expression which is not a card is the entrypoint.
+        loop {
+            match &ast.value {
+                ast::expr::Expr::Card { uuid, item, next } => {
+                    let card = self.gen_card(item)?;
+                    self.cards.push((CardId(*uuid), card));
+                    match next {
+                        Some(next) => ast = next,
+                        None => return Ok(()),
+                    }
+                }
+                // Type aliases before cards are shared by the following cards.
+                ast::expr::Expr::NewType { ident, ty, expr } => {
+                    let ty = self.gen_type(ty)?.value;
+                    self.type_aliases.borrow_mut().insert(ident.clone(), ty);
+                    ast = expr;
+                }
+                // So are brands.
+                ast::expr::Expr::Brand { brands, item } => {
+                    self.brands.borrow_mut().extend(brands.iter().cloned());
+                    ast = item;
+                }
+                ast::expr::Expr::Comment { item, .. } => ast = item,
+                _ => {
+                    self.entrypoint = Some(self.gen_card(ast)?);
+                    return Ok(());
+                }
+            }
+        }
     }
 
     pub(crate) fn push_span(&self, node_id: NodeId, span: Span) {
@@ -335,6 +428,7 @@ mod tests {
         ty::Type,
     };
     use pretty_assertions::assert_eq;
+    use uuid::Uuid;
 
     use super::*;
 
@@ -446,6 +540,64 @@ mod tests {
         )
     }
 
+    #[test]
+    fn brand_scope() {
+        let expr = parse(
+            r#"
+        $ 'brand brand ~ & @brand 'number ~
+        & @brand 'number
+        "#,
+        );
+
+        let gen = HirGen::default();
+        assert_eq!(
+            remove_meta(gen.gen_card(&expr).unwrap()),
+            dummy_meta(Expr::Let {
+                ty: dummy_meta(Type::Infer),
+                definition: Box::new(dummy_meta(Expr::Apply {
+                    function: dummy_meta(Type::Brand {
+                        brand: "brand".into(),
+                        item: Box::new(dummy_meta(Type::Number)),
+                    }),
+                    link_name: Default::default(),
+                    arguments: vec![],
+                })),
+                expression: Box::new(dummy_meta(Expr::Apply {
+                    function: dummy_meta(Type::Label {
+                        label: "brand".into(),
+                        item: Box::new(dummy_meta(Type::Number)),
+                    }),
+                    link_name: Default::default(),
+                    arguments: vec![],
+                }))
+            })
+        )
+    }
+
+    #[test]
+    fn brand_scope_in_type() {
+        let expr = parse("& * 'brand brand ~ @brand 'number, @brand 'string");
+
+        let gen = HirGen::default();
+        assert_eq!(
+            remove_meta(gen.gen_card(&expr).unwrap()),
+            dummy_meta(Expr::Apply {
+                function: dummy_meta(Type::Product(vec![
+                    dummy_meta(Type::Brand {
+                        brand: "brand".into(),
+                        item: Box::new(dummy_meta(Type::Number)),
+                    }),
+                    dummy_meta(Type::Label {
+                        label: "brand".into(),
+                        item: Box::new(dummy_meta(Type::String)),
+                    }),
+                ])),
+                link_name: Default::default(),
+                arguments: vec![],
+            })
+        )
+    }
+
     #[test]
     fn gen_entrypoint() {
         let expr = parse(
@@ -460,4 +612,169 @@ mod tests {
             dummy_meta(Expr::Literal(Literal::Integer(1)))
         );
     }
+
+    #[test]
+    fn gen_cards_with_entrypoint() {
+        let expr = parse(
+            r#"
+        'type number 'number ~
+        'card 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa
+        1
+        ~
+        'card 'uuid 9883b420-f7be-468d-95f6-bbbbbbbbbbbb
+        &'number
+        ~
+        2
+        "#,
+        );
+        let (_, hir) = gen_cards(&expr).unwrap();
+        assert_eq!(
+            hir.cards
+                .into_iter()
+                .map(|(id, card)| (id, remove_meta(card)))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    CardId("9883b420-f7be-468d-95f6-aaaaaaaaaaaa".parse().unwrap()),
+                    dummy_meta(Expr::Literal(Literal::Integer(1)))
+                ),
+                (
+                    CardId("9883b420-f7be-468d-95f6-bbbbbbbbbbbb".parse().unwrap()),
+                    dummy_meta(Expr::Apply {
+                        function: dummy_meta(Type::Number),
+                        link_name: Default::default(),
+                        arguments: vec![],
+                    })
+                ),
+            ]
+        );
+        assert_eq!(
+            remove_meta(hir.entrypoint.unwrap()),
+            dummy_meta(Expr::Literal(Literal::Integer(2)))
+        );
+    }
+
+    #[test]
+    fn gen_hir_with_imports_and_exports() {
+        let expr = parse("$ 'export 'number ~ 'import 'string");
+        let import_id = match &expr.value {
+            ast::expr::Expr::Let { body, .. } => body.id.clone(),
+            _ => unreachable!(),
+        };
+        let card = Uuid::from_u128(1);
+        let (gen, hir) = gen_hir_with_imports(
+            &expr,
+            [(import_id.clone(), LinkName::Card(card))]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap();
+        assert_eq!(
+            remove_meta(hir),
+            dummy_meta(Expr::Apply {
+                function: dummy_meta(Type::String),
+                link_name: LinkName::Card(card),
+                arguments: vec![],
+            })
+        );
+        assert_eq!(
+            gen.exports
+                .borrow()
+                .iter()
+                .map(normalize_type)
+                .collect::<Vec<_>>(),
+            vec![Type::Number]
+        );
+        assert_eq!(
+            gen_hir(&parse("'export 'number")).unwrap_err(),
+            HirGenError::UnexpectedExport { span: 0..15 }
+        );
+        assert_eq!(
+            gen.imports.take(),
+            vec![Import {
+                id: import_id,
+                span: 20..35,
+                ty: Type::String,
+                link_name: LinkName::None,
+            }]
+        );
+    }
+
+    #[test]
+    fn resolves_imports() {
+        let a = CardId(Uuid::from_u128(1));
+        let b = CardId(Uuid::from_u128(2));
+        let import = |ty, link_name| Import {
+            id: NodeId::default(),
+            span: 0..0,
+            ty,
+            link_name,
+        };
+        let exports = [
+            (a.clone(), vec![Type::Number, Type::String]),
+            (b.clone(), vec![Type::Number]),
+        ];
+        let hash = |card_id: &CardId| Some(Uuid::from_u128(card_id.0.as_u128() + 100));
+        let resolve = |import: Import| {
+            import::resolve_imports(
+                &[import],
+                exports.iter().map(|(id, types)| (id, types.as_slice())),
+                hash,
+            )
+        };
+        assert_eq!(
+            resolve(import(Type::String, LinkName::None)).unwrap()[&NodeId::default()],
+            LinkName::Card(a.0)
+        );
+        assert_eq!(
+            resolve(import(Type::Number, LinkName::Card(b.0))).unwrap()[&NodeId::default()],
+            LinkName::Card(b.0)
+        );
+        assert_eq!(
+            resolve(import(Type::Number, LinkName::None)).unwrap_err().0,
+            vec![import::ImportError::Ambiguous {
+                ty: Type::Number,
+                candidates: vec![a.0, b.0],
+                span: 0..0,
+            }]
+        );
+        assert_eq!(
+            resolve(import(Type::String, LinkName::Card(b.0)))
+                .unwrap_err()
+                .0,
+            vec![import::ImportError::NotExported {
+                ty: Type::String,
+                link_name: LinkName::Card(b.0),
+                span: 0..0,
+            }]
+        );
+        assert_eq!(
+            resolve(import(
+                Type::Vector(Box::new(dummy_meta(Type::Number))),
+                LinkName::None
+            ))
+            .unwrap_err()
+            .0,
+            vec![import::ImportError::Unresolved {
+                ty: Type::Vector(Box::new(dummy_meta(Type::Number))),
+                span: 0..0,
+            }]
+        );
+        let hash_of_a = LinkName::Version(Uuid::from_u128(101));
+        assert_eq!(
+            resolve(import(Type::String, hash_of_a.clone())).unwrap()[&NodeId::default()],
+            hash_of_a
+        );
+        let hash_of_b = LinkName::Version(Uuid::from_u128(102));
+        assert_eq!(
+            resolve(import(Type::String, hash_of_b.clone()))
+                .unwrap_err()
+                .0,
+            vec![import::ImportError::NotExported {
+                ty: Type::String,
+                link_name: hash_of_b,
+                span: 0..0,
+            }]
+        );
+    }
 }
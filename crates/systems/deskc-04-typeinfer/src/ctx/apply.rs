@@ -18,11 +18,13 @@ impl Ctx {
                 let delta = self.check(expr, parameter)?.recover_effects();
                 // if a type of expr is synthed, output can be substituded with the type.
                 delta
+                    .without_recovery()
                     .synth(expr)
                     .ok()
                     .map(|with| with.recover_effects())
                     .and_then(|(ctx, ty)| {
-                        ctx.subtype(&ty, parameter).ok().map(|ctx| {
+                        ctx.subtype(&ty, parameter).ok().map(|mut ctx| {
+                            ctx.recovery = self.recovery;
                             let ty = ctx.substitute_from_ctx(body);
                             (ctx, ty)
                         })
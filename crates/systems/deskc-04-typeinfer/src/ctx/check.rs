@@ -15,23 +15,65 @@ impl Ctx {
         ty: &Type,
     ) -> Result<WithEffects<Ctx>, ExprTypeError> {
         let scope = self.begin_scope();
+        let (ctx, synthed_ty) = match self.check_expr(expr, ty) {
+            Ok(ctx_synthed) => ctx_synthed,
+            Err(error) => (self.recover(error)?, Some(Type::Error)),
+        };
+        let effects = ctx.end_scope(scope);
+        let ty = synthed_ty.as_ref().unwrap_or(ty);
+        ctx.store_type_and_effects(
+            expr.id.clone(),
+            ctx.substitute_from_ctx(ty),
+            effects.clone(),
+        );
+        Ok(WithEffects(ctx, effects))
+    }
+
+    // Returns the synthesized type if it's synthesized.
+    fn check_expr(
+        &self,
+        expr: &WithMeta<Expr>,
+        ty: &Type,
+    ) -> Result<(Ctx, Option<Type>), ExprTypeError> {
         let mut synthed_ty = None;
         let ctx = match (&expr.value, ty) {
+            // A subtree typed in advance is checked by its type.
+            (_, _) if self.subtrees.contains_key(&expr.id) => {
+                let (ctx, synthed) = self.synth(expr)?.recover_effects();
+                synthed_ty = Some(synthed.clone());
+                ctx.subtype(&synthed, &ctx.substitute_from_ctx(ty))
+                    .map_err(|error| to_expr_type_error(expr, error))?
+            }
             (Expr::Literal(Literal::Integer(_)), Type::Number) => self.clone(),
             (Expr::Literal(Literal::Float(_)), Type::Number) => self.clone(),
             (Expr::Literal(Literal::Rational(_, _)), Type::Number) => self.clone(),
             (Expr::Literal(Literal::String(_)), Type::String) => self.clone(),
+            (Expr::Literal(Literal::Hole), _) => {
+                self.add_hole(expr.id.clone(), expr.meta.clone(), ty.clone());
+                self.clone()
+            }
+            // A named parameter is typed by the checked type, so that a signature can be polymorphic.
             (
                 Expr::Function {
-                    parameter: _,
-                    body: _,
+                    parameter:
+                        WithMeta {
+                            value: hir::ty::Type::Variable(var),
+                            ..
+                        },
+                    body,
                 },
                 Type::Function {
-                    parameter: _ty_parameter,
-                    body: _ty_body,
+                    parameter: ty_parameter,
+                    body: ty_body,
                 },
-            ) => {
-                todo!()
+            ) if !matches!(**ty_body, Type::Effectful { .. }) => {
+                let var_id = self.get_id_of(var.clone());
+                let typed = Log::TypedVariable(var_id, *ty_parameter.clone());
+                self.add(typed.clone())
+                    .check(body, ty_body)?
+                    .recover_effects()
+                    .truncate_from(&typed)
+                    .recover_effects()
             }
             (_, Type::ForAll { variable, body }) => self
                 .add(Log::Variable(*variable))
@@ -49,13 +91,6 @@ impl Ctx {
                 .map_err(|error| to_expr_type_error(expr, error))?
             }
         };
-        let effects = ctx.end_scope(scope);
-        let ty = synthed_ty.as_ref().unwrap_or(ty);
-        ctx.store_type_and_effects(
-            expr.id.clone(),
-            ctx.substitute_from_ctx(ty),
-            effects.clone(),
-        );
-        Ok(WithEffects(ctx, effects))
+        Ok((ctx, synthed_ty))
     }
 }
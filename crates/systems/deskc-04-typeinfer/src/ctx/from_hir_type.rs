@@ -11,13 +11,19 @@ impl Ctx {
         match &ty.value {
             Number => Type::Number,
             String => Type::String,
-            Trait(_types) => todo!(),
+            Trait(types) => {
+                let mut types: Vec<_> = types.iter().map(|t| self.gen_from_hir_type(t)).collect();
+                // Sort is required to make traits comparable.
+                types.sort();
+                types.dedup();
+                Type::Trait(types)
+            }
             Effectful { ty, effects } => self.with_effects(
                 self.gen_from_hir_type(ty),
                 self.gen_from_hir_effect_expr(effects),
             ),
             Infer => Type::Infer(ty.id.clone()),
-            This => todo!(),
+            This => Type::This,
             Product(types) => {
                 Type::Product(types.iter().map(|t| self.gen_from_hir_type(t)).collect())
             }
@@ -36,10 +42,11 @@ impl Ctx {
                 body: Box::new(self.gen_from_hir_type(body)),
             },
             Variable(id) => Type::Variable(self.get_id_of(id.clone())),
+            // The bound is not checked yet, so it's just the variable bound by the quantifier.
             BoundedVariable {
                 bound: _,
-                identifier: _,
-            } => todo!(),
+                identifier,
+            } => Type::Variable(self.get_id_of(identifier.clone())),
             Brand { brand, item } => Type::Brand {
                 brand: brand.clone(),
                 item: Box::new(self.gen_from_hir_type(item)),
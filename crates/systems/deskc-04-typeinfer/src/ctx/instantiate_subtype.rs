@@ -45,10 +45,18 @@ impl Ctx {
                     .instantiate_subtype(id, body)?
                     .truncate_from(&Log::Variable(*variable))
                     .recover_effects(),
-                Type::Existential(b) => self.insert_in_place(
-                    &Log::Existential(*b),
-                    vec![Log::Solved(*b, Type::Existential(*id))],
-                ),
+                Type::Existential(b) => {
+                    // The other one is solved, so it's stored instead.
+                    self.store_solved_type_and_effects(
+                        *b,
+                        Type::Existential(*id),
+                        EffectExpr::Effects(vec![]),
+                    );
+                    return Ok(self.insert_in_place(
+                        &Log::Existential(*b),
+                        vec![Log::Solved(*b, Type::Existential(*id))],
+                    ));
+                }
                 Type::Product(types) => self.instantiate_composite_type_vec(
                     *id,
                     types,
@@ -49,12 +49,16 @@ impl Ctx {
                 label: label.clone(),
                 item: Box::new(self.gen_type(item)),
             },
-            Type::Existential(id) => self.gen_type(
-                self.types
-                    .borrow()
-                    .get(id)
-                    .unwrap_or_else(|| panic!("should be instansiated: {}", id)),
-            ),
+            // An existential is left unsolved if nothing constrains it, for example the type of a hole.
+            Type::Existential(id) => match self.types.borrow().get(id) {
+                Some(ty) => self.gen_type(ty),
+                None => types::Type::Variable(self.get_ident_of(*id)),
+            },
+            Type::Trait(types) => {
+                types::Type::trait_(types.iter().map(|t| self.gen_type(t)).collect())
+            }
+            Type::This => types::Type::This,
+            Type::Error => types::Type::Error,
             Type::Infer(id) => {
                 self.gen_type(self.ir_types.borrow().get(id).expect("should be inferred"))
             }
@@ -92,7 +96,7 @@ impl Ctx {
         }
     }
 
-    fn get_ident_of(&self, id: Id) -> String {
+    pub(crate) fn get_ident_of(&self, id: Id) -> String {
         self.variables_idents
             .borrow_mut()
             .entry(id)
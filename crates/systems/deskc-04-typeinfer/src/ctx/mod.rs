@@ -9,13 +9,15 @@ mod synth;
 
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use hir::meta::WithMeta;
+use hir::{expr::Expr, meta::WithMeta};
 use ids::NodeId;
 use types::{IdGen, Types};
 
 use crate::{
-    error::TypeError,
+    error::{ExprTypeError, TypeError},
+    hole::HoleLog,
     substitute_from_ctx::SubstituteFromCtx,
+    subtree::SubtreeTypes,
     ty::{
         effect_expr::{simplify, simplify_effect_expr, EffectExpr},
         Type, TypeVisitor, TypeVisitorMut,
@@ -44,13 +46,23 @@ pub struct Ctx {
     // Result of type inference
     pub(crate) ir_types: Rc<RefCell<HashMap<NodeId, Type>>>,
     pub(crate) types: Rc<RefCell<HashMap<Id, Type>>>,
+    // Parameter types to which arguments are applied
+    pub(crate) parameters: Rc<RefCell<HashMap<NodeId, Type>>>,
     // a stack; continue's input of current context
     pub(crate) continue_input: RefCell<Vec<Type>>,
     // a stack; continue's output of current context
     pub(crate) continue_output: RefCell<Vec<Type>>,
+    // a stack; types of values defined in the current context, which can implement traits
+    pub(crate) definitions: RefCell<Vec<Type>>,
     pub(crate) inferred_types: RefCell<HashMap<NodeId, Type>>,
     pub(crate) variables_ids: RefCell<HashMap<String, usize>>,
     pub(crate) variables_idents: RefCell<HashMap<usize, String>>,
+    pub(crate) holes: Rc<RefCell<Vec<HoleLog>>>,
+    // If true, errors are collected instead of being returned.
+    pub(crate) recovery: bool,
+    pub(crate) errors: Rc<RefCell<Vec<ExprTypeError>>>,
+    // Subtrees typed in advance, which are not typed again.
+    pub(crate) subtrees: Rc<HashMap<NodeId, SubtreeTypes>>,
 }
 
 impl Ctx {
@@ -63,11 +75,17 @@ impl Ctx {
             logs: Default::default(),
             ir_types: self.ir_types.clone(),
             types: self.types.clone(),
+            parameters: self.parameters.clone(),
             continue_input: Default::default(),
             continue_output: Default::default(),
+            definitions: Default::default(),
             inferred_types: Default::default(),
             variables_idents: Default::default(),
             variables_ids: Default::default(),
+            holes: self.holes.clone(),
+            recovery: self.recovery,
+            errors: self.errors.clone(),
+            subtrees: self.subtrees.clone(),
         }
     }
 
@@ -88,6 +106,28 @@ impl Ctx {
         self.inferred_types.borrow_mut().insert(infer, ty);
     }
 
+    // Arguments are applied to the curried parameters in order.
+    fn store_parameters(&self, function: &Type, arguments: &[WithMeta<Expr>]) {
+        let mut ty = function;
+        for argument in arguments {
+            while let Type::ForAll { body: inner, .. }
+            | Type::Effectful { ty: inner, .. }
+            | Type::Label { item: inner, .. }
+            | Type::Brand { item: inner, .. } = ty
+            {
+                ty = inner;
+            }
+            if let Type::Function { parameter, body } = ty {
+                self.parameters
+                    .borrow_mut()
+                    .insert(argument.id.clone(), *parameter.clone());
+                ty = body;
+            } else {
+                return;
+            }
+        }
+    }
+
     pub fn get_type(&self, id: &NodeId) -> Type {
         self.finalize(
             &self
@@ -105,7 +145,7 @@ impl Ctx {
         ty
     }
 
-    fn save_from_hir_type(&self, hir_ty: &WithMeta<hir::ty::Type>) -> Type {
+    pub(crate) fn save_from_hir_type(&self, hir_ty: &WithMeta<hir::ty::Type>) -> Type {
         let ty = self.gen_from_hir_type(hir_ty);
         let ty = self.substitute_from_ctx(&ty);
         self.store_type_and_effects(hir_ty.id.clone(), ty.clone(), EffectExpr::Effects(vec![]));
@@ -119,10 +159,26 @@ impl Ctx {
     pub fn get_types(&self) -> Types {
         Types {
             types: self
-                .ir_types
-                .borrow()
-                .iter()
-                .map(|(id, ty)| (id.clone(), self.gen_type(ty)))
+                .subtrees
+                .values()
+                .flat_map(|subtree| subtree.types.types.clone())
+                .chain(
+                    self.ir_types
+                        .borrow()
+                        .iter()
+                        .map(|(id, ty)| (id.clone(), self.gen_type(ty))),
+                )
+                .collect(),
+            parameters: self
+                .subtrees
+                .values()
+                .flat_map(|subtree| subtree.types.parameters.clone())
+                .chain(
+                    self.parameters
+                        .borrow()
+                        .iter()
+                        .map(|(id, ty)| (id.clone(), self.gen_type(ty))),
+                )
                 .collect(),
         }
     }
@@ -156,6 +212,24 @@ impl Ctx {
         self.id_gen.borrow_mut().next_id()
     }
 
+    // The expression failed to be typed is treated as an error type if the error recovery is enabled.
+    fn recover(&self, error: ExprTypeError) -> Result<Ctx, ExprTypeError> {
+        if self.recovery {
+            self.errors.borrow_mut().push(error);
+            Ok(self.clone())
+        } else {
+            Err(error)
+        }
+    }
+
+    // Errors in a speculative inference must not be collected.
+    fn without_recovery(&self) -> Ctx {
+        Ctx {
+            recovery: false,
+            ..self.clone()
+        }
+    }
+
     fn with_type(self, ty: Type) -> (Self, Type) {
         (self, ty)
     }
@@ -239,7 +313,9 @@ impl Ctx {
                 }
             })
             .cloned()
-            .ok_or(TypeError::VariableNotTyped { id: *id })
+            .ok_or_else(|| TypeError::VariableNotTyped {
+                ident: self.get_ident_of(*id),
+            })
     }
 
     fn is_well_formed(&self, ty: &Type) -> bool {
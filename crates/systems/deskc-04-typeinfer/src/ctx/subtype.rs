@@ -3,6 +3,7 @@ use crate::{
     ctx::Log,
     error::TypeError,
     occurs_in::occurs_in,
+    replace_this::replace_this,
     substitute::substitute,
     ty::{effect_expr::EffectExpr, Type},
 };
@@ -14,12 +15,14 @@ impl Ctx {
                 Ok(self.clone())
             } else {
                 Err(TypeError::NotSubtype {
-                    sub: sub.clone(),
-                    ty: ty.clone(),
+                    sub: Box::new(sub.clone()),
+                    ty: Box::new(ty.clone()),
                 })
             }
         };
         let ctx = match (sub, ty) {
+            // The error is already reported.
+            (Type::Error, _) | (_, Type::Error) => self.clone(),
             (Type::Variable(id), Type::Variable(id2)) if id == id2 => self.clone(),
             (Type::Number, Type::Number) => self.clone(),
             (Type::String, Type::String) => self.clone(),
@@ -45,6 +48,29 @@ impl Ctx {
                 }
             }
 
+            (Type::This, Type::This) => self.clone(),
+            // A trait with more functions is a subtype of one with less functions.
+            (Type::Trait(sub_types), Type::Trait(types)) => {
+                subtype_if(types.iter().all(|ty| sub_types.contains(ty)))?
+            }
+            // A type implements a trait if a function of each signature is defined.
+            (sub, Type::Trait(types)) => {
+                for ty in types {
+                    let implementation = self.finalize(&replace_this(ty, sub));
+                    if !self
+                        .definitions
+                        .borrow()
+                        .iter()
+                        .any(|defined| self.finalize(defined) == implementation)
+                    {
+                        return Err(TypeError::MissingImplementation {
+                            implementation: Box::new(implementation),
+                        });
+                    }
+                }
+                self.clone()
+            }
+
             // handling things must be under the instantiations of existential.
             (Type::Product(sub_types), Type::Product(types)) => {
                 if sub_types
@@ -54,8 +80,8 @@ impl Ctx {
                     self.clone()
                 } else {
                     return Err(TypeError::NotSubtype {
-                        sub: sub.clone(),
-                        ty: ty.clone(),
+                        sub: Box::new(sub.clone()),
+                        ty: Box::new(ty.clone()),
                     });
                 }
             }
@@ -67,8 +93,8 @@ impl Ctx {
                     Err(_) => None,
                 })
                 .ok_or(TypeError::NotSubtype {
-                    sub: sub.clone(),
-                    ty: ty.clone(),
+                    sub: Box::new(sub.clone()),
+                    ty: Box::new(ty.clone()),
                 })?,
             (Type::Sum(sub_types), Type::Sum(types)) => {
                 if types.iter().all(|ty| {
@@ -79,8 +105,8 @@ impl Ctx {
                     self.clone()
                 } else {
                     return Err(TypeError::NotSubtype {
-                        sub: sub.clone(),
-                        ty: ty.clone(),
+                        sub: Box::new(sub.clone()),
+                        ty: Box::new(ty.clone()),
                     });
                 }
             }
@@ -92,8 +118,8 @@ impl Ctx {
                     Err(_) => None,
                 })
                 .ok_or(TypeError::NotSubtype {
-                    sub: sub.clone(),
-                    ty: ty.clone(),
+                    sub: Box::new(sub.clone()),
+                    ty: Box::new(ty.clone()),
                 })?,
             (
                 Type::Function {
@@ -125,9 +151,16 @@ impl Ctx {
                 .truncate_from(&Log::Variable(*variable))
                 .recover_effects(),
 
+            // Brands are nominal, so only the same brand is a subtype.
+            (
+                Type::Brand {
+                    brand: sub_brand,
+                    item: sub_item,
+                },
+                Type::Brand { brand, item },
+            ) if sub_brand == brand => self.subtype(sub_item, item)?,
             (sub, Type::Label { item, label: _ }) => self.subtype(sub, item)?,
             (Type::Label { item, label: _ }, sup) => self.subtype(item, sup)?,
-            (Type::Brand { item, brand: _ }, sup) => self.subtype(item, sup)?,
             // one without brand is not subtype of other with brand
             (Type::Infer(id), sup) => {
                 self.store_inferred_type(id.clone(), sup.clone());
@@ -158,8 +191,8 @@ impl Ctx {
             (sub, Type::Effectful { ty, effects: _ }) => self.subtype(sub, ty)?,
             (_, _) => {
                 return Err(TypeError::NotSubtype {
-                    sub: sub.clone(),
-                    ty: ty.clone(),
+                    sub: Box::new(sub.clone()),
+                    ty: Box::new(ty.clone()),
                 })
             }
         };
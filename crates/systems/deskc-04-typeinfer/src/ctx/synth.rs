@@ -15,13 +15,34 @@ use crate::{
 
 impl Ctx {
     pub fn synth(&self, expr: &WithMeta<Expr>) -> Result<WithEffects<(Ctx, Type)>, ExprTypeError> {
+        if let Some(subtree) = self.subtrees.get(&expr.id) {
+            let (ty, effects) = subtree.ty_and_effects();
+            self.store_type_and_effects(expr.id.clone(), ty.clone(), effects.clone());
+            return Ok(WithEffects((self.clone(), ty), effects));
+        }
         let scope = self.begin_scope();
-        let (ctx, ty) = match &expr.value {
+        let (ctx, ty) = match self.synth_expr(expr) {
+            Ok(ctx_ty) => ctx_ty,
+            Err(error) => self.recover(error)?.with_type(Type::Error),
+        };
+        let effects = ctx.end_scope(scope);
+        let ty = ctx.substitute_from_ctx(&ty);
+        ctx.store_type_and_effects(expr.id.clone(), ty.clone(), effects.clone());
+        Ok(WithEffects((ctx, ty), effects))
+    }
+
+    fn synth_expr(&self, expr: &WithMeta<Expr>) -> Result<(Ctx, Type), ExprTypeError> {
+        let ctx_ty = match &expr.value {
             Expr::Literal(Literal::Integer(_)) => (self.clone(), Type::Number),
             Expr::Literal(Literal::Float(_)) => (self.clone(), Type::Number),
             Expr::Literal(Literal::Rational(_, _)) => (self.clone(), Type::Number),
             Expr::Literal(Literal::String(_)) => (self.clone(), Type::String),
-            Expr::Literal(Literal::Hole) => todo!(),
+            Expr::Literal(Literal::Hole) => {
+                let a = self.fresh_existential();
+                self.add_hole(expr.id.clone(), expr.meta.clone(), Type::Existential(a));
+                self.add(Log::Existential(a))
+                    .with_type(Type::Existential(a))
+            }
             Expr::Let {
                 ty,
                 definition,
@@ -42,8 +63,10 @@ impl Ctx {
                     ctx.insert_in_place(&Log::TypedVariable(var_id, def_ty), vec![])
                         .with_type(ty)
                 } else {
-                    let (ctx, _def_ty) = self.synth(definition)?.recover_effects();
+                    let (ctx, def_ty) = self.synth(definition)?.recover_effects();
+                    ctx.definitions.borrow_mut().push(def_ty);
                     let (ctx, ty) = ctx.synth(expression)?.recover_effects();
+                    ctx.definitions.borrow_mut().pop();
                     ctx.with_type(ty)
                 }
             }
@@ -190,30 +213,33 @@ impl Ctx {
                 link_name: _,
                 arguments,
             } => {
+                // A variable is resolved by the name because its type may be a type variable.
+                let fun = match &function.value {
+                    hir::ty::Type::Variable(var) => {
+                        let ty = self
+                            .get_typed_var(&self.get_id_of(var.clone()))
+                            .map_err(|error| to_expr_type_error(expr, error))?;
+                        self.save_from_hir_type(function);
+                        ty
+                    }
+                    _ => self.save_from_hir_type(function),
+                };
                 if arguments.is_empty() {
                     // Reference
-                    let fun = self.save_from_hir_type(function);
-                    if let Type::Variable(id) = fun {
-                        self.clone().with_type(
-                            self.get_typed_var(&id)
-                                .map_err(|error| to_expr_type_error(expr, error))?,
-                        )
-                    } else {
-                        self.clone().with_type(fun)
-                    }
+                    self.clone().with_type(fun)
                 } else {
                     // Normal application
-                    let fun = match self.save_from_hir_type(function) {
-                        Type::Variable(var) => self
-                            .get_typed_var(&var)
-                            .map_err(|error| to_expr_type_error(expr, error))?,
-                        ty => ty,
-                    };
+                    self.store_parameters(&fun, arguments);
                     let (ctx, ty) = arguments
                         .iter()
                         .try_fold((self.clone(), fun.clone()), |(ctx, fun), arg| {
                             ctx.apply(&fun, arg)
                         })?;
+                    // Effects of the body are performed by the application effect below.
+                    let ty = match ty {
+                        Type::Effectful { ty, effects: _ } => *ty,
+                        ty => ty,
+                    };
 
                     ctx.add_effects(&EffectExpr::Apply {
                         function: Box::new(fun),
@@ -253,9 +279,13 @@ impl Ctx {
                         body: Box::new(self.with_effects(Type::Existential(b), effects)),
                     })
                 } else {
-                    let (ctx, ty) = self.synth(body)?.recover_effects();
+                    let parameter = self.save_from_hir_type(parameter);
+                    let ctx = self.clone();
+                    ctx.definitions.borrow_mut().push(parameter.clone());
+                    let (ctx, ty) = ctx.synth(body)?.recover_effects();
+                    ctx.definitions.borrow_mut().pop();
                     ctx.with_type(Type::Function {
-                        parameter: Box::new(self.save_from_hir_type(parameter)),
+                        parameter: Box::new(parameter),
                         body: Box::new(ty),
                     })
                 }
@@ -269,7 +299,7 @@ impl Ctx {
                         types.push(ty);
                         Ok(ctx)
                     })?
-                    .with_type(Type::Vector(Box::new(Type::Sum(types))))
+                    .with_type(Type::Vector(Box::new(item_type(types))))
             }
             Expr::Set(values) => {
                 let mut types = vec![];
@@ -280,7 +310,7 @@ impl Ctx {
                         types.push(ty);
                         Ok(ctx)
                     })?
-                    .with_type(Type::Set(Box::new(Type::Sum(types))))
+                    .with_type(Type::Set(Box::new(item_type(types))))
             }
             Expr::Match { of, cases } => {
                 let (ty, out): (Vec<_>, Vec<_>) = cases
@@ -296,7 +326,10 @@ impl Ctx {
                     .unzip();
                 let ty = sum_all(self, ty);
                 let out = sum_all(self, out);
-                self.check(of, &ty)?.recover_effects().with_type(out)
+                let (ctx, of_ty) = self.synth(of)?.recover_effects();
+                ctx.subtype(&of_ty, &ty)
+                    .map_err(|error| to_expr_type_error(expr, error))?
+                    .with_type(out)
             }
             Expr::Label { label, item: body } => {
                 let (ctx, ty) = self.synth(body)?.recover_effects();
@@ -312,10 +345,30 @@ impl Ctx {
                     item: Box::new(ty),
                 })
             }
+            Expr::Unbrand { item } => {
+                let (ctx, ty) = self.synth(item)?.recover_effects();
+                match ty {
+                    Type::Brand { item, .. } => ctx.with_type(*item),
+                    Type::Error => ctx.with_type(Type::Error),
+                    ty => return Err(to_expr_type_error(expr, TypeError::NotBranded { ty })),
+                }
+            }
         };
-        let effects = ctx.end_scope(scope);
-        let ty = ctx.substitute_from_ctx(&ty);
-        ctx.store_type_and_effects(expr.id.clone(), ty.clone(), effects.clone());
-        Ok(WithEffects((ctx, ty), effects))
+        Ok(ctx_ty)
+    }
+}
+
+// Items of the same type make a collection of the type instead of a sum of duplicates.
+fn item_type(types: Vec<Type>) -> Type {
+    let mut items = vec![];
+    for ty in types {
+        if !items.contains(&ty) {
+            items.push(ty);
+        }
+    }
+    if items.len() == 1 {
+        items.pop().unwrap()
+    } else {
+        Type::Sum(items)
     }
 }
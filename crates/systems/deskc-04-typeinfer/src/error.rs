@@ -5,7 +5,7 @@ use hir::{expr::Expr, meta::Meta};
 use textual_diagnostics::{Report, TextualDiagnostics};
 use thiserror::Error;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ExprTypeError {
     pub meta: Meta,
     pub error: TypeError,
@@ -23,34 +23,73 @@ impl std::error::Error for ExprTypeError {
     }
 }
 
-#[derive(Error, Debug, PartialEq)]
+#[derive(Error, Clone, Debug, PartialEq)]
 pub enum TypeError {
-    #[error("not applicable")]
+    #[error("{ty} is not applicable")]
     NotApplicable { expr: Box<Expr>, ty: Type },
-    #[error("not subtype")]
-    NotSubtype { sub: Type, ty: Type },
-    #[error("circular existential")]
+    #[error("{sub} is not a subtype of {ty}")]
+    NotSubtype { sub: Box<Type>, ty: Box<Type> },
+    #[error("_{id} occurs in {ty}")]
     CircularExistential { id: Id, ty: Type },
-    #[error("not instantiable subtype")]
+    #[error("{ty} is not instantiable as a subtype")]
     NotInstantiableSubtype { ty: Type },
-    #[error("not instantiable supertype")]
+    #[error("{ty} is not instantiable as a supertype")]
     NotInstantiableSupertype { ty: Type },
-    #[error("variable not typed {id}")]
-    VariableNotTyped { id: Id },
-    #[error("unknown effect handled: {effect:?}")]
-    UnknownEffectHandled { effect: Effect },
+    #[error("variable {ident} is not typed")]
+    VariableNotTyped { ident: String },
+    #[error("unknown effect handled: {effect}")]
+    UnknownEffectHandled { effect: Box<Effect> },
     #[error("continue out of handle")]
     ContinueOutOfHandle,
+    #[error("missing implementation {implementation}")]
+    MissingImplementation { implementation: Box<Type> },
+    #[error("{ty} is not branded")]
+    NotBranded { ty: Type },
+}
+
+impl TypeError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeError::NotApplicable { .. } => "E0401",
+            TypeError::NotSubtype { .. } => "E0402",
+            TypeError::CircularExistential { .. } => "E0403",
+            TypeError::NotInstantiableSubtype { .. } => "E0404",
+            TypeError::NotInstantiableSupertype { .. } => "E0405",
+            TypeError::VariableNotTyped { .. } => "E0406",
+            TypeError::UnknownEffectHandled { .. } => "E0407",
+            TypeError::ContinueOutOfHandle => "E0408",
+            TypeError::MissingImplementation { .. } => "E0409",
+            TypeError::NotBranded { .. } => "E0410",
+        }
+    }
+}
+
+/// Errors in the order of occurrence.
+#[derive(Clone, Debug, PartialEq, Default, Error)]
+#[error("{0:?}")]
+pub struct ExprTypeErrors(pub Vec<ExprTypeError>);
+
+impl From<ExprTypeError> for Report {
+    fn from(error: ExprTypeError) -> Report {
+        Report::error(
+            error.error.code(),
+            error.meta.span.unwrap_or(0..0),
+            error.error.to_string(),
+        )
+    }
 }
 
 impl From<ExprTypeError> for TextualDiagnostics {
     fn from(error: ExprTypeError) -> TextualDiagnostics {
+        ExprTypeErrors(vec![error]).into()
+    }
+}
+
+impl From<ExprTypeErrors> for TextualDiagnostics {
+    fn from(ExprTypeErrors(errors): ExprTypeErrors) -> TextualDiagnostics {
         TextualDiagnostics {
             title: "Typeinfer error".into(),
-            reports: vec![Report {
-                span: error.meta.span.unwrap_or(0..0),
-                text: format!("{:?}", error.error),
-            }],
+            reports: errors.into_iter().map(Report::from).collect(),
         }
     }
 }
@@ -0,0 +1,103 @@
+use hir::meta::Meta;
+use ids::NodeId;
+use textual_diagnostics::{Report, Severity, TextualDiagnostics};
+
+use crate::{
+    ctx::{Ctx, Id, Log},
+    ty::Type,
+};
+
+/// A hole found in type inference.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypedHole {
+    pub id: NodeId,
+    pub meta: Meta,
+    /// The type expected at the hole.
+    pub expected: types::Type,
+    /// Typed variables visible from the hole, the innermost first.
+    pub context: Vec<(String, types::Type)>,
+}
+
+/// Holes in the order of appearance.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct TypedHoles(pub Vec<TypedHole>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct HoleLog {
+    pub id: NodeId,
+    pub meta: Meta,
+    pub expected: Type,
+    pub context: Vec<(Id, Type)>,
+}
+
+impl Ctx {
+    pub(crate) fn add_hole(&self, id: NodeId, meta: Meta, expected: Type) {
+        let context = self
+            .logs
+            .borrow()
+            .iter()
+            .rev()
+            .filter_map(|log| match log {
+                Log::TypedVariable(id, ty) => Some((*id, ty.clone())),
+                _ => None,
+            })
+            .collect();
+        self.holes.borrow_mut().push(HoleLog {
+            id,
+            meta,
+            expected,
+            context,
+        });
+    }
+
+    pub fn get_holes(&self) -> TypedHoles {
+        let mut holes: Vec<_> = self
+            .holes
+            .borrow()
+            .iter()
+            .map(|hole| TypedHole {
+                id: hole.id.clone(),
+                meta: hole.meta.clone(),
+                expected: self.gen_type(&self.finalize(&hole.expected)),
+                context: hole
+                    .context
+                    .iter()
+                    .map(|(id, ty)| (self.get_ident_of(*id), self.gen_type(&self.finalize(ty))))
+                    .collect(),
+            })
+            .collect();
+        if !self.subtrees.is_empty() {
+            holes.extend(
+                self.subtrees
+                    .values()
+                    .flat_map(|subtree| subtree.holes.0.clone()),
+            );
+            // Holes in subtrees are typed separately, so they are sorted back in the order of appearance.
+            holes.sort_by_key(|hole| hole.meta.span.as_ref().map(|span| span.start));
+        }
+        TypedHoles(holes)
+    }
+}
+
+impl From<TypedHoles> for TextualDiagnostics {
+    fn from(TypedHoles(holes): TypedHoles) -> TextualDiagnostics {
+        TextualDiagnostics {
+            title: "Typed holes".into(),
+            reports: holes
+                .into_iter()
+                .map(|hole| {
+                    let report = Report::new(
+                        "I0401",
+                        Severity::Information,
+                        hole.meta.span.unwrap_or(0..0),
+                        format!("expected {}", hole.expected),
+                    );
+                    // Variables which can be used to fill the hole.
+                    hole.context.iter().fold(report, |report, (ident, ty)| {
+                        report.with_note(format!("{}: {}", ident, ty))
+                    })
+                })
+                .collect(),
+        }
+    }
+}
@@ -1,31 +1,103 @@
 pub mod ctx;
 pub mod error;
+pub mod hole;
 mod mono_type;
 mod occurs_in;
 mod polymorphic_function;
+mod replace_this;
 mod substitute;
 mod substitute_from_ctx;
+pub mod subtree;
 mod ty;
 mod utils;
 mod well_formed;
 mod with_effects;
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 use ctx::Ctx;
-use error::{ExprTypeError, TypeError};
+use error::{ExprTypeError, ExprTypeErrors, TypeError};
 use hir::{expr::Expr, meta::WithMeta};
+use ids::NodeId;
+use subtree::SubtreeTypes;
 use ty::Type;
 use types::IdGen;
 use with_effects::WithEffects;
 
 pub fn synth(next_id: usize, expr: &WithMeta<Expr>) -> Result<(Ctx, Type), ExprTypeError> {
-    Ctx {
-        id_gen: Rc::new(RefCell::new(IdGen { next_id })),
-        ..Default::default()
+    synth_with_ctx(
+        Ctx {
+            id_gen: Rc::new(RefCell::new(IdGen { next_id })),
+            ..Default::default()
+        },
+        expr,
+    )
+}
+
+/// Like `synth`, but the given subtrees are not typed again.
+pub fn synth_with_subtrees(
+    next_id: usize,
+    expr: &WithMeta<Expr>,
+    subtrees: HashMap<NodeId, SubtreeTypes>,
+) -> Result<(Ctx, Type), ExprTypeError> {
+    // Ids continue after the ones used in the subtrees.
+    let next_id = subtrees
+        .values()
+        .map(SubtreeTypes::next_id)
+        .fold(next_id, usize::max);
+    synth_with_ctx(
+        Ctx {
+            id_gen: Rc::new(RefCell::new(IdGen { next_id })),
+            subtrees: Rc::new(subtrees),
+            ..Default::default()
+        },
+        expr,
+    )
+}
+
+/// Unlike `synth`, this continues past errors by typing a failed expression as an error type,
+/// and returns all errors.
+pub fn synth_with_recovery(next_id: usize, expr: &WithMeta<Expr>) -> (Ctx, Type, ExprTypeErrors) {
+    let (ctx, ty) = synth_with_ctx(
+        Ctx {
+            id_gen: Rc::new(RefCell::new(IdGen { next_id })),
+            recovery: true,
+            ..Default::default()
+        },
+        expr,
+    )
+    .expect("errors should be recovered");
+    let errors = ExprTypeErrors(ctx.errors.take());
+    (ctx, ty, errors)
+}
+
+/// Checks that a card of the type provides each of its exports.
+pub fn check_exports(
+    ctx: &Ctx,
+    ty: &Type,
+    exports: &[WithMeta<hir::ty::Type>],
+) -> Result<(), ExprTypeErrors> {
+    let errors: Vec<_> = exports
+        .iter()
+        .filter_map(|export| {
+            let export_ty = ctx.save_from_hir_type(export);
+            ctx.subtype(ty, &export_ty)
+                .err()
+                .map(|error| ExprTypeError {
+                    meta: export.meta.clone(),
+                    error,
+                })
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ExprTypeErrors(errors))
     }
-    .synth(expr)
-    .map(|WithEffects((ctx, ty), effects)| {
+}
+
+fn synth_with_ctx(ctx: Ctx, expr: &WithMeta<Expr>) -> Result<(Ctx, Type), ExprTypeError> {
+    ctx.synth(expr).map(|WithEffects((ctx, ty), effects)| {
         assert!(ctx.continue_input.borrow().is_empty());
         assert!(ctx.continue_output.borrow().is_empty());
         let ty = ctx.substitute_from_ctx(&ty);
@@ -97,7 +169,7 @@ mod tests {
             .into_iter()
             .fold(
                 report,
-                |report, textual_diagnostics::Report { span, text }| {
+                |report, textual_diagnostics::Report { span, text, .. }| {
                     report.with_label(Label::new(span).with_message(text))
                 },
             )
@@ -183,6 +255,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn let_function_with_parameters() {
+        assert_eq!(
+            synth(parse(
+                r#"
+                    $ \ 'a x, 'a y -> &'a y: 'a second ~
+                    >'a second 1, "s"
+            "#
+            )),
+            Ok(Type::String)
+        );
+    }
+
+    #[test]
+    fn explicit_forall() {
+        assert_eq!(
+            synth(parse(
+                r#"
+                    $ ^\ 'a x -> &'a x: $ t ~ \ 'a t -> 'a t: 'a id ~
+                    $ >'a id 1 ~
+                    >'a id "a"
+            "#
+            )),
+            Ok(Type::String)
+        );
+    }
+
+    #[test]
+    fn explicit_forall_rejects_monomorphic_body() {
+        let t = 101;
+        assert_eq!(
+            synth(parse(
+                r#"
+                    ^\ 'a x -> 1: $ t ~ \ 'a t -> 'a t
+            "#
+            ))
+            .map_err(|e| e.error),
+            Err(TypeError::NotSubtype {
+                sub: Box::new(Type::Number),
+                ty: Box::new(Type::Variable(t)),
+            })
+        );
+    }
+
     #[test]
     fn typing_expressions() {
         let input = &r#"
@@ -549,11 +665,11 @@ mod tests {
         assert_eq!(
             synth(expr).map_err(|e| e.error),
             Err(TypeError::NotSubtype {
-                sub: Type::Number,
-                ty: Type::Brand {
+                sub: Box::new(Type::Number),
+                ty: Box::new(Type::Brand {
                     brand: "brand".into(),
                     item: Box::new(Type::Number),
-                },
+                }),
             })
         );
     }
@@ -566,9 +682,150 @@ mod tests {
             ^&@brand 'number: 'number
         "#,
         );
+        assert_eq!(
+            synth(expr).map_err(|e| e.error),
+            Err(TypeError::NotSubtype {
+                sub: Box::new(Type::Brand {
+                    brand: "brand".into(),
+                    item: Box::new(Type::Number),
+                }),
+                ty: Box::new(Type::Number),
+            })
+        );
+    }
+
+    #[test]
+    fn unbrand() {
+        let expr = parse(
+            r#"
+            'brand brand
+            ^'unbrand &@brand 'number: 'number
+        "#,
+        );
         assert_eq!(synth(expr), Ok(Type::Number));
     }
 
+    #[test]
+    fn unbrand_not_branded() {
+        let expr = parse("'unbrand 1");
+        assert_eq!(
+            synth(expr).map_err(|e| e.error),
+            Err(TypeError::NotBranded { ty: Type::Number })
+        );
+    }
+
+    #[test]
+    fn same_brand() {
+        let expr = parse(
+            r#"
+            'brand brand
+            ^@brand 1: @brand 'number
+        "#,
+        );
+        assert_eq!(
+            synth(expr),
+            Ok(Type::Brand {
+                brand: "brand".into(),
+                item: Box::new(Type::Number),
+            })
+        );
+    }
+
+    #[test]
+    fn different_brands() {
+        let expr = parse(
+            r#"
+            'brand a, b.
+            ^@a 1: @b 'number
+        "#,
+        );
+        assert_eq!(
+            synth(expr).map_err(|e| e.error),
+            Err(TypeError::NotSubtype {
+                sub: Box::new(Type::Brand {
+                    brand: "a".into(),
+                    item: Box::new(Type::Number),
+                }),
+                ty: Box::new(Type::Brand {
+                    brand: "b".into(),
+                    item: Box::new(Type::Number),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn trait_object() {
+        let expr = parse(
+            r#"
+            $ \ 'number -> "a" ~
+            ^1: % \ 'this -> 'string.
+        "#,
+        );
+        assert_eq!(
+            synth(expr),
+            Ok(Type::Trait(vec![Type::Function {
+                parameter: Box::new(Type::This),
+                body: Box::new(Type::String),
+            }]))
+        );
+    }
+
+    #[test]
+    fn trait_object_without_implementation() {
+        let expr = parse(
+            r#"
+            $ \ 'string -> "a" ~
+            ^1: % \ 'this -> 'string.
+        "#,
+        );
+        assert_eq!(
+            synth(expr).map_err(|e| e.error),
+            Err(TypeError::MissingImplementation {
+                implementation: Box::new(Type::Function {
+                    parameter: Box::new(Type::Number),
+                    body: Box::new(Type::String),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn trait_subtyping() {
+        let to_string = Type::Function {
+            parameter: Box::new(Type::This),
+            body: Box::new(Type::String),
+        };
+        let to_number = Type::Function {
+            parameter: Box::new(Type::This),
+            body: Box::new(Type::Number),
+        };
+        let expr = parse(
+            r#"
+            $ \ 'number -> "a" ~
+            $ \ 'number -> 1 ~
+            ^ ^1: % \ 'this -> 'string, \ 'this -> 'number.: % \ 'this -> 'string.
+        "#,
+        );
+        assert_eq!(synth(expr), Ok(Type::Trait(vec![to_string.clone()])));
+
+        let expr = parse(
+            r#"
+            $ \ 'number -> "a" ~
+            ^ ^1: % \ 'this -> 'string.: % \ 'this -> 'string, \ 'this -> 'number.
+        "#,
+        );
+        let mut sup = vec![to_string.clone(), to_number];
+        sup.sort();
+        assert_eq!(
+            synth(expr).map_err(|e| e.error),
+            Err(TypeError::NotSubtype {
+                sub: Box::new(Type::Trait(vec![to_string])),
+                ty: Box::new(Type::Trait(sup)),
+            })
+        );
+    }
+
     #[test]
     fn infer() {
         let (_hirgen, expr) = parse_inner(
@@ -614,6 +871,77 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hole() {
+        let (_hirgen, expr) = parse_inner("$ 1: 'a x ~ * ?, ^ ?: 'string");
+        let (ctx, ty) = crate::synth(100, &expr).unwrap();
+        let holes = ctx.get_holes().0;
+
+        assert_eq!(holes.len(), 2);
+        let unknown = match &holes[0].expected {
+            types::Type::Variable(ident) => ident.clone(),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(holes[0].context, vec![("x".into(), types::Type::Number)]);
+        assert_eq!(holes[0].meta.span, Some(14..15));
+        assert_eq!(holes[1].expected, types::Type::String);
+        assert_eq!(
+            ctx.gen_type(&ty),
+            types::Type::product(vec![types::Type::Variable(unknown), types::Type::String])
+        );
+    }
+
+    #[test]
+    fn hole_diagnostics() {
+        let (_hirgen, expr) = parse_inner("$ 1: 'a x ~ ^ ?: 'string");
+        let (ctx, _ty) = crate::synth(100, &expr).unwrap();
+
+        assert_eq!(
+            TextualDiagnostics::from(ctx.get_holes()),
+            TextualDiagnostics {
+                title: "Typed holes".into(),
+                reports: vec![textual_diagnostics::Report::new(
+                    "I0401",
+                    textual_diagnostics::Severity::Information,
+                    14..15,
+                    "expected 'string",
+                )
+                .with_note("x: 'number")],
+            }
+        );
+    }
+
+    #[test]
+    fn error_recovery() {
+        let input = r#"* &x, > \ 'number -> 'string ~ "a"., > \ 'string -> 'number ~ 1"#;
+        let (_hirgen, expr) = parse_inner(input);
+        let (_ctx, ty, errors) = crate::synth_with_recovery(100, &expr);
+
+        assert_eq!(
+            ty,
+            Type::Product(vec![Type::Error, Type::String, Type::Number])
+        );
+        assert_eq!(
+            TextualDiagnostics::from(errors),
+            TextualDiagnostics {
+                title: "Typeinfer error".into(),
+                reports: vec![
+                    textual_diagnostics::Report::error("E0406", 2..4, "variable x is not typed"),
+                    textual_diagnostics::Report::error(
+                        "E0402",
+                        31..34,
+                        "'string is not a subtype of 'number"
+                    ),
+                    textual_diagnostics::Report::error(
+                        "E0402",
+                        62..63,
+                        "'number is not a subtype of 'string"
+                    ),
+                ],
+            }
+        );
+    }
+
     // TODO:
     // Priority labels in function application
     // Priority labels in product and sum
@@ -1,41 +1,40 @@
-use std::collections::HashMap;
+use std::collections::BTreeSet;
 
 use crate::{
     ctx::{Ctx, Id},
-    ty::{Type, TypeVisitorMut},
+    ty::{Type, TypeVisitor},
 };
 
 impl Ctx {
-    pub(crate) fn make_polymorphic(&self, mut ty: Type) -> Type {
+    // Existentials are quantified as they are, so that the function is typed with the same
+    // variables as its generalized type.
+    pub(crate) fn make_polymorphic(&self, ty: Type) -> Type {
         if let Type::Function { .. } = ty {
             let mut visitor = Visitor {
-                ctx: self,
                 ids: Default::default(),
             };
-            visitor.visit(&mut ty);
-            let mut ids: Vec<_> = visitor.ids.values().collect();
-            ids.sort();
-            ids.into_iter().rev().fold(ty, |ty, id| Type::ForAll {
-                variable: *id,
-                body: Box::new(ty),
-            })
+            visitor.visit(&ty);
+            visitor
+                .ids
+                .into_iter()
+                .rev()
+                .fold(ty, |ty, id| Type::ForAll {
+                    variable: id,
+                    body: Box::new(ty),
+                })
         } else {
             ty
         }
     }
 }
 
-struct Visitor<'a> {
-    ctx: &'a Ctx,
-    ids: HashMap<Id, Id>,
+struct Visitor {
+    ids: BTreeSet<Id>,
 }
 
-impl<'a> TypeVisitorMut for Visitor<'a> {
-    fn visit_existential(&mut self, id: &mut Id) {
-        *id = *self
-            .ids
-            .entry(*id)
-            .or_insert_with(|| self.ctx.fresh_existential());
+impl TypeVisitor for Visitor {
+    fn visit_existential(&mut self, id: &Id) {
+        self.ids.insert(*id);
     }
 }
 
@@ -70,12 +69,12 @@ mod tests {
                 body: Box::new(Type::Existential(2))
             }),
             Type::ForAll {
-                variable: 0,
+                variable: 1,
                 body: Box::new(Type::ForAll {
-                    variable: 1,
+                    variable: 2,
                     body: Box::new(Type::Function {
-                        parameter: Box::new(Type::Existential(0)),
-                        body: Box::new(Type::Existential(1))
+                        parameter: Box::new(Type::Existential(1)),
+                        body: Box::new(Type::Existential(2))
                     })
                 })
             }
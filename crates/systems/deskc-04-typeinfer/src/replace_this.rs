@@ -0,0 +1,24 @@
+use crate::ty::{Type, TypeVisitorMut};
+
+// Replaces `This` in a function signature of a trait with the type that implements the trait.
+pub fn replace_this(signature: &Type, this: &Type) -> Type {
+    let mut replace_this = ReplaceThis { this: this.clone() };
+    let mut signature = signature.clone();
+    replace_this.visit(&mut signature);
+    signature
+}
+
+struct ReplaceThis {
+    this: Type,
+}
+
+impl TypeVisitorMut for ReplaceThis {
+    fn visit(&mut self, ty: &mut Type) {
+        match ty {
+            Type::This => *ty = self.this.clone(),
+            // `This` in a nested trait refers to the implementation of the nested trait.
+            Type::Trait(_) => {}
+            ty => self.visit_inner(ty),
+        }
+    }
+}
@@ -13,6 +13,8 @@ impl<'a> TypeVisitorMut for SubstituteFromCtx<'a> {
             Type::Existential(id) => {
                 if let Some(solved) = self.ctx.get_solved(id) {
                     *ty = solved;
+                    // A solution may refer to existentials solved later.
+                    self.visit(ty);
                 }
             }
             Type::Variable(id) => {
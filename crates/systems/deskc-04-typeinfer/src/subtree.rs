@@ -0,0 +1,359 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use hir::{
+    expr::{Expr, Handler, Literal, MatchCase},
+    helper::children,
+    meta::WithMeta,
+    ty::{Effect as HirEffect, EffectExpr as HirEffectExpr, Type as HirType},
+};
+use ids::NodeId;
+use types::{IdGen, Types};
+
+use crate::{
+    ctx::Ctx,
+    error::ExprTypeError,
+    hole::TypedHoles,
+    ty::{
+        effect_expr::{simplify_effect_expr, EffectExpr},
+        Type, TypeVisitor,
+    },
+    with_effects::WithEffects,
+};
+
+/// Types of a subtree inferred apart from the enclosing expression.
+///
+/// This is reused by `synth_with_subtrees` instead of typing the subtree again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubtreeTypes {
+    ty: Type,
+    effects: EffectExpr,
+    pub types: Types,
+    pub holes: TypedHoles,
+    // The next id after the ids used in the subtree.
+    next_id: usize,
+}
+
+impl SubtreeTypes {
+    pub(crate) fn ty_and_effects(&self) -> (Type, EffectExpr) {
+        (self.ty.clone(), self.effects.clone())
+    }
+    pub(crate) fn next_id(&self) -> usize {
+        self.next_id
+    }
+}
+
+/// Types a subtree on its own.
+///
+/// The enclosing expression continues after the ids used here, so that they don't collide.
+///
+/// Returns `None` if the subtree refers to an enclosing variable or continuation,
+/// or if its type is not determined without the enclosing expression.
+pub fn synth_subtree(expr: &WithMeta<Expr>) -> Result<Option<SubtreeTypes>, ExprTypeError> {
+    if !is_closed(expr) {
+        return Ok(None);
+    }
+    let ctx = Ctx {
+        id_gen: Rc::new(RefCell::new(IdGen { next_id: 0 })),
+        ..Default::default()
+    };
+    let WithEffects((ctx, ty), mut effects) = ctx.synth(expr)?;
+    let ty = ctx.finalize(&ty);
+    ctx.substitute_from_ctx_effect_expr(&mut effects);
+    simplify_effect_expr(&mut effects);
+    let mut ground = Ground(true);
+    ground.visit(&ty);
+    ground.visit_effect_expr(&effects);
+    if !ground.0 {
+        return Ok(None);
+    }
+    Ok(Some(SubtreeTypes {
+        ty,
+        effects,
+        types: ctx.get_types(),
+        holes: ctx.get_holes(),
+        next_id: ctx.get_id_gen().next_id,
+    }))
+}
+
+/// Finds the outermost subtrees below the root for which `synth` returns types.
+pub fn collect_subtrees<E>(
+    expr: &WithMeta<Expr>,
+    synth: &mut impl FnMut(&WithMeta<Expr>) -> Result<Option<SubtreeTypes>, E>,
+) -> Result<HashMap<NodeId, SubtreeTypes>, E> {
+    let mut subtrees = HashMap::new();
+    collect_children(expr, synth, &mut subtrees)?;
+    Ok(subtrees)
+}
+
+fn collect_children<E>(
+    expr: &WithMeta<Expr>,
+    synth: &mut impl FnMut(&WithMeta<Expr>) -> Result<Option<SubtreeTypes>, E>,
+    subtrees: &mut HashMap<NodeId, SubtreeTypes>,
+) -> Result<(), E> {
+    for child in children(expr) {
+        // Typing a literal again costs nothing.
+        if let Expr::Literal(_) = child.value {
+            continue;
+        }
+        if let Some(types) = synth(child)? {
+            subtrees.insert(child.id.clone(), types);
+        } else {
+            collect_children(child, synth, subtrees)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether the expression refers to no variable or continuation of the enclosing expression.
+fn is_closed(expr: &WithMeta<Expr>) -> bool {
+    let mut closed = Closed {
+        bound: vec![],
+        handlers: 0,
+        closed: true,
+    };
+    closed.expr(expr);
+    closed.closed
+}
+
+struct Closed {
+    bound: Vec<String>,
+    // The number of enclosing handlers in the subtree.
+    handlers: usize,
+    closed: bool,
+}
+
+impl Closed {
+    fn expr(&mut self, expr: &WithMeta<Expr>) {
+        if !self.closed {
+            return;
+        }
+        match &expr.value {
+            // A hole is typed with the variables visible from it, which include the enclosing ones.
+            Expr::Literal(Literal::Hole) => self.closed = false,
+            Expr::Literal(_) => {}
+            Expr::Let {
+                ty,
+                definition,
+                expression,
+            } => {
+                self.expr(definition);
+                if let HirType::Variable(var) = &ty.value {
+                    self.bind(var, |this| this.expr(expression));
+                } else {
+                    self.ty(ty);
+                    self.expr(expression);
+                }
+            }
+            Expr::Perform { input, output } => {
+                self.expr(input);
+                self.ty(output);
+            }
+            Expr::Continue { input, output } => {
+                if self.handlers == 0 {
+                    self.closed = false;
+                }
+                self.expr(input);
+                if let Some(output) = output {
+                    self.ty(output);
+                }
+            }
+            Expr::Handle { handlers, expr } => {
+                self.expr(expr);
+                for Handler {
+                    input,
+                    output,
+                    handler,
+                } in handlers
+                {
+                    self.ty(input);
+                    self.ty(output);
+                    self.handlers += 1;
+                    self.expr(handler);
+                    self.handlers -= 1;
+                }
+            }
+            Expr::Apply {
+                function,
+                arguments,
+                ..
+            } => {
+                self.ty(function);
+                arguments.iter().for_each(|argument| self.expr(argument));
+            }
+            Expr::Product(exprs) | Expr::Vector(exprs) | Expr::Set(exprs) => {
+                exprs.iter().for_each(|expr| self.expr(expr))
+            }
+            Expr::Match { of, cases } => {
+                self.expr(of);
+                for MatchCase { ty, expr } in cases {
+                    self.ty(ty);
+                    self.expr(expr);
+                }
+            }
+            Expr::Typed { ty, item } => {
+                self.ty(ty);
+                self.expr(item);
+            }
+            Expr::Function { parameter, body } => {
+                if let HirType::Variable(var) = &parameter.value {
+                    self.bind(var, |this| this.expr(body));
+                } else {
+                    self.ty(parameter);
+                    self.expr(body);
+                }
+            }
+            Expr::Label { item, .. } | Expr::Brand { item, .. } | Expr::Unbrand { item } => {
+                self.expr(item)
+            }
+        }
+    }
+
+    fn ty(&mut self, ty: &WithMeta<HirType>) {
+        match &ty.value {
+            HirType::Number | HirType::String | HirType::Infer | HirType::This => {}
+            HirType::Variable(var) => self.reference(var),
+            HirType::BoundedVariable { bound, identifier } => {
+                self.ty(bound);
+                self.reference(identifier);
+            }
+            HirType::Let { variable, body } => self.bind(variable, |this| this.ty(body)),
+            HirType::Effectful { ty, effects } => {
+                self.ty(ty);
+                self.effect_expr(effects);
+            }
+            HirType::Trait(types) | HirType::Product(types) | HirType::Sum(types) => {
+                types.iter().for_each(|ty| self.ty(ty))
+            }
+            HirType::Function { parameters, body } => {
+                parameters.iter().for_each(|ty| self.ty(ty));
+                self.ty(body);
+            }
+            HirType::Vector(ty) | HirType::Set(ty) => self.ty(ty),
+            HirType::Brand { item, .. } | HirType::Label { item, .. } => self.ty(item),
+        }
+    }
+
+    fn effect_expr(&mut self, expr: &WithMeta<HirEffectExpr>) {
+        match &expr.value {
+            HirEffectExpr::Effects(effects) => {
+                for WithMeta {
+                    value: HirEffect { input, output },
+                    ..
+                } in effects
+                {
+                    self.ty(input);
+                    self.ty(output);
+                }
+            }
+            HirEffectExpr::Add(exprs) => exprs.iter().for_each(|expr| self.effect_expr(expr)),
+            HirEffectExpr::Sub {
+                minuend,
+                subtrahend,
+            } => {
+                self.effect_expr(minuend);
+                self.effect_expr(subtrahend);
+            }
+            HirEffectExpr::Apply {
+                function,
+                arguments,
+            } => {
+                self.ty(function);
+                arguments.iter().for_each(|ty| self.ty(ty));
+            }
+        }
+    }
+
+    fn bind(&mut self, var: &str, f: impl FnOnce(&mut Self)) {
+        self.bound.push(var.to_string());
+        f(self);
+        self.bound.pop();
+    }
+
+    fn reference(&mut self, var: &str) {
+        if !self.bound.iter().any(|bound| bound == var) {
+            self.closed = false;
+        }
+    }
+}
+
+/// Whether the type has neither variables nor existentials.
+struct Ground(bool);
+
+impl TypeVisitor for Ground {
+    fn visit_variable(&mut self, _id: &usize) {
+        self.0 = false;
+    }
+    fn visit_forall(&mut self, _variable: &usize, _body: &Type) {
+        self.0 = false;
+    }
+    fn visit_existential(&mut self, _id: &usize) {
+        self.0 = false;
+    }
+    fn visit_infer(&mut self, _id: &NodeId) {
+        self.0 = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hirgen::gen_hir;
+    use parser::parse;
+
+    use super::*;
+
+    fn hir(input: &str) -> WithMeta<Expr> {
+        gen_hir(&parse(lexer::scan(input).unwrap()).unwrap())
+            .unwrap()
+            .1
+    }
+
+    fn subtree_ids(expr: &WithMeta<Expr>) -> Vec<NodeId> {
+        collect_subtrees(expr, &mut synth_subtree)
+            .unwrap()
+            .into_keys()
+            .collect()
+    }
+
+    #[test]
+    fn reuses_closed_subtrees() {
+        let expr = hir(r#"$ 1: x ~ * &x, * 1, "a"."#);
+        let Expr::Let { expression, .. } = &expr.value else {
+            panic!()
+        };
+        let Expr::Product(items) = &expression.value else {
+            panic!()
+        };
+        assert_eq!(subtree_ids(&expr), vec![items[1].id.clone()]);
+    }
+
+    #[test]
+    fn does_not_reuse_continue_outside_of_handler() {
+        let expr = hir("'handle 1 ~ 'number => 'string -> <! \"a\" => 'number");
+        assert!(synth_subtree(&expr).unwrap().is_some());
+        let Expr::Handle { handlers, .. } = &expr.value else {
+            panic!()
+        };
+        assert!(synth_subtree(&handlers[0].handler).unwrap().is_none());
+    }
+
+    #[test]
+    fn synth_with_subtrees_gives_the_same_types() {
+        let expr = hir(r#"$ \ 'number -> * &'number, 1: f ~ * > f 2., "a"."#);
+        let subtrees = collect_subtrees(&expr, &mut synth_subtree).unwrap();
+        assert!(!subtrees.is_empty());
+        let (expected, expected_ty) = crate::synth(0, &expr).unwrap();
+        let (actual, actual_ty) = crate::synth_with_subtrees(0, &expr, subtrees).unwrap();
+        assert_eq!(actual_ty, expected_ty);
+        assert_eq!(actual.get_types(), expected.get_types());
+    }
+
+    #[test]
+    fn continues_after_ids_of_subtrees() {
+        let expr = hir(r#"* \ 'number -> 1, &'string"#);
+        let subtrees = collect_subtrees(&expr, &mut synth_subtree).unwrap();
+        let next_id = subtrees.values().map(SubtreeTypes::next_id).max().unwrap();
+        assert!(next_id > 0);
+        let (ctx, _) = crate::synth_with_subtrees(0, &expr, subtrees).unwrap();
+        assert!(ctx.get_id_gen().next_id >= next_id);
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt::{Display, Formatter};
+
+use super::{effect_expr::EffectExpr, Effect, Type};
+
+// Types are printed in the same syntax as the inferred types.
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", to_types(self))
+    }
+}
+
+impl Display for Effect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} => {}", self.input, self.output)
+    }
+}
+
+// Unlike `Ctx::gen_type`, this keeps the type as is without a context.
+fn to_types(ty: &Type) -> types::Type {
+    let boxed = |ty: &Type| Box::new(to_types(ty));
+    match ty {
+        Type::Number => types::Type::Number,
+        Type::String => types::Type::String,
+        Type::Product(types) => types::Type::Product(types.iter().map(to_types).collect()),
+        Type::Sum(types) => types::Type::Sum(types.iter().map(to_types).collect()),
+        Type::Function { parameter, body } => {
+            // Curried functions are printed as a function with multiple parameters.
+            let mut parameters = vec![to_types(parameter)];
+            let mut body = body;
+            while let Type::Function {
+                parameter,
+                body: next,
+            } = &**body
+            {
+                parameters.push(to_types(parameter));
+                body = next;
+            }
+            types::Type::Function {
+                parameters,
+                body: boxed(body),
+            }
+        }
+        Type::Vector(item) => types::Type::Vector(boxed(item)),
+        Type::Set(item) => types::Type::Set(boxed(item)),
+        Type::Variable(id) => types::Type::Variable(variable(*id)),
+        Type::ForAll { variable: id, body } => types::Type::ForAll {
+            variable: variable(*id),
+            body: boxed(body),
+        },
+        Type::Existential(id) => types::Type::Variable(format!("_{}", id)),
+        Type::Trait(types) => types::Type::Trait(types.iter().map(to_types).collect()),
+        Type::This => types::Type::This,
+        Type::Infer(_) | Type::Error => types::Type::Error,
+        Type::Effectful { ty, effects } => types::Type::Effectful {
+            ty: boxed(ty),
+            effects: effect_expr_to_types(effects),
+        },
+        Type::Brand { brand, item } => types::Type::Brand {
+            brand: brand.clone(),
+            item: boxed(item),
+        },
+        Type::Label { label, item } => types::Type::Label {
+            label: label.clone(),
+            item: boxed(item),
+        },
+    }
+}
+
+fn effect_expr_to_types(expr: &EffectExpr) -> types::EffectExpr {
+    match expr {
+        EffectExpr::Effects(effects) => types::EffectExpr::Effects(
+            effects
+                .iter()
+                .map(|Effect { input, output }| types::Effect {
+                    input: to_types(input),
+                    output: to_types(output),
+                })
+                .collect(),
+        ),
+        EffectExpr::Add(exprs) => {
+            types::EffectExpr::Add(exprs.iter().map(effect_expr_to_types).collect())
+        }
+        EffectExpr::Sub {
+            minuend,
+            subtrahend,
+        } => types::EffectExpr::Sub {
+            minuend: Box::new(effect_expr_to_types(minuend)),
+            subtrahend: Box::new(effect_expr_to_types(subtrahend)),
+        },
+        EffectExpr::Apply {
+            function,
+            arguments,
+        } => types::EffectExpr::Apply {
+            function: Box::new(to_types(function)),
+            arguments: arguments.iter().map(to_types).collect(),
+        },
+    }
+}
+
+// Identifiers of type variables are not known here.
+fn variable(id: usize) -> String {
+    format!("t{}", id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displays_in_desk_syntax() {
+        let ty = Type::Function {
+            parameter: Box::new(Type::Number),
+            body: Box::new(Type::Function {
+                parameter: Box::new(Type::Existential(1)),
+                body: Box::new(Type::Effectful {
+                    ty: Box::new(Type::Label {
+                        label: "sum".into(),
+                        item: Box::new(Type::Product(vec![Type::String, Type::Variable(2)])),
+                    }),
+                    effects: EffectExpr::Effects(vec![Effect {
+                        input: Type::String,
+                        output: Type::Number,
+                    }]),
+                }),
+            }),
+        };
+        assert_eq!(
+            ty.to_string(),
+            r"\ 'number, _1 -> ! @sum * 'string, t2 {'string => 'number}"
+        );
+    }
+
+    #[test]
+    fn displays_brands_distinctly_from_labels() {
+        let ty = Type::Product(vec![
+            Type::Brand {
+                brand: "userid".into(),
+                item: Box::new(Type::String),
+            },
+            Type::Label {
+                label: "name".into(),
+                item: Box::new(Type::String),
+            },
+        ]);
+        assert_eq!(
+            ty.to_string(),
+            "* 'brand userid ~ @userid 'string, @name 'string"
+        );
+    }
+}
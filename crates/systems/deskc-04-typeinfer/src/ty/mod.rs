@@ -1,3 +1,4 @@
+mod display;
 pub mod effect_expr;
 
 use std::collections::HashMap;
@@ -51,6 +52,11 @@ pub enum Type {
         label: String,
         item: Box<Self>,
     },
+    // Function signatures in which `This` is the type that implements the trait.
+    Trait(Vec<Self>),
+    This,
+    // A type of the expression that failed to be typed, which is compatible with any type.
+    Error,
 }
 
 pub(crate) trait TypeVisitorMut {
@@ -132,6 +138,10 @@ pub(crate) trait TypeVisitorMut {
     fn visit_label(&mut self, _label: &mut String, item: &mut Type) {
         self.visit(item);
     }
+    fn visit_trait(&mut self, types: &mut Vec<Type>) {
+        types.iter_mut().for_each(|ty| self.visit(ty))
+    }
+    fn visit_this(&mut self) {}
     fn visit(&mut self, ty: &mut Type) {
         self.visit_inner(ty)
     }
@@ -151,6 +161,9 @@ pub(crate) trait TypeVisitorMut {
             Type::Effectful { ty, effects } => self.visit_effectful(ty, effects),
             Type::Brand { brand, item } => self.visit_brand(brand, item),
             Type::Label { label, item } => self.visit_label(label, item),
+            Type::Trait(types) => self.visit_trait(types),
+            Type::This => self.visit_this(),
+            Type::Error => {}
         }
     }
 }
@@ -230,6 +243,10 @@ pub(crate) trait TypeVisitor {
     fn visit_label(&mut self, _label: &str, item: &Type) {
         self.visit(item);
     }
+    fn visit_trait(&mut self, types: &[Type]) {
+        types.iter().for_each(|ty| self.visit(ty))
+    }
+    fn visit_this(&mut self) {}
     fn visit(&mut self, ty: &Type) {
         self.visit_inner(ty)
     }
@@ -249,6 +266,9 @@ pub(crate) trait TypeVisitor {
             Type::Effectful { ty, effects } => self.visit_effectful(ty, effects),
             Type::Brand { brand, item } => self.visit_brand(brand, item),
             Type::Label { label, item } => self.visit_label(label, item),
+            Type::Trait(types) => self.visit_trait(types),
+            Type::This => self.visit_this(),
+            Type::Error => {}
         }
     }
 }
@@ -15,17 +15,35 @@ pub(crate) enum Builtin {
     Custom(CustomBuiltin),
 }
 
+/// An integer overflow in arithmetic builtins is approximated by a float,
+/// unless the signature declares the overflow effect like `\ 'number, 'number -> ! @sum 'number {@overflow 'number => 'number}`.
 pub(crate) fn find_builtin(ty: &Type) -> Option<Builtin> {
+    let add = Builtin::Normal {
+        op: BuiltinOp::Add,
+        params: 2,
+    };
+    let sub = Builtin::Normal {
+        op: BuiltinOp::Sub,
+        params: 2,
+    };
+    let mul = Builtin::Normal {
+        op: BuiltinOp::Mul,
+        params: 2,
+    };
     let map: HashMap<_, _> = [
         (
             Type::function(
                 vec![Type::Number, Type::Number],
                 labeled("sum", Type::Number),
             ),
-            Builtin::Normal {
-                op: BuiltinOp::Add,
-                params: 2,
-            },
+            add.clone(),
+        ),
+        (
+            Type::function(
+                vec![Type::Number, Type::Number],
+                overflowing(labeled("sum", Type::Number)),
+            ),
+            add,
         ),
         (
             Type::function(
@@ -35,20 +53,31 @@ pub(crate) fn find_builtin(ty: &Type) -> Option<Builtin> {
                 ],
                 Type::Number,
             ),
-            Builtin::Normal {
-                op: BuiltinOp::Sub,
-                params: 2,
-            },
+            sub.clone(),
+        ),
+        (
+            Type::function(
+                vec![
+                    labeled("minuend", Type::Number),
+                    labeled("subtrahend", Type::Number),
+                ],
+                overflowing(Type::Number),
+            ),
+            sub,
         ),
         (
             Type::function(
                 vec![Type::Number, Type::Number],
                 labeled("product", Type::Number),
             ),
-            Builtin::Normal {
-                op: BuiltinOp::Mul,
-                params: 2,
-            },
+            mul.clone(),
+        ),
+        (
+            Type::function(
+                vec![Type::Number, Type::Number],
+                overflowing(labeled("product", Type::Number)),
+            ),
+            mul,
         ),
         (
             Type::function(
@@ -58,16 +87,30 @@ pub(crate) fn find_builtin(ty: &Type) -> Option<Builtin> {
                 ],
                 Type::Effectful {
                     ty: Box::new(labeled("quotient", Type::Number)),
-                    effects: EffectExpr::Effects(vec![Effect {
-                        input: labeled("division by zero", Type::Number),
-                        output: Type::Number,
-                    }]),
+                    effects: EffectExpr::Effects(vec![Effect::division_by_zero()]),
                 },
             ),
             Builtin::Custom(Rc::new(Box::new(|thirgen, args| {
                 divide(thirgen, args, BuiltinOp::Div)
             }))),
         ),
+        (
+            Type::function(
+                vec![
+                    labeled("self", Type::Number),
+                    labeled("other", Type::Number),
+                ],
+                Type::sum(vec![
+                    labeled("greater", Type::unit()),
+                    labeled("less", Type::unit()),
+                    labeled("equal", Type::unit()),
+                ]),
+            ),
+            Builtin::Normal {
+                op: BuiltinOp::Cmp,
+                params: 2,
+            },
+        ),
         (
             Type::function(
                 vec![Type::Number, Type::Number],
@@ -84,7 +127,125 @@ pub(crate) fn find_builtin(ty: &Type) -> Option<Builtin> {
     ]
     .into_iter()
     .collect();
-    map.get(ty).cloned()
+    map.get(ty).cloned().or_else(|| find_collection_builtin(ty))
+}
+
+/// Collection builtins are generic over the item type, so they are found by the shape of the type.
+/// Like other builtins, arguments are given in the order of the sorted parameters.
+///
+/// - `\ [T] -> @length 'number` and `\ {T} -> @length 'number`
+/// - `\ [T], @index 'number -> ! T ~ @index out of bounds 'number => T`
+/// - `\ [T], T -> @pushed [T]`
+/// - `\ [T], \ T -> U -> [U]`
+/// - `\ [T], @initial A, \ A, T -> A -> A`
+/// - `\ {T}, T -> + @true *, @false *`
+/// - `\ {T}, {T} -> @union {T}` and `\ {T}, {T} -> @intersection {T}`
+fn find_collection_builtin(ty: &Type) -> Option<Builtin> {
+    let (parameters, body) = match ty {
+        Type::Function { parameters, body } => (parameters, &**body),
+        _ => return None,
+    };
+    // Parameters are sorted, so find the order of operands from the shape.
+    let permutations: &[&[usize]] = match parameters.len() {
+        1 => &[&[0]],
+        2 => &[&[0, 1], &[1, 0]],
+        3 => &[
+            &[0, 1, 2],
+            &[0, 2, 1],
+            &[1, 0, 2],
+            &[1, 2, 0],
+            &[2, 0, 1],
+            &[2, 1, 0],
+        ],
+        _ => return None,
+    };
+    permutations.iter().find_map(|order| {
+        let operands: Vec<_> = order.iter().map(|index| &parameters[*index]).collect();
+        collection_op(&operands, body).map(|op| {
+            let order = order.to_vec();
+            Builtin::Custom(Rc::new(Box::new(move |thirgen, args| Expr::Op {
+                op: op.clone(),
+                operands: order
+                    .iter()
+                    .map(|index| thirgen.gen(&args[*index]))
+                    .collect(),
+            })))
+        })
+    })
+}
+
+fn collection_op(operands: &[&Type], body: &Type) -> Option<BuiltinOp> {
+    let op = match operands {
+        [Type::Vector(_) | Type::Set(_)] if *body == labeled("length", Type::Number) => {
+            BuiltinOp::Length
+        }
+        [Type::Vector(item), index]
+            if *index == &labeled("index", Type::Number)
+                && *body
+                    == (Type::Effectful {
+                        ty: item.clone(),
+                        effects: EffectExpr::Effects(vec![Effect::index_out_of_bounds(
+                            *item.clone(),
+                        )]),
+                    }) =>
+        {
+            BuiltinOp::Index
+        }
+        [Type::Vector(item), pushed]
+            if **item == **pushed && *body == labeled("pushed", Type::Vector(item.clone())) =>
+        {
+            BuiltinOp::Push
+        }
+        [Type::Vector(item), Type::Function {
+            parameters,
+            body: output,
+        }] if parameters == &vec![*item.clone()] && *body == Type::Vector(output.clone()) => {
+            BuiltinOp::Map
+        }
+        [Type::Vector(item), initial, Type::Function {
+            parameters,
+            body: output,
+        }] if **initial == labeled("initial", body.clone())
+            && **output == *body
+            && *parameters == sorted(vec![body.clone(), *item.clone()]) =>
+        {
+            BuiltinOp::Fold
+        }
+        [Type::Set(item), member]
+            if **item == **member
+                && *body
+                    == Type::sum(vec![
+                        labeled("true", Type::unit()),
+                        labeled("false", Type::unit()),
+                    ]) =>
+        {
+            BuiltinOp::Member
+        }
+        [Type::Set(lhs), Type::Set(rhs)]
+            if lhs == rhs && *body == labeled("union", Type::Set(lhs.clone())) =>
+        {
+            BuiltinOp::Union
+        }
+        [Type::Set(lhs), Type::Set(rhs)]
+            if lhs == rhs && *body == labeled("intersection", Type::Set(lhs.clone())) =>
+        {
+            BuiltinOp::Intersection
+        }
+        _ => return None,
+    };
+    Some(op)
+}
+
+fn sorted(mut types: Vec<Type>) -> Vec<Type> {
+    types.sort();
+    types
+}
+
+fn overflowing(ty: Type) -> Type {
+    Type::Effectful {
+        ty: Box::new(ty),
+        effects: EffectExpr::Effects(vec![Effect::overflow()]),
+    }
 }
 
 fn labeled(label: &str, item: Type) -> Type {
@@ -126,14 +287,11 @@ fn divide(thirgen: &TypedHirGen, args: &[WithMeta<hir::expr::Expr>], op: Builtin
                     id: NodeId::default(),
                     ty: Type::Effectful {
                         ty: Box::new(Type::Number),
-                        effects: EffectExpr::Effects(vec![Effect {
-                            input: Type::label("division by zero", Type::Number),
-                            output: Type::Number,
-                        }]),
+                        effects: EffectExpr::Effects(vec![Effect::division_by_zero()]),
                     },
                     expr: Expr::Perform(Box::new(TypedHir {
                         id: NodeId::default(),
-                        ty: Type::label("division by zero", Type::Number),
+                        ty: Effect::division_by_zero().input,
                         expr: dividend.expr.clone(),
                     })),
                 },
@@ -153,3 +311,63 @@ fn divide(thirgen: &TypedHirGen, args: &[WithMeta<hir::expr::Expr>], op: Builtin
         ],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_op(ty: &Type) -> Option<BuiltinOp> {
+        let (parameters, body) = match ty {
+            Type::Function { parameters, body } => (parameters, &**body),
+            _ => unreachable!(),
+        };
+        let operands: Vec<_> = parameters.iter().collect();
+        collection_op(&operands, body)
+    }
+
+    #[test]
+    fn collection_builtins() {
+        let vector = Type::Vector(Box::new(Type::String));
+        assert_eq!(
+            find_op(&Type::function(
+                vec![vector.clone()],
+                labeled("length", Type::Number)
+            )),
+            Some(BuiltinOp::Length)
+        );
+        assert_eq!(
+            find_op(&Type::function(
+                vec![vector.clone(), labeled("index", Type::Number)],
+                Type::Effectful {
+                    ty: Box::new(Type::String),
+                    effects: EffectExpr::Effects(vec![Effect::index_out_of_bounds(Type::String)]),
+                },
+            )),
+            Some(BuiltinOp::Index)
+        );
+        assert_eq!(
+            find_op(&Type::function(
+                vec![vector.clone(), Type::Number],
+                labeled("pushed", vector.clone())
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn finds_operands_in_any_order() {
+        let set = Type::Set(Box::new(Type::Number));
+        let member = Type::function(
+            vec![set, Type::Number],
+            Type::sum(vec![
+                labeled("true", Type::unit()),
+                labeled("false", Type::unit()),
+            ]),
+        );
+        // The item comes before the set after sorted.
+        assert!(
+            matches!(&member, Type::Function { parameters, .. } if parameters[0] == Type::Number)
+        );
+        assert!(find_collection_builtin(&member).is_some());
+    }
+}
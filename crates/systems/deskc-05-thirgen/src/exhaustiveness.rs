@@ -0,0 +1,339 @@
+use std::fmt::{Display, Formatter};
+
+use hir::{
+    expr::{Expr, Handler, MatchCase},
+    meta::{Meta, WithMeta},
+};
+use textual_diagnostics::{Report, TextualDiagnostics};
+use thiserror::Error;
+use types::{Type, Types};
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("{error}")]
+pub struct ExprMatchError {
+    pub meta: Meta,
+    pub error: MatchError,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum MatchError {
+    #[error("missing cases: {}", DisplayTypes(.0))]
+    MissingCases(Vec<Type>),
+    #[error("unreachable case: {0}")]
+    UnreachableCase(Type),
+}
+
+impl MatchError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            MatchError::MissingCases(_) => "E0501",
+            MatchError::UnreachableCase(_) => "E0502",
+        }
+    }
+}
+
+/// Errors in the order of appearance.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Error)]
+#[error("{0:?}")]
+pub struct MatchErrors(pub Vec<ExprMatchError>);
+
+impl From<MatchErrors> for TextualDiagnostics {
+    fn from(MatchErrors(errors): MatchErrors) -> TextualDiagnostics {
+        TextualDiagnostics {
+            title: "Match error".into(),
+            reports: errors
+                .into_iter()
+                .map(|error| {
+                    Report::error(
+                        error.error.code(),
+                        error.meta.span.unwrap_or(0..0),
+                        error.error.to_string(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Checks that every match covers all variants of the input and has no unreachable case.
+pub fn check_matches(types: &Types, expr: &WithMeta<Expr>) -> Result<(), MatchErrors> {
+    let mut errors = vec![];
+    check(types, expr, &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(MatchErrors(errors))
+    }
+}
+
+fn check(types: &Types, expr: &WithMeta<Expr>, errors: &mut Vec<ExprMatchError>) {
+    match &expr.value {
+        Expr::Literal(_) => {}
+        Expr::Let {
+            definition,
+            expression,
+            ..
+        } => {
+            check(types, definition, errors);
+            check(types, expression, errors);
+        }
+        Expr::Perform { input, .. } | Expr::Continue { input, .. } => check(types, input, errors),
+        Expr::Handle { handlers, expr } => {
+            check(types, expr, errors);
+            for Handler { handler, .. } in handlers {
+                check(types, handler, errors);
+            }
+        }
+        Expr::Apply { arguments, .. } => {
+            for argument in arguments {
+                check(types, argument, errors);
+            }
+        }
+        Expr::Product(items) | Expr::Vector(items) | Expr::Set(items) => {
+            for item in items {
+                check(types, item, errors);
+            }
+        }
+        Expr::Match { of, cases } => {
+            check(types, of, errors);
+            for MatchCase { expr, .. } in cases {
+                check(types, expr, errors);
+            }
+            check_match(types, expr, of, cases, errors);
+        }
+        Expr::Typed { item, .. }
+        | Expr::Function { body: item, .. }
+        | Expr::Label { item, .. }
+        | Expr::Brand { item, .. }
+        | Expr::Unbrand { item } => check(types, item, errors),
+    }
+}
+
+fn check_match(
+    types: &Types,
+    expr: &WithMeta<Expr>,
+    of: &WithMeta<Expr>,
+    cases: &[MatchCase],
+    errors: &mut Vec<ExprMatchError>,
+) {
+    let variants = match types.get(&of.id) {
+        Some(ty) => variants(ty),
+        None => return,
+    };
+    // Nothing can be said about the variants of an unknown type.
+    if variants.iter().any(|variant| {
+        matches!(
+            variant,
+            Type::Variable(_) | Type::ForAll { .. } | Type::Error
+        )
+    }) {
+        return;
+    }
+    let mut covered = vec![false; variants.len()];
+    for MatchCase { ty, .. } in cases {
+        let case = match types.get(&ty.id) {
+            Some(case) => case,
+            None => continue,
+        };
+        let mut reachable = false;
+        for (variant, covered) in variants.iter().zip(covered.iter_mut()) {
+            if !*covered && covers(case, variant) {
+                *covered = true;
+                reachable = true;
+            }
+        }
+        if !reachable {
+            errors.push(ExprMatchError {
+                meta: ty.meta.clone(),
+                error: MatchError::UnreachableCase(case.clone()),
+            });
+        }
+    }
+    let missing: Vec<_> = variants
+        .into_iter()
+        .zip(covered)
+        .filter(|(_, covered)| !covered)
+        .map(|(variant, _)| variant)
+        .collect();
+    if !missing.is_empty() {
+        errors.push(ExprMatchError {
+            meta: expr.meta.clone(),
+            error: MatchError::MissingCases(missing),
+        });
+    }
+}
+
+// Expands sums, even nested in products, labels and brands, into the list of variants.
+fn variants(ty: &Type) -> Vec<Type> {
+    match ty {
+        Type::Sum(types) => types.iter().flat_map(variants).collect(),
+        Type::Product(types) => types
+            .iter()
+            .fold(vec![vec![]], |products, ty| {
+                let variants = variants(ty);
+                products
+                    .iter()
+                    .flat_map(|product| {
+                        variants.iter().map(move |variant| {
+                            let mut product = product.clone();
+                            product.push(variant.clone());
+                            product
+                        })
+                    })
+                    .collect()
+            })
+            .into_iter()
+            .map(Type::product)
+            .collect(),
+        Type::Label { label, item } => variants(item)
+            .into_iter()
+            .map(|item| Type::label(label, item))
+            .collect(),
+        Type::Brand { brand, item } => variants(item)
+            .into_iter()
+            .map(|item| Type::Brand {
+                brand: brand.clone(),
+                item: Box::new(item),
+            })
+            .collect(),
+        Type::Effectful { ty, .. } => variants(ty),
+        ty => vec![ty.clone()],
+    }
+}
+
+fn covers(case: &Type, variant: &Type) -> bool {
+    match (case, variant) {
+        (Type::Sum(cases), variant) => cases.iter().any(|case| covers(case, variant)),
+        (Type::Effectful { ty, .. }, variant) => covers(ty, variant),
+        (
+            Type::Label { label, item },
+            Type::Label {
+                label: variant_label,
+                item: variant_item,
+            },
+        ) => label == variant_label && covers(item, variant_item),
+        (
+            Type::Brand { brand, item },
+            Type::Brand {
+                brand: variant_brand,
+                item: variant_item,
+            },
+        ) if brand == variant_brand => covers(item, variant_item),
+        // A branded value is also a value without the brand.
+        (case, Type::Brand { item, .. }) => covers(case, item),
+        (Type::Product(cases), Type::Product(variants)) => {
+            cases.len() == variants.len() && covers_all(cases, variants)
+        }
+        (case, variant) => case == variant,
+    }
+}
+
+// Items of a product are unordered, so each variant is covered by a distinct case.
+fn covers_all(cases: &[Type], variants: &[Type]) -> bool {
+    match variants.split_first() {
+        Some((variant, rest)) => cases.iter().enumerate().any(|(index, case)| {
+            covers(case, variant) && {
+                let mut cases = cases.to_vec();
+                cases.remove(index);
+                covers_all(&cases, rest)
+            }
+        }),
+        None => true,
+    }
+}
+
+struct DisplayTypes<'a>(&'a [Type]);
+
+impl<'a> Display for DisplayTypes<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, ty) in self.0.iter().enumerate() {
+            if index != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", ty)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn check(input: &str) -> Result<(), TextualDiagnostics> {
+        let tokens = lexer::scan(input).unwrap();
+        let ast = parser::parse(tokens).unwrap();
+        let (_, hir) = hirgen::gen_hir(&ast).unwrap();
+        // Missing cases are also type errors, so they are recovered to test this check.
+        let (ctx, _, _) = typeinfer::synth_with_recovery(100, &hir);
+        check_matches(&ctx.get_types(), &hir).map_err(|errors| errors.into())
+    }
+
+    fn reports(input: &str) -> Vec<(std::ops::Range<usize>, String)> {
+        check(input)
+            .unwrap_err()
+            .reports
+            .into_iter()
+            .map(|report| (report.span, report.text))
+            .collect()
+    }
+
+    #[test]
+    fn exhaustive() {
+        assert_eq!(
+            check(
+                r#"
+                \ + @a 'number, @b * ->
+                  + &+ @a 'number, @b * ~
+                    @a 'number -> 1,
+                    @b * -> 2.
+                "#
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn missing_case() {
+        assert_eq!(
+            reports(
+                r#"\ + @a 'number, @b 'number, @c 'number. -> + &+ @a 'number, @b 'number, @c 'number. ~ @a 'number -> 1."#
+            ),
+            vec![(43..102, "missing cases: @b 'number, @c 'number".into())]
+        );
+    }
+
+    #[test]
+    fn unreachable_case() {
+        assert_eq!(
+            reports(
+                r#"\ + @a 'number, @b 'string. -> + &+ @a 'number, @b 'string. ~ @a 'number -> 1, @b 'string -> 2, @a 'number -> 3."#
+            ),
+            vec![(96..106, "unreachable case: @a 'number".into())]
+        );
+    }
+
+    #[test]
+    fn nested_products() {
+        let input = r#"\ * + @a 'number, @b 'number., + @c 'string, @d 'string.. ->
+            + &* + @a 'number, @b 'number., + @c 'string, @d 'string.. ~
+              * @a 'number, + @c 'string, @d 'string.. -> 1,
+              * @c 'string, @b 'number. -> 2."#;
+        assert_eq!(
+            reports(input),
+            vec![(73..240, "missing cases: * @b 'number, @d 'string".into())]
+        );
+    }
+
+    #[test]
+    fn branded_value_is_covered_by_case_without_brand() {
+        assert_eq!(
+            check(
+                r#"'brand a ~ \ + @a 'number, 'string -> + &+ @a 'number, 'string ~ 'number -> 1, 'string -> 2."#
+            ),
+            Ok(())
+        );
+    }
+}
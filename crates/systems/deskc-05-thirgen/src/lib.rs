@@ -1,4 +1,5 @@
 mod builtin;
+pub mod exhaustiveness;
 
 use std::cell::RefCell;
 
@@ -6,8 +7,9 @@ use hir::{
     expr::{Expr, Literal, MatchCase},
     meta::WithMeta,
 };
+use ids::NodeId;
 use thir::{Handler, TypedHir};
-use types::{Effect, IdGen, Type, Types};
+use types::{Effect, EffectExpr, IdGen, Type, Types};
 
 use crate::builtin::find_builtin;
 
@@ -30,7 +32,7 @@ impl TypedHirGen {
         let expr_id = &expr.id;
         let ty = self.types.get(expr_id).expect("must have type").clone();
         let expr = match &expr.value {
-            Expr::Literal(Literal::Hole) => todo!(),
+            Expr::Literal(Literal::Hole) => return self.gen_hole(expr_id),
             Expr::Literal(Literal::Integer(value)) => {
                 thir::Expr::Literal(thir::Literal::Int(*value))
             }
@@ -52,7 +54,7 @@ impl TypedHirGen {
                 body: Box::new(self.gen(expression)),
             },
             Expr::Perform { input, output: _ } => thir::Expr::Perform(Box::new(self.gen(input))),
-            Expr::Continue { input, output: _ } => thir::Expr::Perform(Box::new(self.gen(input))),
+            Expr::Continue { input, output: _ } => thir::Expr::Continue(Box::new(self.gen(input))),
             Expr::Handle { handlers, expr } => thir::Expr::Handle {
                 handlers: handlers
                     .iter()
@@ -92,21 +94,41 @@ impl TypedHirGen {
                         builtin::Builtin::Custom(expr) => expr(self, arguments),
                     }
                 } else {
+                    let function = self.get_type(function);
+                    let arguments = self.gen_arguments(&function, arguments);
                     thir::Expr::Apply {
-                        function: self.get_type(function),
+                        function,
                         link_name: link_name.clone(),
-                        arguments: arguments.iter().map(|arg| self.gen(arg)).collect(),
+                        arguments,
                     }
                 }
             }
             Expr::Product(values) => {
                 thir::Expr::Product(values.iter().map(|value| self.gen(value)).collect())
             }
-            // one ID disappeared here, but fine
-            Expr::Typed { ty: _, item: expr } => self.gen(expr).expr,
+            // The type of a hole is not the annotated one because it performs an effect.
+            Expr::Typed { ty: _, item } if item.value == Expr::Literal(Literal::Hole) => {
+                return self.gen_hole(expr_id)
+            }
+            Expr::Typed { ty: _, item: expr } => {
+                let item = self.gen(expr);
+                if is_trait_object(&ty, &item.ty) {
+                    thir::Expr::TraitObject(Box::new(item))
+                } else {
+                    // one ID disappeared here, but fine
+                    item.expr
+                }
+            }
             Expr::Function { parameter: _, body } => {
                 // get type from whole function is more accurate than from parameter.
-                let function_ty = self.get_type(expr);
+                let mut function_ty = self.get_type(expr);
+                // A closure which continues in a handler is typed with the effect,
+                // and one checked with a polymorphic signature is quantified.
+                while let Type::Effectful { ty, effects: _ } | Type::ForAll { body: ty, .. } =
+                    function_ty
+                {
+                    function_ty = *ty;
+                }
                 if let Type::Function {
                     parameters,
                     body: _,
@@ -154,6 +176,8 @@ impl TypedHirGen {
                 label: label.clone(),
                 item: Box::new(self.gen(body)),
             },
+            // Brands are erased at runtime like labels, so only the type changes.
+            Expr::Unbrand { item } => self.gen(item).expr,
         };
         TypedHir {
             id: expr_id.clone(),
@@ -162,6 +186,87 @@ impl TypedHirGen {
         }
     }
 
+    // A hole halts with the hole effect instead of having the inferred type.
+    fn gen_hole(&self, expr_id: &NodeId) -> TypedHir {
+        let effect = Effect::hole();
+        let reason = Type::label("hole", Type::unit());
+        TypedHir {
+            id: expr_id.clone(),
+            ty: Type::Effectful {
+                ty: Box::new(effect.output.clone()),
+                effects: EffectExpr::Effects(vec![effect.clone()]),
+            },
+            expr: thir::Expr::Perform(Box::new(TypedHir {
+                id: NodeId::default(),
+                ty: effect.input,
+                expr: thir::Expr::Label {
+                    label: "halt".into(),
+                    item: Box::new(TypedHir {
+                        id: NodeId::default(),
+                        ty: reason,
+                        expr: thir::Expr::Label {
+                            label: "hole".into(),
+                            item: Box::new(TypedHir {
+                                id: NodeId::default(),
+                                ty: Type::unit(),
+                                expr: thir::Expr::Product(vec![]),
+                            }),
+                        },
+                    }),
+                },
+            })),
+        }
+    }
+
+    // Arguments for trait parameters are made into trait objects.
+    fn gen_arguments(&self, function: &Type, arguments: &[WithMeta<Expr>]) -> Vec<TypedHir> {
+        let parameters = match function {
+            Type::Function { parameters, .. } => parameters.as_slice(),
+            _ => &[],
+        };
+        let arguments: Vec<_> = arguments
+            .iter()
+            .map(|arg| {
+                let argument = self.gen(arg);
+                // An argument of a type variable is passed by the variable, which the function
+                // uses to find the parameter.
+                match self.types.get_parameter(&arg.id) {
+                    Some(parameter) if parameter.has_variable() => TypedHir {
+                        ty: parameter.clone(),
+                        ..argument
+                    },
+                    _ => argument,
+                }
+            })
+            .collect();
+        let mut traits: Vec<_> = parameters
+            .iter()
+            .filter(|parameter| {
+                matches!(parameter, Type::Trait(_))
+                    && !arguments.iter().any(|argument| argument.ty == **parameter)
+            })
+            .collect();
+        arguments
+            .into_iter()
+            .map(|argument| {
+                if parameters.contains(&argument.ty) {
+                    return argument;
+                }
+                match traits
+                    .iter()
+                    .position(|trait_| is_trait_object(trait_, &argument.ty))
+                {
+                    Some(index) => TypedHir {
+                        id: NodeId::default(),
+                        ty: traits.remove(index).clone(),
+                        expr: thir::Expr::TraitObject(Box::new(argument)),
+                    },
+                    None => argument,
+                }
+            })
+            .collect()
+    }
+
     fn get_type<T>(&self, expr: &WithMeta<T>) -> Type {
         self.types.get(&expr.id).expect("must have type").clone()
     }
@@ -171,10 +276,13 @@ impl TypedHirGen {
     }
 }
 
+fn is_trait_object(ty: &Type, value: &Type) -> bool {
+    matches!(ty, Type::Trait(_)) && !matches!(value, Type::Trait(_))
+}
+
 #[cfg(test)]
 mod tests {
-    use ids::NodeId;
-    use thir::{visitor::TypedHirVisitorMut, BuiltinOp};
+    use thir::BuiltinOp;
 
     use super::*;
     use pretty_assertions::assert_eq;
@@ -191,16 +299,8 @@ mod tests {
         infer.get_types()
     }
 
-    pub struct RemoveIdVisitor;
-    impl TypedHirVisitorMut for RemoveIdVisitor {
-        fn visit(&mut self, hir: &mut TypedHir) {
-            hir.id = NodeId::default();
-            self.super_visit(hir);
-        }
-    }
-    fn remove_id(mut expr: TypedHir) -> TypedHir {
-        RemoveIdVisitor.visit(&mut expr);
-        expr
+    fn remove_id(expr: TypedHir) -> TypedHir {
+        thir::hash::normalize(expr)
     }
 
     #[test]
@@ -220,6 +320,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn trait_object_argument() {
+        let expr = parse(r#"$ \ 'number -> "a" ~ > \ % \ 'this -> 'string. -> 'string 1"#);
+        let gen = TypedHirGen {
+            types: infer(&expr),
+            ..Default::default()
+        };
+        let trait_ = Type::Trait(vec![Type::function(vec![Type::This], Type::String)]);
+        let TypedHir {
+            expr: thir::Expr::Let { body, .. },
+            ..
+        } = remove_id(gen.gen(&expr))
+        else {
+            panic!("should be a let");
+        };
+        assert_eq!(
+            *body,
+            TypedHir {
+                id: NodeId::default(),
+                ty: Type::String,
+                expr: thir::Expr::Apply {
+                    function: Type::function(vec![trait_.clone()], Type::String),
+                    link_name: Default::default(),
+                    arguments: vec![TypedHir {
+                        id: NodeId::default(),
+                        ty: trait_,
+                        expr: thir::Expr::TraitObject(Box::new(TypedHir {
+                            id: NodeId::default(),
+                            ty: Type::Number,
+                            expr: thir::Expr::Literal(thir::Literal::Int(1)),
+                        })),
+                    }]
+                },
+            }
+        );
+    }
+
     #[test]
     fn function_and_reference() {
         let expr = parse(r#"\ 'number, 'string -> &'number"#);
@@ -345,6 +482,44 @@ mod tests {
         // );
     }
 
+    #[test]
+    fn hole() {
+        let expr = parse("^ ?: 'number");
+        let gen = TypedHirGen {
+            types: infer(&expr),
+            ..Default::default()
+        };
+        assert_eq!(
+            remove_id(gen.gen(&expr)),
+            TypedHir {
+                id: NodeId::default(),
+                ty: Type::Effectful {
+                    ty: Box::new(Type::Sum(vec![])),
+                    effects: EffectExpr::Effects(vec![Effect::hole()]),
+                },
+                expr: thir::Expr::Perform(Box::new(TypedHir {
+                    id: NodeId::default(),
+                    ty: Type::label("halt", Type::label("hole", Type::unit())),
+                    expr: thir::Expr::Label {
+                        label: "halt".into(),
+                        item: Box::new(TypedHir {
+                            id: NodeId::default(),
+                            ty: Type::label("hole", Type::unit()),
+                            expr: thir::Expr::Label {
+                                label: "hole".into(),
+                                item: Box::new(TypedHir {
+                                    id: NodeId::default(),
+                                    ty: Type::unit(),
+                                    expr: thir::Expr::Product(vec![]),
+                                }),
+                            },
+                        }),
+                    },
+                })),
+            }
+        );
+    }
+
     #[test]
     fn match_() {
         let expr = parse(
@@ -391,6 +566,4 @@ mod tests {
             }
         );
     }
-
-    // TODO: match exhaustive check
 }
@@ -14,6 +14,7 @@ pub(crate) fn into_op(op: &thir::BuiltinOp) -> Op {
         thir::BuiltinOp::Le => Op::Le,
         thir::BuiltinOp::Gt => Op::Gt,
         thir::BuiltinOp::Ge => Op::Ge,
+        thir::BuiltinOp::Cmp => Op::Cmp,
         thir::BuiltinOp::And => panic!(),
         thir::BuiltinOp::Or => panic!(),
         thir::BuiltinOp::Not => Op::Not,
@@ -24,5 +25,13 @@ pub(crate) fn into_op(op: &thir::BuiltinOp) -> Op {
         thir::BuiltinOp::BitNot => Op::BitNot,
         thir::BuiltinOp::Shl => Op::Shl,
         thir::BuiltinOp::Shr => Op::Shr,
+        thir::BuiltinOp::Length => Op::Length,
+        thir::BuiltinOp::Index => Op::Index,
+        thir::BuiltinOp::Push => Op::Push,
+        thir::BuiltinOp::Map => panic!("map is lowered to a loop"),
+        thir::BuiltinOp::Fold => panic!("fold is lowered to a loop"),
+        thir::BuiltinOp::Member => Op::Member,
+        thir::BuiltinOp::Union => Op::Union,
+        thir::BuiltinOp::Intersection => Op::Intersection,
     }
 }
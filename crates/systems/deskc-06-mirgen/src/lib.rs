@@ -3,14 +3,17 @@ mod into_op;
 mod mir_proto;
 mod scope_proto;
 
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Range};
+
+use ids::NodeId;
 
 use mir::{
     mir::{ControlFlowGraph, ControlFlowGraphId, Mir},
-    stmt::{Const, FnRef, MatchCase, Stmt, Terminator},
+    stmt::{Const, FnRef, MatchCase, Op, Stmt, Terminator},
     var::VarId,
 };
 use mir_proto::MirProto;
+use textual_diagnostics::{Report, TextualDiagnostics};
 use thir::{Handler, LinkName, TypedHir};
 use thiserror::Error;
 use types::Type;
@@ -20,6 +23,7 @@ pub fn gen_mir(thir: &TypedHir) -> Result<Mir, GenMirError> {
     gen.gen_mir(thir).map(|entrypoint_mir_id| Mir {
         entrypoint: entrypoint_mir_id,
         cfgs: gen.mirs,
+        links: Default::default(),
     })
 }
 
@@ -37,10 +41,69 @@ impl Default for MirGen {
     }
 }
 
-#[derive(Debug, Clone, Error)]
+/// An error at the expression of `id`, whose span is attached by `with_spans`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum GenMirError {
-    #[error("reference unknown var {0:?}")]
-    ReferencesUnknownVar(Type),
+    #[error("reference unknown var {ty}")]
+    ReferencesUnknownVar {
+        ty: Type,
+        id: NodeId,
+        span: Option<Range<usize>>,
+    },
+    #[error("trait object must have a trait type but {ty}")]
+    NotTrait {
+        ty: Type,
+        id: NodeId,
+        span: Option<Range<usize>>,
+    },
+    #[error("missing implementation {ty}")]
+    MissingImplementation {
+        ty: Type,
+        id: NodeId,
+        span: Option<Range<usize>>,
+    },
+}
+
+impl GenMirError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            GenMirError::ReferencesUnknownVar { .. } => "E0601",
+            GenMirError::NotTrait { .. } => "E0602",
+            GenMirError::MissingImplementation { .. } => "E0603",
+        }
+    }
+
+    /// Attaches the span of the expression, which THIR doesn't know.
+    pub fn with_spans(mut self, spans: &HashMap<NodeId, Range<usize>>) -> Self {
+        let (GenMirError::ReferencesUnknownVar { id, span, .. }
+        | GenMirError::NotTrait { id, span, .. }
+        | GenMirError::MissingImplementation { id, span, .. }) = &mut self;
+        if let Some(found) = spans.get(id) {
+            *span = Some(found.clone());
+        }
+        self
+    }
+
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            GenMirError::ReferencesUnknownVar { span, .. }
+            | GenMirError::NotTrait { span, .. }
+            | GenMirError::MissingImplementation { span, .. } => span.clone(),
+        }
+    }
+}
+
+impl From<GenMirError> for TextualDiagnostics {
+    fn from(error: GenMirError) -> TextualDiagnostics {
+        TextualDiagnostics {
+            title: "Mirgen error".into(),
+            reports: vec![Report::error(
+                error.code(),
+                error.span().unwrap_or(0..0),
+                error.to_string(),
+            )],
+        }
+    }
 }
 
 macro_rules! mir_proto {
@@ -128,6 +191,11 @@ impl MirGen {
                 self.mir_proto()
                     .bind_stmt(stmt_ty.clone(), Stmt::Perform(var))
             }
+            thir::Expr::Continue(input) => {
+                let var = self.gen_stmt(input)?;
+                self.mir_proto()
+                    .bind_stmt(stmt_ty.clone(), Stmt::Continue(var))
+            }
             thir::Expr::Handle { handlers, expr } => {
                 let handlers = handlers
                     .iter()
@@ -136,12 +204,14 @@ impl MirGen {
                         let handler_end = self.gen_stmt(handler)?;
                         let handler_mir = self.end_mir(handler_end, stmt_ty.clone());
                         let handler_type = self.get_mir(&handler_mir).get_type();
+                        // The input of the effect is given when handled.
+                        let captured = self.captured_vars(&handler_mir, Some(&effect.input));
                         // call effectful mir
                         let handler_var = self.mir_proto().bind_stmt(
                             handler_type,
                             Stmt::Fn(FnRef::Closure {
                                 mir: handler_mir,
-                                captured: vec![], // TODO
+                                captured,
                                 handlers: HashMap::new(),
                             }),
                         );
@@ -155,12 +225,13 @@ impl MirGen {
                 let effectful_end = self.gen_stmt(expr)?;
                 let effectful_mir = self.end_mir(effectful_end, stmt_ty.clone());
                 let effectful_type = self.get_mir(&effectful_mir).get_type();
+                let captured = self.captured_vars(&effectful_mir, None);
 
                 let effectful_fun = self.mir_proto().bind_stmt(
                     effectful_type,
                     Stmt::Fn(FnRef::Closure {
                         mir: effectful_mir,
-                        captured: vec![], // TODO
+                        captured,
                         handlers,
                     }),
                 );
@@ -182,6 +253,9 @@ impl MirGen {
                 thir::BuiltinOp::Or => {
                     todo!()
                 }
+                thir::BuiltinOp::Map | thir::BuiltinOp::Fold => {
+                    self.gen_iteration(stmt_ty, op, arguments)?
+                }
                 op => {
                     let arguments = arguments
                         .iter()
@@ -201,6 +275,11 @@ impl MirGen {
                 link_name,
                 arguments,
             } => {
+                if link_name == &LinkName::None {
+                    if let Some((object, method)) = find_method(function, arguments) {
+                        return self.gen_dispatch(stmt_ty, object, method, arguments);
+                    }
+                }
                 let function = if link_name != &LinkName::None {
                     self.mir_proto()
                         .bind_link(function.clone(), link_name.clone())
@@ -210,9 +289,39 @@ impl MirGen {
                 if arguments.is_empty() {
                     function
                 } else {
+                    let mut function_ty = self.mir_proto().get_var(&function).ty.clone();
+                    while let Type::Effectful { ty, effects: _ } | Type::ForAll { body: ty, .. } =
+                        function_ty
+                    {
+                        function_ty = *ty;
+                    }
+                    let parameters: Vec<_> = match function_ty {
+                        Type::Function { parameters, .. } => {
+                            parameters.iter().map(Type::without_quantifiers).collect()
+                        }
+                        _ => vec![],
+                    };
+                    // Parameters are sorted, so arguments are matched by the types, and the rest
+                    // are cast to the unmatched parameters.
+                    let mut unmatched = parameters.iter().filter(|parameter| {
+                        !arguments
+                            .iter()
+                            .any(|arg| &&arg.ty.without_quantifiers() == parameter)
+                    });
                     let arguments = arguments
                         .iter()
-                        .map(|arg| self.gen_stmt(arg))
+                        .map(|arg| {
+                            let var = self.gen_stmt(arg)?;
+                            if parameters.contains(&arg.ty.without_quantifiers()) {
+                                return Ok(var);
+                            }
+                            match unmatched.next() {
+                                Some(parameter) => Ok(self
+                                    .mir_proto()
+                                    .bind_stmt(parameter.clone(), Stmt::Cast(var))),
+                                None => Ok(var),
+                            }
+                        })
                         .collect::<Result<Vec<_>, _>>()?;
                     self.mir_proto().bind_stmt(
                         stmt_ty.clone(),
@@ -289,10 +398,200 @@ impl MirGen {
                     })?
                 }
             }
+            thir::Expr::TraitObject(value) => {
+                let methods = match stmt_ty {
+                    Type::Trait(methods) => methods,
+                    ty => {
+                        return Err(GenMirError::NotTrait {
+                            ty: ty.clone(),
+                            id: value.id.clone(),
+                            span: None,
+                        })
+                    }
+                };
+                let value_ty = match &value.ty {
+                    Type::Effectful { ty, effects: _ } => &**ty,
+                    ty => ty,
+                };
+                let methods = methods
+                    .iter()
+                    .map(|method| {
+                        let implementation = method.replace_this(value_ty);
+                        // An implementation must be defined in this or an enclosing function.
+                        if !self
+                            .protos
+                            .iter()
+                            .any(|proto| proto.has_named_var(&implementation))
+                        {
+                            return Err(GenMirError::MissingImplementation {
+                                ty: implementation,
+                                id: value.id.clone(),
+                                span: None,
+                            });
+                        }
+                        Ok((method.clone(), self.mir_proto().find_var(&implementation)))
+                    })
+                    .collect::<Result<_, _>>()?;
+                let value = self.gen_stmt(value)?;
+                self.mir_proto()
+                    .bind_stmt(stmt_ty.clone(), Stmt::TraitObject { value, methods })
+            }
         };
         Ok(var_id)
     }
 
+    fn gen_dispatch(
+        &mut self,
+        stmt_ty: &Type,
+        object: usize,
+        method: Type,
+        arguments: &[TypedHir],
+    ) -> Result<VarId, GenMirError> {
+        let arguments = arguments
+            .iter()
+            .map(|arg| self.gen_stmt(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.mir_proto().bind_stmt(
+            stmt_ty.clone(),
+            Stmt::Dispatch {
+                object: arguments[object],
+                method,
+                arguments,
+            },
+        ))
+    }
+
+    /// Generates a loop that applies the function to each item of the vector.
+    ///
+    /// Map pushes each result to a new vector, and fold passes each result to the next application.
+    fn gen_iteration(
+        &mut self,
+        stmt_ty: &Type,
+        op: &thir::BuiltinOp,
+        operands: &[TypedHir],
+    ) -> Result<VarId, GenMirError> {
+        let function_ty = &operands[operands.len() - 1].ty;
+        let (parameters, body) = match function_ty {
+            Type::Function { parameters, body } => (parameters, &**body),
+            _ => panic!("the last operand of {:?} must be a function", op),
+        };
+        let operands = operands
+            .iter()
+            .map(|operand| self.gen_stmt(operand))
+            .collect::<Result<Vec<_>, _>>()?;
+        let vector = operands[0];
+        let function = operands[operands.len() - 1];
+        let accumulator = self.mir_proto().create_var(stmt_ty.clone());
+        let (item_ty, accumulator_stmt) = match op {
+            thir::BuiltinOp::Map => (parameters[0].clone(), Stmt::Vector(vec![])),
+            // The parameter which is not the accumulator is the item.
+            _ => (
+                parameters
+                    .iter()
+                    .find(|parameter| *parameter != stmt_ty)
+                    .unwrap_or(stmt_ty)
+                    .clone(),
+                Stmt::Cast(operands[1]),
+            ),
+        };
+        self.mir_proto().bind_to(accumulator, accumulator_stmt);
+
+        let length = self.mir_proto().bind_stmt(
+            Type::Number,
+            Stmt::Op {
+                op: Op::Length,
+                operands: vec![vector],
+            },
+        );
+        let index = self
+            .mir_proto()
+            .bind_stmt(Type::Number, Stmt::Const(Const::Int(0)));
+        let one = self
+            .mir_proto()
+            .bind_stmt(Type::Number, Stmt::Const(Const::Int(1)));
+
+        // begin and defer the exit block and the head block
+        let exit_block_id = self.mir_proto().begin_block();
+        self.mir_proto().defer_block();
+        let head_block_id = self.mir_proto().begin_block();
+        self.mir_proto().defer_block();
+        self.mir_proto().end_block(Terminator::Goto(head_block_id));
+
+        // head block checks the index
+        self.mir_proto().pop_deferred_block();
+        let true_ty = Type::label("true", Type::unit());
+        let false_ty = Type::label("false", Type::unit());
+        let condition = self.mir_proto().bind_stmt(
+            Type::sum(vec![true_ty.clone(), false_ty.clone()]),
+            Stmt::Op {
+                op: Op::Lt,
+                operands: vec![index, length],
+            },
+        );
+
+        // body block applies the function
+        let body_block_id = self.mir_proto().begin_block();
+        let item = self.mir_proto().bind_stmt(
+            item_ty,
+            Stmt::Op {
+                op: Op::Index,
+                operands: vec![vector, index],
+            },
+        );
+        match op {
+            thir::BuiltinOp::Map => {
+                let result = self.mir_proto().bind_stmt(
+                    body.clone(),
+                    Stmt::Apply {
+                        function,
+                        arguments: vec![item],
+                    },
+                );
+                self.mir_proto().bind_to(
+                    accumulator,
+                    Stmt::Op {
+                        op: Op::Push,
+                        operands: vec![accumulator, result],
+                    },
+                );
+            }
+            _ => {
+                self.mir_proto().bind_to(
+                    accumulator,
+                    Stmt::Apply {
+                        function,
+                        arguments: vec![accumulator, item],
+                    },
+                );
+            }
+        }
+        self.mir_proto().bind_to(
+            index,
+            Stmt::Op {
+                op: Op::Add,
+                operands: vec![index, one],
+            },
+        );
+        self.mir_proto().end_block(Terminator::Goto(head_block_id));
+
+        self.mir_proto().end_block(Terminator::Match {
+            var: condition,
+            cases: vec![
+                MatchCase {
+                    ty: true_ty,
+                    next: body_block_id,
+                },
+                MatchCase {
+                    ty: false_ty,
+                    next: exit_block_id,
+                },
+            ],
+        });
+        // undefer the exit block
+        self.mir_proto().pop_deferred_block();
+        Ok(accumulator)
+    }
+
     fn gen_closure(&mut self, parameters: &[Type], body: &TypedHir) -> Result<FnRef, GenMirError> {
         // Begin new mir
         self.begin_mir();
@@ -309,12 +608,7 @@ impl MirGen {
         // Out of function
         let mir_id = self.end_mir(var, body.ty.clone());
 
-        let mir = get_mir!(self, mir_id);
-        let captured = mir
-            .captured
-            .iter()
-            .map(|ty| mir_proto!(self).find_var(ty))
-            .collect();
+        let captured = self.captured_vars(&mir_id, None);
 
         Ok(FnRef::Closure {
             mir: mir_id,
@@ -323,6 +617,16 @@ impl MirGen {
         })
     }
 
+    // Finds the variables captured by the mir in the current scope.
+    fn captured_vars(&mut self, mir_id: &ControlFlowGraphId, except: Option<&Type>) -> Vec<VarId> {
+        let mir = get_mir!(self, mir_id);
+        mir.captured
+            .iter()
+            .filter(|ty| Some(*ty) != except)
+            .map(|ty| mir_proto!(self).find_var(ty))
+            .collect()
+    }
+
     fn begin_mir(&mut self) {
         self.protos.push(MirProto::default());
     }
@@ -339,6 +643,20 @@ impl MirGen {
     }
 }
 
+// Finds the argument of a trait object which has the applied function as a method.
+fn find_method(function: &Type, arguments: &[TypedHir]) -> Option<(usize, Type)> {
+    arguments
+        .iter()
+        .enumerate()
+        .find_map(|(index, argument)| match &argument.ty {
+            Type::Trait(methods) => methods
+                .iter()
+                .find(|method| method.replace_this(&argument.ty) == *function)
+                .map(|method| (index, method.clone())),
+            _ => None,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use ids::NodeId;
@@ -383,4 +701,29 @@ mod tests {
             Terminator::Return(VarId(0))
         );
     }
+
+    #[test]
+    fn trait_object_without_implementation() {
+        let method = Type::function(vec![Type::This], Type::String);
+        let thir = TypedHir {
+            id: NodeId::default(),
+            ty: Type::Trait(vec![method]),
+            expr: thir::Expr::TraitObject(Box::new(TypedHir {
+                id: NodeId::default(),
+                ty: Type::Number,
+                expr: thir::Expr::Literal(thir::Literal::Int(1)),
+            })),
+        };
+        let error = MirGen::default().gen_mir(&thir).unwrap_err();
+        assert_eq!(
+            error,
+            GenMirError::MissingImplementation {
+                ty: Type::function(vec![Type::Number], Type::String),
+                id: NodeId::default(),
+                span: None,
+            }
+        );
+        let error = error.with_spans(&[(NodeId::default(), 3..5)].into_iter().collect());
+        assert_eq!(error.span(), Some(3..5));
+    }
 }
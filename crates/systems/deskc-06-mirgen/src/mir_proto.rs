@@ -119,6 +119,7 @@ impl MirProto {
     }
 
     pub fn find_var(&mut self, ty: &Type) -> VarId {
+        let ty = &ty.without_quantifiers();
         let mut next_scope_id = Some(self.current_scope_id());
         while let Some(scope_id) = next_scope_id {
             let scope = self.get_scope(&scope_id);
@@ -135,6 +136,20 @@ impl MirProto {
         })
     }
 
+    // Unlike `find_var`, this does not capture the variable.
+    pub fn has_named_var(&self, ty: &Type) -> bool {
+        let ty = &ty.without_quantifiers();
+        let mut next_scope_id = Some(self.current_scope_id());
+        while let Some(scope_id) = next_scope_id {
+            let scope = &self.scopes[scope_id.0];
+            next_scope_id = scope.super_id;
+            if scope.named_vars.contains_key(ty) {
+                return true;
+            }
+        }
+        false
+    }
+
     pub fn bind_link(&mut self, ty: Type, name: LinkName) -> VarId {
         self.links.insert(LinkId {
             ty: ty.clone(),
@@ -156,7 +171,9 @@ impl MirProto {
             Type::Effectful { ty, effects: _ } => *ty,
             ty => ty,
         };
-        self.current_scope().named_vars.insert(ty, var);
+        self.current_scope()
+            .named_vars
+            .insert(ty.without_quantifiers(), var);
     }
 
     pub fn get_var(&mut self, var_id: &VarId) -> &mut Var {
@@ -0,0 +1,370 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::Range,
+};
+
+use ids::LinkName;
+use mir::{
+    block::BasicBlock,
+    mir::{ControlFlowGraph, ControlFlowGraphId, Mir},
+    scope::{Scope, ScopeId},
+    stmt::{FnRef, LinkId, Stmt, StmtBind, Terminator},
+    var::{Var, VarId, Vars},
+};
+use textual_diagnostics::{Report, TextualDiagnostics};
+use thiserror::Error;
+use types::Type;
+
+/// Links the entrypoint and the cards reachable from it into one program.
+///
+/// Links are resolved by `(Type, LinkName)`, and the type must be the output of the card.
+/// All missing links are reported at once before the program runs.
+pub fn link(entrypoint: Mir, cards: &HashMap<LinkName, Mir>) -> Result<Mir, LinkErrors> {
+    let mut linker = Linker::default();
+    let entrypoint = linker.append(entrypoint);
+    while let Some(link) = linker.queue.pop_front() {
+        if linker.links.contains_key(&link) {
+            continue;
+        }
+        let card = match linker.cards.get(&link.name) {
+            Some(card) => *card,
+            None => match cards.get(&link.name) {
+                Some(mir) => {
+                    let card = linker.append(mir.clone());
+                    linker.cards.insert(link.name.clone(), card);
+                    card
+                }
+                None => {
+                    linker
+                        .errors
+                        .push(LinkError::MissingCard { link, span: None });
+                    continue;
+                }
+            },
+        };
+        let actual = &linker.cfgs[card.0].output;
+        if *actual == link.ty {
+            linker.links.insert(link, card);
+        } else if provides(actual, &link.ty) {
+            // The card is linked through a function which casts the output.
+            let adapter = linker.append(adapter(&link, actual.clone()));
+            linker.links.insert(link, adapter);
+        } else {
+            let actual = actual.clone();
+            linker.errors.push(LinkError::TypeMismatch {
+                link,
+                actual,
+                span: None,
+            });
+        }
+    }
+    if !linker.errors.is_empty() {
+        return Err(LinkErrors(linker.errors));
+    }
+    Ok(Mir {
+        entrypoint,
+        cfgs: linker.cfgs,
+        links: linker.links,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LinkError {
+    #[error("missing card {} of {}", .link.name, .link.ty)]
+    MissingCard {
+        link: LinkId,
+        span: Option<Range<usize>>,
+    },
+    #[error("card {} is {actual} but linked as {}", .link.name, .link.ty)]
+    TypeMismatch {
+        link: LinkId,
+        actual: Type,
+        span: Option<Range<usize>>,
+    },
+}
+
+impl LinkError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LinkError::MissingCard { .. } => "E0701",
+            LinkError::TypeMismatch { .. } => "E0702",
+        }
+    }
+
+    /// The span of the reference to the card.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            LinkError::MissingCard { span, .. } | LinkError::TypeMismatch { span, .. } => {
+                span.clone()
+            }
+        }
+    }
+}
+
+/// Errors in the order of discovery.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{0:?}")]
+pub struct LinkErrors(pub Vec<LinkError>);
+
+impl LinkErrors {
+    /// Attaches the spans of the references, which MIR doesn't know, to the errors.
+    pub fn with_spans(mut self, spans: &HashMap<LinkName, Range<usize>>) -> Self {
+        for error in self.0.iter_mut() {
+            let (LinkError::MissingCard { link, span }
+            | LinkError::TypeMismatch { link, span, .. }) = error;
+            if let Some(found) = spans.get(&link.name) {
+                *span = Some(found.clone());
+            }
+        }
+        self
+    }
+}
+
+impl From<LinkErrors> for TextualDiagnostics {
+    fn from(errors: LinkErrors) -> TextualDiagnostics {
+        TextualDiagnostics {
+            title: "Link error".into(),
+            reports: errors
+                .0
+                .into_iter()
+                .map(|error| {
+                    Report::error(
+                        error.code(),
+                        error.span().unwrap_or(0..0),
+                        error.to_string(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Whether a card exports the type by the output, which is the type with labels or a product of it.
+fn provides(output: &Type, ty: &Type) -> bool {
+    let ty = without_label(ty);
+    match without_label(output) {
+        Type::Product(types) => types.iter().any(|item| without_label(item) == ty),
+        output => output == ty,
+    }
+}
+
+fn without_label(mut ty: &Type) -> &Type {
+    while let Type::Label { item, .. } = ty {
+        ty = item;
+    }
+    ty
+}
+
+// Links to the card by its output and casts it to the linked type.
+fn adapter(link: &LinkId, output: Type) -> Mir {
+    let output_link = LinkId {
+        ty: output.clone(),
+        name: link.name.clone(),
+    };
+    Mir {
+        entrypoint: ControlFlowGraphId(0),
+        cfgs: vec![ControlFlowGraph {
+            parameters: vec![],
+            captured: vec![],
+            output: link.ty.clone(),
+            vars: Vars(vec![
+                Var {
+                    ty: output,
+                    scope: ScopeId(0),
+                },
+                Var {
+                    ty: link.ty.clone(),
+                    scope: ScopeId(0),
+                },
+            ]),
+            scopes: vec![Scope { super_scope: None }],
+            blocks: vec![BasicBlock {
+                stmts: vec![
+                    StmtBind {
+                        var: VarId(0),
+                        stmt: Stmt::Link(link.name.clone()),
+                    },
+                    StmtBind {
+                        var: VarId(1),
+                        stmt: Stmt::Cast(VarId(0)),
+                    },
+                ],
+                terminator: Terminator::Return(VarId(1)),
+            }],
+            links: vec![output_link],
+        }],
+        links: Default::default(),
+    }
+}
+
+#[derive(Default)]
+struct Linker {
+    cfgs: Vec<ControlFlowGraph>,
+    links: HashMap<LinkId, ControlFlowGraphId>,
+    // Entrypoints of the appended cards
+    cards: HashMap<LinkName, ControlFlowGraphId>,
+    queue: VecDeque<LinkId>,
+    errors: Vec<LinkError>,
+}
+
+impl Linker {
+    // Appends the control flow graphs and returns the new ID of the entrypoint.
+    fn append(&mut self, mir: Mir) -> ControlFlowGraphId {
+        let offset = self.cfgs.len();
+        let shift = |id: ControlFlowGraphId| ControlFlowGraphId(id.0 + offset);
+        for mut cfg in mir.cfgs {
+            for stmt in cfg
+                .blocks
+                .iter_mut()
+                .flat_map(|block| block.stmts.iter_mut())
+            {
+                if let Stmt::Fn(FnRef::Closure { mir, .. }) = &mut stmt.stmt {
+                    *mir = shift(*mir);
+                }
+            }
+            self.queue.extend(cfg.links.iter().cloned());
+            self.cfgs.push(cfg);
+        }
+        // A linked program may be linked again.
+        for (link, card) in mir.links {
+            self.links.insert(link, shift(card));
+        }
+        shift(mir.entrypoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mir::stmt::Const;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn card(id: u128) -> LinkName {
+        LinkName::Card(Uuid::from_u128(id))
+    }
+
+    fn mir(stmt: Stmt, links: Vec<LinkId>) -> Mir {
+        Mir {
+            entrypoint: ControlFlowGraphId(0),
+            cfgs: vec![ControlFlowGraph {
+                parameters: vec![],
+                captured: vec![],
+                output: Type::Number,
+                vars: Vars(vec![Var {
+                    ty: Type::Number,
+                    scope: ScopeId(0),
+                }]),
+                scopes: vec![Scope { super_scope: None }],
+                blocks: vec![BasicBlock {
+                    stmts: vec![StmtBind {
+                        var: VarId(0),
+                        stmt,
+                    }],
+                    terminator: Terminator::Return(VarId(0)),
+                }],
+                links,
+            }],
+            links: Default::default(),
+        }
+    }
+
+    fn link_to(name: LinkName, ty: Type) -> Mir {
+        let link = LinkId { ty, name };
+        mir(Stmt::Link(link.name.clone()), vec![link])
+    }
+
+    #[test]
+    fn links_cards() {
+        let cards = [
+            (card(1), link_to(card(2), Type::Number)),
+            (card(2), mir(Stmt::Const(Const::Int(1)), vec![])),
+            (card(3), mir(Stmt::Const(Const::Int(3)), vec![])),
+        ]
+        .into_iter()
+        .collect();
+        let linked = link(link_to(card(1), Type::Number), &cards).unwrap();
+        // Unreachable card 3 is not linked.
+        assert_eq!(linked.cfgs.len(), 3);
+        assert_eq!(linked.entrypoint, ControlFlowGraphId(0));
+        assert_eq!(
+            linked.links,
+            [
+                (
+                    LinkId {
+                        ty: Type::Number,
+                        name: card(1)
+                    },
+                    ControlFlowGraphId(1)
+                ),
+                (
+                    LinkId {
+                        ty: Type::Number,
+                        name: card(2)
+                    },
+                    ControlFlowGraphId(2)
+                ),
+            ]
+            .into_iter()
+            .collect()
+        );
+    }
+
+    #[test]
+    fn reports_all_missing_links() {
+        let cards = [(card(1), link_to(card(2), Type::Number))]
+            .into_iter()
+            .collect();
+        let entrypoint = mir(
+            Stmt::Link(card(1)),
+            vec![
+                LinkId {
+                    ty: Type::Number,
+                    name: card(1),
+                },
+                LinkId {
+                    ty: Type::String,
+                    name: card(1),
+                },
+                LinkId {
+                    ty: Type::Number,
+                    name: card(3),
+                },
+            ],
+        );
+        assert_eq!(
+            link(entrypoint, &cards),
+            Err(LinkErrors(vec![
+                LinkError::TypeMismatch {
+                    link: LinkId {
+                        ty: Type::String,
+                        name: card(1)
+                    },
+                    actual: Type::Number,
+                    span: None,
+                },
+                LinkError::MissingCard {
+                    link: LinkId {
+                        ty: Type::Number,
+                        name: card(3)
+                    },
+                    span: None,
+                },
+                LinkError::MissingCard {
+                    link: LinkId {
+                        ty: Type::Number,
+                        name: card(2)
+                    },
+                    span: None,
+                },
+            ]))
+        );
+    }
+
+    #[test]
+    fn attaches_spans() {
+        let errors = link(link_to(card(1), Type::Number), &HashMap::new()).unwrap_err();
+        let errors = errors.with_spans(&[(card(1), 3..5)].into_iter().collect());
+        assert_eq!(errors.0[0].span(), Some(3..5));
+    }
+}
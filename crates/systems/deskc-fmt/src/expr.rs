@@ -0,0 +1,254 @@
+use ast::{
+    expr::{Expr, Handler, LinkName, Literal, MatchCase},
+    span::WithSpan,
+    ty::{CommentPosition, Type},
+};
+use uuid::Uuid;
+
+use crate::Formatter;
+
+impl Formatter {
+    /// `terminated` is false if the following token might be parsed as a part of this expression.
+    pub(crate) fn expr(&mut self, expr: &WithSpan<Expr>, terminated: bool) {
+        match &expr.value {
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Hole => self.token("?"),
+            Expr::Let {
+                ty,
+                definition,
+                body,
+            } => {
+                self.token("$");
+                self.expr(definition, true);
+                if ty.value != Type::Infer {
+                    self.colon();
+                    self.ty(ty, true);
+                }
+                self.token("~");
+                self.newline();
+                self.expr(body, terminated);
+            }
+            Expr::Perform { input, output } => {
+                self.token("!");
+                self.expr(input, true);
+                self.token("=>");
+                self.ty(output, terminated);
+            }
+            Expr::Continue { input, output } => {
+                self.token("<!");
+                if let Some(output) = output {
+                    self.expr(input, true);
+                    self.token("=>");
+                    self.ty(output, terminated);
+                } else {
+                    self.expr(input, terminated);
+                }
+            }
+            Expr::Handle { expr, handlers } => {
+                self.token("'handle");
+                self.expr(expr, true);
+                self.token("~");
+                self.indented(|f| {
+                    f.list(
+                        handlers,
+                        terminated,
+                        true,
+                        |f,
+                         Handler {
+                             input,
+                             output,
+                             handler,
+                         },
+                         terminated| {
+                            f.ty(input, true);
+                            f.token("=>");
+                            f.ty(output, true);
+                            f.token("->");
+                            f.expr(handler, terminated);
+                        },
+                    )
+                });
+            }
+            Expr::Apply {
+                function,
+                link_name,
+                arguments,
+            } => {
+                let has_link_name = *link_name != LinkName::None;
+                if arguments.is_empty() {
+                    self.token("&");
+                    self.ty(function, terminated || has_link_name);
+                    self.link_name(link_name);
+                } else {
+                    self.token(">");
+                    self.ty(function, true);
+                    self.link_name(link_name);
+                    self.token("~");
+                    self.list(arguments, terminated, false, |f, argument, terminated| {
+                        f.expr(argument, terminated)
+                    });
+                }
+            }
+            Expr::Product(items) => {
+                self.token("*");
+                self.list(items, terminated, false, |f, item, terminated| {
+                    f.expr(item, terminated)
+                });
+            }
+            Expr::Match { of, cases } => {
+                self.token("+");
+                self.expr(of, true);
+                self.token("~");
+                self.indented(|f| {
+                    f.list(
+                        cases,
+                        terminated,
+                        true,
+                        |f, MatchCase { ty, expr }, terminated| {
+                            f.ty(ty, true);
+                            f.token("->");
+                            f.expr(expr, terminated);
+                        },
+                    )
+                });
+            }
+            Expr::Typed { ty, item } => {
+                self.token("^");
+                self.expr(item, true);
+                self.colon();
+                self.ty(ty, terminated);
+            }
+            Expr::Function { parameters, body } => {
+                self.token("\\");
+                self.items(parameters, true, false, |f, parameter, terminated| {
+                    f.ty(parameter, terminated)
+                });
+                self.token("->");
+                self.expr(body, terminated);
+            }
+            Expr::Vector(items) => {
+                self.token("[");
+                self.items(items, true, false, |f, item, terminated| {
+                    f.expr(item, terminated)
+                });
+                self.token("]");
+            }
+            Expr::Set(items) => {
+                self.token("{");
+                self.items(items, true, false, |f, item, terminated| {
+                    f.expr(item, terminated)
+                });
+                self.token("}");
+            }
+            Expr::Import { ty, link_name } => {
+                self.token("'import");
+                self.ty(ty, terminated || *link_name != LinkName::None);
+                self.link_name(link_name);
+            }
+            Expr::Export { ty } => {
+                self.token("'export");
+                self.ty(ty, terminated);
+            }
+            Expr::Attribute { attr, item } => {
+                self.token("#");
+                self.expr(attr, true);
+                self.token("~");
+                self.newline();
+                self.expr(item, terminated);
+            }
+            Expr::Brand { brands, item } => {
+                self.token("'brand");
+                self.items(brands, true, false, |f, brand, _| f.ident(brand));
+                self.token("~");
+                self.newline();
+                self.expr(item, terminated);
+            }
+            Expr::Label { label, item } => {
+                self.token("@");
+                self.ident(label);
+                self.expr(item, terminated);
+            }
+            Expr::Unbrand { item } => {
+                self.token("'unbrand");
+                self.expr(item, terminated);
+            }
+            Expr::NewType { ident, ty, expr } => {
+                self.token("'type");
+                self.ident(ident);
+                self.ty(ty, true);
+                self.token("~");
+                self.newline();
+                self.expr(expr, terminated);
+            }
+            Expr::Comment {
+                position: CommentPosition::Prefix,
+                text,
+                item,
+            } => {
+                self.token(text);
+                self.expr(item, terminated);
+            }
+            Expr::Comment {
+                position: CommentPosition::Suffix,
+                text,
+                item,
+            } => {
+                self.expr(item, false);
+                self.token(text);
+            }
+            Expr::Card { uuid, item, next } => {
+                self.token("'card");
+                self.uuid(uuid);
+                self.newline();
+                if let Some(next) = next {
+                    self.expr(item, true);
+                    self.token("~");
+                    self.newline();
+                    self.expr(next, terminated);
+                } else {
+                    self.expr(item, terminated);
+                }
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        match literal {
+            Literal::String(string) => {
+                let mut escaped = String::with_capacity(string.len() + 2);
+                escaped.push('"');
+                for c in string.chars() {
+                    match c {
+                        '\\' => escaped.push_str(r"\\"),
+                        '"' => escaped.push_str(r#"\""#),
+                        '\n' => escaped.push_str(r"\n"),
+                        '\r' => escaped.push_str(r"\r"),
+                        '\t' => escaped.push_str(r"\t"),
+                        c => escaped.push(c),
+                    }
+                }
+                escaped.push('"');
+                self.token(&escaped);
+            }
+            Literal::Integer(integer) => self.token(&integer.to_string()),
+            Literal::Rational(a, b) => self.token(&format!("{}/{}", a, b)),
+            // Debug format always has a fraction or an exponent, so it's not read as an integer.
+            Literal::Float(float) => self.token(&format!("{:?}", float)),
+        }
+    }
+
+    fn link_name(&mut self, link_name: &LinkName) {
+        match link_name {
+            LinkName::None => {}
+            LinkName::Card(uuid) => {
+                self.token("'card");
+                self.uuid(uuid);
+            }
+            LinkName::Version(uuid) => self.uuid(uuid),
+        }
+    }
+
+    fn uuid(&mut self, uuid: &Uuid) {
+        self.token(&format!("'uuid {}", uuid));
+    }
+}
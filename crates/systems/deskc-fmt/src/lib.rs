@@ -1 +1,313 @@
+mod expr;
+mod ty;
 
+use ast::{expr::Expr, span::WithSpan, ty::Type};
+
+/// Prints the expression in the canonical Hacker syntax.
+///
+/// `parse(scan(format(ast)))` equals to `ast` modulo spans except for a few ASTs the parser never
+/// produces (e.g. a bounded variable right after a brand name).
+pub fn format(expr: &WithSpan<Expr>) -> String {
+    let mut formatter = Formatter::default();
+    formatter.expr(expr, true);
+    formatter.out
+}
+
+/// Prints the type in the canonical Hacker syntax.
+pub fn format_type(ty: &WithSpan<Type>) -> String {
+    let mut formatter = Formatter::default();
+    formatter.ty(ty, true);
+    formatter.out
+}
+
+#[derive(Default)]
+pub(crate) struct Formatter {
+    out: String,
+    indent: usize,
+    last: Last,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum Last {
+    #[default]
+    Other,
+    // Tokens that must not be followed by a space.
+    Opening,
+    Ident,
+    // Variable without `'a` and the position it starts.
+    Variable(usize),
+}
+
+const INDENT: usize = 2;
+
+impl Formatter {
+    pub(crate) fn token(&mut self, token: &str) {
+        self.space_for(token);
+        self.out.push_str(token);
+        self.last = if matches!(token, "[" | "{" | "@" | "&") {
+            Last::Opening
+        } else {
+            Last::Other
+        };
+    }
+
+    /// Identifiers that are not type variables such as labels.
+    pub(crate) fn ident(&mut self, ident: &str) {
+        self.space_for(ident);
+        self.out.push_str(ident);
+        self.last = Last::Ident;
+    }
+
+    pub(crate) fn variable(&mut self, ident: &str) {
+        if matches!(self.last, Last::Ident | Last::Variable(_)) {
+            // Without `'a`, two identifiers are lexed as one identifier.
+            self.token("'a");
+            self.ident(ident);
+        } else {
+            self.space_for(ident);
+            let start = self.out.len();
+            self.out.push_str(ident);
+            self.last = Last::Variable(start);
+        }
+    }
+
+    /// `:` after a variable would make it a bounded variable, so `'a` is inserted before it.
+    pub(crate) fn colon(&mut self) {
+        if let Last::Variable(start) = self.last {
+            self.out.insert_str(start, "'a ");
+        }
+        self.token(":");
+    }
+
+    pub(crate) fn newline(&mut self) {
+        while self.out.ends_with(' ') {
+            self.out.pop();
+        }
+        self.out.push('\n');
+        self.out.push_str(&" ".repeat(self.indent));
+        self.last = Last::Other;
+    }
+
+    pub(crate) fn indented(&mut self, f: impl FnOnce(&mut Self)) {
+        self.indent += INDENT;
+        f(self);
+        self.indent -= INDENT;
+    }
+
+    /// Comma separated items which are terminated by an optional `.`.
+    ///
+    /// `terminated` is false if a following token might be parsed as a part of this list.
+    pub(crate) fn list<T>(
+        &mut self,
+        items: &[T],
+        terminated: bool,
+        newline: bool,
+        mut f: impl FnMut(&mut Self, &T, bool),
+    ) {
+        self.items(items, terminated, newline, &mut f);
+        if !terminated && !items.is_empty() {
+            self.token(".");
+        }
+    }
+
+    /// Comma separated items without a terminator.
+    pub(crate) fn items<T>(
+        &mut self,
+        items: &[T],
+        terminated: bool,
+        newline: bool,
+        mut f: impl FnMut(&mut Self, &T, bool),
+    ) {
+        for (index, item) in items.iter().enumerate() {
+            let last = index + 1 == items.len();
+            if newline {
+                self.newline();
+            }
+            f(self, item, last && terminated);
+            if !last {
+                self.token(",");
+            }
+        }
+    }
+
+    fn space_for(&mut self, token: &str) {
+        let needs_space = !(self.out.is_empty()
+            || self.out.ends_with(char::is_whitespace)
+            || self.last == Last::Opening
+            || matches!(token, "," | "." | "]" | "}"));
+        if needs_space {
+            self.out.push(' ');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::remove_span::remove_span;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn parse(input: &str) -> WithSpan<Expr> {
+        let mut expr = parser::parse(lexer::scan(input).unwrap()).unwrap();
+        remove_span(&mut expr);
+        expr
+    }
+
+    fn assert_round_trip(input: &str) {
+        let expr = parse(input);
+        let formatted = format(&expr);
+        let tokens =
+            lexer::scan(&formatted).unwrap_or_else(|err| panic!("{}\n{:?}", formatted, err));
+        let mut actual =
+            parser::parse(tokens).unwrap_or_else(|err| panic!("{}\n{:?}", formatted, err));
+        remove_span(&mut actual);
+        assert_eq!(actual, expr, "{}", formatted);
+    }
+
+    #[test]
+    fn formats_literals() {
+        assert_eq!(
+            format(&parse(r#"* 1, -2, 3/4, "a\"\\\n", ?"#)),
+            r#"* 1, -2, 3/4, "a\"\\\n", ?"#
+        );
+    }
+
+    #[test]
+    fn formats_let() {
+        assert_eq!(
+            format(&parse("$ 1: 'number ~ $ 2 ~ &'number")),
+            "$ 1 : 'number ~\n$ 2 ~\n&'number"
+        );
+    }
+
+    #[test]
+    fn formats_match() {
+        assert_eq!(
+            format(&parse("+ 1 ~ 'number -> 1, 'string -> 2")),
+            "+ 1 ~\n  'number -> 1,\n  'string -> 2"
+        );
+    }
+
+    #[test]
+    fn round_trips_literals() {
+        assert_round_trip(r#"* 1, -2, 3/4, "a\"\\\n\t\r", ?"#);
+        assert_round_trip("* 1.5, -250.0, 1e-20, 1e300");
+    }
+
+    #[test]
+    fn round_trips_nested_lists() {
+        assert_round_trip("* * 1, 2., 3");
+        assert_round_trip("* 1, * 2, 3");
+        assert_round_trip("> \\ * 'number, 'number., 'string -> + 'number, 'string ~ * 1, 2., 3");
+        assert_round_trip("[* 1, 2., * 3]");
+        assert_round_trip("{1, 2}");
+        assert_round_trip("*");
+    }
+
+    #[test]
+    fn round_trips_let_and_typed() {
+        assert_round_trip("$ 1 ~ 2");
+        assert_round_trip("$ &'a x : 'a x ~ &x");
+        assert_round_trip("^ &'a x : 'number");
+        assert_round_trip(
+            "$ ^ 1 : 'number ~ ^ &x 'card 'uuid 9883b420-f7be-468d-95f6-000000000000 : x",
+        );
+    }
+
+    #[test]
+    fn round_trips_functions_and_applies() {
+        assert_round_trip("\\ 'number, x -> > \\ x -> 'number ~ &x");
+        assert_round_trip("\\ -> 1");
+        assert_round_trip("> add 'card 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa ~ 1, 2");
+        assert_round_trip("& 'number 'card 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa");
+    }
+
+    #[test]
+    fn round_trips_effects() {
+        assert_round_trip(
+            "'handle ! 1 => 'string ~ 'number => 'string -> <! \"a\", 'string => 'number -> <! 1 => 'number",
+        );
+        assert_round_trip("^ 1 : ! 'number {'number => 'string, 'string => 'number}");
+        assert_round_trip("^ 1 : ! 'number + {'number => 'string}, > a ~ 'number, 'string");
+        assert_round_trip("^ 1 : ! 'number - + {}, {'number => 'string}., {}");
+        assert_round_trip("^ 1 : ! 'number > a ~");
+    }
+
+    #[test]
+    fn round_trips_types() {
+        assert_round_trip(
+            "^ ? : * % 'number, 'string., _, 'this, [x], {'a y}, $ x ~ x, x: 'number, @a 'a b, @a * 'number, 'string",
+        );
+        assert_round_trip("^ ? : # 1 ~ 'number");
+        assert_round_trip("'type a 'a x ~ 'type b @c 'a y ~ &b");
+    }
+
+    #[test]
+    fn round_trips_brands_labels_and_attributes() {
+        assert_round_trip("'brand a, b ~ @a 1");
+        assert_round_trip("'brand ~ @a * 1, 2");
+        assert_round_trip("'brand a ~ ^ 'unbrand @a 1 : 'brand a ~ @a 'number");
+        assert_round_trip("# 1 ~ # * 1, 2. ~ 3");
+    }
+
+    #[test]
+    fn round_trips_comments() {
+        assert_round_trip("(a) * 1 (b), (c) 2 (d)");
+        assert_round_trip("* 1, 2. (a)");
+        assert_round_trip("^ 1 : (a) * 'number (b), 'string. (c)");
+        assert_round_trip("(a (b)) $ 1 ~ (c) 2");
+    }
+
+    #[test]
+    fn round_trips_cards() {
+        assert_round_trip(
+            r#"
+            'card 'uuid 9883b420-f7be-468d-95f6-000000000000
+            > \'number -> 'number 'card 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa ~ 10
+            ~
+            'card 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa
+            \ 'number ->
+              ^> add
+                &'number,
+                + >cmp &'number, 3 ~
+                  + @greater *, @equal * ->
+                    > \ 'number -> 'number 'card 'uuid 9883b420-f7be-468d-95f6-bbbbbbbbbbbb ~ &'number;
+                  @less * ->
+                    0
+              : 'number
+            "#,
+        );
+    }
+
+    #[test]
+    fn round_trips_fibonacci() {
+        assert_round_trip(
+            r#"
+            (defines aliases of types)
+            'type add \ 'number, 'number -> @sum 'number ~
+            'type sub \ @minuend 'number, @subtrahend 'number -> 'number ~
+            'type eq \ 'number, 'number -> + @equal *, @unequal * ~
+            $ \ 'number ->
+              + >eq &'number, 0 ~
+                (if the number == 0)
+                @equal * ->
+                  0,
+                @unequal * ->
+                  + >eq &'number, 1 ~
+                    @equal * ->
+                      1,
+                    @unequal * ->
+                      ^>add
+                        > \'number -> 'number
+                          >sub @minuend &'number, 1. ;
+                        > \'number -> 'number
+                          >sub @minuend &'number, 2
+                      (`^expr: type` is a type annotation)
+                      : 'number
+            ~
+            > \'number -> 'number ~ 7
+            "#,
+        );
+    }
+}
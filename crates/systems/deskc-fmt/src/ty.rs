@@ -0,0 +1,154 @@
+use ast::{
+    span::WithSpan,
+    ty::{CommentPosition, Effect, EffectExpr, Type},
+};
+
+use crate::Formatter;
+
+impl Formatter {
+    /// `terminated` is false if the following token might be parsed as a part of this type.
+    pub(crate) fn ty(&mut self, ty: &WithSpan<Type>, terminated: bool) {
+        match &ty.value {
+            Type::Brand { brand, item } => {
+                self.token("@");
+                self.ident(brand);
+                self.ty(item, terminated);
+            }
+            Type::Brands { brands, item } => {
+                self.token("'brand");
+                self.items(brands, true, false, |f, brand, _| f.ident(brand));
+                self.token("~");
+                self.ty(item, terminated);
+            }
+            Type::Number => self.token("'number"),
+            Type::String => self.token("'string"),
+            Type::Trait(types) => {
+                self.token("%");
+                self.list(types, terminated, false, |f, ty, terminated| {
+                    f.ty(ty, terminated)
+                });
+            }
+            Type::Effectful { ty, effects } => {
+                self.token("!");
+                self.ty(ty, true);
+                self.effects(effects, terminated);
+            }
+            Type::Infer => self.token("_"),
+            Type::This => self.token("'this"),
+            Type::Product(types) => {
+                self.token("*");
+                self.list(types, terminated, false, |f, ty, terminated| {
+                    f.ty(ty, terminated)
+                });
+            }
+            Type::Sum(types) => {
+                self.token("+");
+                self.list(types, terminated, false, |f, ty, terminated| {
+                    f.ty(ty, terminated)
+                });
+            }
+            Type::Function { parameters, body } => {
+                self.token("\\");
+                self.items(parameters, true, false, |f, parameter, terminated| {
+                    f.ty(parameter, terminated)
+                });
+                self.token("->");
+                self.ty(body, terminated);
+            }
+            Type::Vector(item) => {
+                self.token("[");
+                self.ty(item, true);
+                self.token("]");
+            }
+            Type::Set(item) => {
+                self.token("{");
+                self.ty(item, true);
+                self.token("}");
+            }
+            Type::Let { variable, body } => {
+                self.token("$");
+                self.ident(variable);
+                self.token("~");
+                self.ty(body, terminated);
+            }
+            Type::Variable(ident) => self.variable(ident),
+            Type::BoundedVariable { bound, identifier } => {
+                self.ident(identifier);
+                self.token(":");
+                self.ty(bound, terminated);
+            }
+            Type::Attribute { attr, ty } => {
+                self.token("#");
+                self.expr(attr, true);
+                self.token("~");
+                self.ty(ty, terminated);
+            }
+            Type::Comment {
+                position: CommentPosition::Prefix,
+                text,
+                item,
+            } => {
+                self.token(text);
+                self.ty(item, terminated);
+            }
+            Type::Comment {
+                position: CommentPosition::Suffix,
+                text,
+                item,
+            } => {
+                self.ty(item, false);
+                self.token(text);
+            }
+        }
+    }
+
+    fn effects(&mut self, effects: &WithSpan<EffectExpr>, terminated: bool) {
+        match &effects.value {
+            EffectExpr::Effects(effects) => {
+                self.token("{");
+                self.items(
+                    effects,
+                    true,
+                    false,
+                    |f,
+                     WithSpan {
+                         value: Effect { input, output },
+                         ..
+                     },
+                     terminated| {
+                        f.ty(input, true);
+                        f.token("=>");
+                        f.ty(output, terminated);
+                    },
+                );
+                self.token("}");
+            }
+            EffectExpr::Add(effects) => {
+                self.token("+");
+                self.list(effects, terminated, false, |f, effects, terminated| {
+                    f.effects(effects, terminated)
+                });
+            }
+            EffectExpr::Sub {
+                minuend,
+                subtrahend,
+            } => {
+                self.token("-");
+                self.effects(minuend, false);
+                self.token(",");
+                self.effects(subtrahend, terminated);
+            }
+            EffectExpr::Apply {
+                function,
+                arguments,
+            } => {
+                self.token(">");
+                self.ty(function, true);
+                self.token("~");
+                self.list(arguments, terminated, false, |f, argument, terminated| {
+                    f.ty(argument, terminated)
+                });
+            }
+        }
+    }
+}
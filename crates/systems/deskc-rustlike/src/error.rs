@@ -0,0 +1,68 @@
+use std::fmt::Debug;
+
+use chumsky::{error::SimpleReason, prelude::Simple};
+use textual_diagnostics::{unexpected_text, Report, TextualDiagnostics};
+use thiserror::Error;
+
+use crate::token::Token;
+
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum RustLikeError {
+    #[error("{0:?}")]
+    Lexer(Vec<Simple<char>>),
+    #[error("{0:?}")]
+    Parser(Vec<Simple<Token>>),
+}
+
+impl From<RustLikeError> for TextualDiagnostics {
+    fn from(error: RustLikeError) -> TextualDiagnostics {
+        // Codes are shared with Hacker syntax.
+        match error {
+            RustLikeError::Lexer(errors) => TextualDiagnostics {
+                title: "Lexer error".into(),
+                reports: errors
+                    .into_iter()
+                    .map(|error| report(["E0101", "E0102", "E0103"], error))
+                    .collect(),
+            },
+            RustLikeError::Parser(errors) => TextualDiagnostics {
+                title: "Parser error".into(),
+                reports: errors
+                    .into_iter()
+                    .map(|error| report(["E0201", "E0202", "E0203"], error))
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Codes are for unexpected input, an unclosed delimiter, and an invalid literal.
+fn report<T: Debug + std::hash::Hash + Eq>(
+    [unexpected, unclosed, custom]: [&'static str; 3],
+    error: Simple<T>,
+) -> Report {
+    match error.reason() {
+        SimpleReason::Unexpected => Report::error(
+            unexpected,
+            error.span(),
+            unexpected_text(
+                error.found().map(|found| format!("{:?}", found)),
+                error
+                    .expected()
+                    .map(|expected| {
+                        expected
+                            .as_ref()
+                            .map_or("end of input".into(), |expected| format!("{:?}", expected))
+                    })
+                    .collect(),
+            ),
+        ),
+        SimpleReason::Unclosed { span, delimiter } => Report::error(
+            unclosed,
+            error.span(),
+            format!("unclosed delimiter {:?}", delimiter),
+        )
+        .with_label(span.clone(), "opened here"),
+        SimpleReason::Custom(text) => Report::error(custom, error.span(), text),
+    }
+}
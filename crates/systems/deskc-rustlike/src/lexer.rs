@@ -0,0 +1,165 @@
+use chumsky::prelude::*;
+
+use crate::token::{Token, Tokens, KEYWORDS};
+
+pub fn lexer() -> impl Parser<char, Tokens, Error = Simple<char>> {
+    let comment = just("/*")
+        .ignore_then(take_until(just("*/")))
+        .map(|(text, _)| Token::Comment(format!("({})", text.into_iter().collect::<String>())));
+    let escape = just('\\').ignore_then(
+        just('\\')
+            .or(just('"'))
+            .or(just('`'))
+            .or(just('n').to('\n'))
+            .or(just('r').to('\r'))
+            .or(just('t').to('\t')),
+    );
+    let string = just('"')
+        .ignore_then(filter(|c| *c != '\\' && *c != '"').or(escape).repeated())
+        .then_ignore(just('"'))
+        .collect::<String>()
+        .map(Token::Str);
+    let raw_ident = just('`')
+        .ignore_then(filter(|c| *c != '\\' && *c != '`').or(escape).repeated())
+        .then_ignore(just('`'))
+        .collect::<String>()
+        .map(Token::Ident);
+    let ident = text::ident().map(|ident: String| {
+        if ident == "_" {
+            return Token::Infer;
+        }
+        KEYWORDS
+            .iter()
+            .find(|(keyword, _)| *keyword == ident)
+            .map(|(_, token)| token.clone())
+            .unwrap_or(Token::Ident(ident))
+    });
+    let fraction = just('.').chain::<char, _, _>(text::digits(10));
+    let exponent = one_of("eE")
+        .chain::<char, _, _>(one_of("+-").or_not())
+        .chain::<char, _, _>(text::digits(10));
+    let number = just('-')
+        .or_not()
+        .chain::<char, _, _>(text::int(10))
+        .chain::<char, _, _>(fraction.or_not().flatten())
+        .chain::<char, _, _>(exponent.or_not().flatten())
+        .collect::<String>()
+        .try_map(|number, span| {
+            if number.contains(['.', 'e', 'E']) {
+                Ok(Token::Float(number))
+            } else {
+                number
+                    .parse()
+                    .map(Token::Int)
+                    .map_err(|_| Simple::custom(span, "integer overflow"))
+            }
+        });
+    let symbol = just("::")
+        .to(Token::PathSeparator)
+        .or(just("->").to(Token::Arrow))
+        .or(just("=>").to(Token::EArrow))
+        .or(just('(').to(Token::ParenBegin))
+        .or(just(')').to(Token::ParenEnd))
+        .or(just('[').to(Token::ArrayBegin))
+        .or(just(']').to(Token::ArrayEnd))
+        .or(just('{').to(Token::SetBegin))
+        .or(just('}').to(Token::SetEnd))
+        .or(just('<').to(Token::AngleBegin))
+        .or(just('>').to(Token::AngleEnd))
+        .or(just(',').to(Token::Comma))
+        .or(just(';').to(Token::Semicolon))
+        .or(just(':').to(Token::Colon))
+        .or(just('=').to(Token::Equal))
+        .or(just('|').to(Token::Bar))
+        .or(just('&').to(Token::Reference))
+        .or(just('@').to(Token::Label))
+        .or(just('#').to(Token::Attribute))
+        .or(just('!').to(Token::Bang))
+        .or(just('?').to(Token::Hole))
+        .or(just('/').to(Token::Divide));
+    let token = comment
+        .or(string)
+        .or(raw_ident)
+        .or(ident)
+        .or(number)
+        .or(symbol);
+    token
+        .map_with_span(|token, span| (token, span))
+        .padded()
+        .repeated()
+        .then_ignore(end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(input: &str) -> Vec<Token> {
+        lexer()
+            .parse(input)
+            .unwrap()
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect()
+    }
+
+    #[test]
+    fn all_syntax() {
+        use Token::*;
+        assert_eq!(
+            scan(
+                r#"
+                /* comment */
+                let add(1, -2/3): @sum number; `a b` |Self, _| 1.5e-3 "a\"\n" ? :: => -> # [ ] { } < > = ! match
+                "#
+            ),
+            vec![
+                Comment("( comment )".into()),
+                Let,
+                Ident("add".into()),
+                ParenBegin,
+                Int(1),
+                Comma,
+                Int(-2),
+                Divide,
+                Int(3),
+                ParenEnd,
+                Colon,
+                Label,
+                Ident("sum".into()),
+                NumberType,
+                Semicolon,
+                Ident("a b".into()),
+                Bar,
+                This,
+                Comma,
+                Infer,
+                Bar,
+                Float("1.5e-3".into()),
+                Str("a\"\n".into()),
+                Hole,
+                PathSeparator,
+                EArrow,
+                Arrow,
+                Attribute,
+                ArrayBegin,
+                ArrayEnd,
+                SetBegin,
+                SetEnd,
+                AngleBegin,
+                AngleEnd,
+                Equal,
+                Bang,
+                Match,
+            ]
+        );
+    }
+
+    #[test]
+    fn identifier_with_keyword_prefix() {
+        assert_eq!(
+            scan("letter _a"),
+            vec![Token::Ident("letter".into()), Token::Ident("_a".into())]
+        );
+    }
+}
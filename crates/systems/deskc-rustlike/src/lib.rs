@@ -0,0 +1,169 @@
+//! RustLike syntax of Desk-lang.
+//!
+//! It parses into the same AST as Hacker syntax, so that a code can be converted between syntaxes.
+//!
+//! ```text
+//! type add = fn(number, number) -> @sum number;
+//! let |number| match eq(&number, 0) {
+//!     @equal () => 0,
+//!     @unequal () => add(&number, 1),
+//! };
+//! <fn(number) -> number>(2)
+//! ```
+pub mod error;
+mod lexer;
+mod parser;
+mod printer;
+pub mod token;
+
+use ast::{expr::Expr, span::WithSpan};
+use chumsky::{Parser, Stream};
+use error::RustLikeError;
+use printer::Printer;
+use token::Tokens;
+
+pub fn scan(input: &str) -> Result<Tokens, RustLikeError> {
+    lexer::lexer().parse(input).map_err(RustLikeError::Lexer)
+}
+
+pub fn parse(input: &str) -> Result<WithSpan<Expr>, RustLikeError> {
+    let tokens = scan(input)?;
+    let len = input.chars().count();
+    parser::parser()
+        .then_ignore(chumsky::primitive::end())
+        .parse(Stream::from_iter(len..len + 1, tokens.into_iter()))
+        .map_err(RustLikeError::Parser)
+}
+
+/// Prints the expression in RustLike syntax.
+pub fn format(expr: &WithSpan<Expr>) -> String {
+    let mut printer = Printer::default();
+    printer.expr(expr);
+    printer.out
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::{
+        expr::{LinkName, Literal},
+        remove_span::remove_span,
+        span::dummy_span,
+        ty::Type,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn parse_hacker(input: &str) -> WithSpan<Expr> {
+        let mut expr = deskc_parser::parse(deskc_lexer::scan(input).unwrap()).unwrap();
+        remove_span(&mut expr);
+        expr
+    }
+
+    fn parse_rust_like(input: &str) -> WithSpan<Expr> {
+        let mut expr = parse(input).unwrap_or_else(|err| panic!("{}\n{:?}", input, err));
+        remove_span(&mut expr);
+        expr
+    }
+
+    fn assert_round_trip(expr: &WithSpan<Expr>) {
+        let formatted = format(expr);
+        assert_eq!(&parse_rust_like(&formatted), expr, "{}", formatted);
+    }
+
+    #[test]
+    fn parses_rust_like() {
+        assert_eq!(
+            parse_rust_like(
+                r#"
+                type add = fn(number, number) -> @sum number;
+                let |number| match eq(&number, 0) {
+                    @equal () => 0,
+                    @unequal () => add(&number, 1),
+                };
+                <fn(number) -> number>(2)
+                "#
+            ),
+            parse_hacker(
+                r#"
+                'type add \ 'number, 'number -> @sum 'number ~
+                $ \ 'number -> + > eq ~ &'number, 0 ~
+                    @equal * -> 0,
+                    @unequal * -> > add ~ &'number, 1.
+                ~
+                > \ 'number -> 'number ~ 2
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn formats_rust_like() {
+        assert_eq!(
+            format(&parse_hacker(
+                "$ 1: 'number ~ + &'number ~ 'number -> > add 'card 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa ~ (a) 1, \"a\""
+            )),
+            r#"let 1: number;
+match &number {
+    number => card!("9883b420-f7be-468d-95f6-aaaaaaaaaaaa")::add(/*a*/ 1, "a"),
+}"#
+        );
+    }
+
+    #[test]
+    fn round_trips_hacker_sources() {
+        for source in [
+            r#"* 1, -2, 3/4, "a\"\\\n\t\r", ?, [1, 2], {"a"}"#,
+            "'handle ! 1 => 'string ~ 'number => 'string -> <! \"a\", 'string => 'number -> <! 1 => 'number",
+            "^ 1 : ! 'number + {'number => 'string}, - {}, > a ~ 'number, 'string",
+            "^ ? : * % 'number, 'string., _, 'this, [x], {'a y}, $ x ~ x, x: 'number, @a 'a b, + a, b",
+            "'brand a, b ~ 'brand ~ @a # 1 ~ 'type b 'a x ~ \\ -> &'a x 'card 'uuid 9883b420-f7be-468d-95f6-aaaaaaaaaaaa",
+            "'brand a ~ ^ 'unbrand @a 1 : 'brand a ~ @a 'number",
+            "(a) * 1 (b), (c) 2 (d)",
+            "^ 1 : (a) * 'number (b), 'string. (c)",
+            "> @a 'number ~ 1",
+            "> (a) 'number ~ 1",
+            "> $ x ~ x ~ 1",
+            "> # 1 ~ x ~ 1",
+            "> * 'number ~ 1",
+            "> Vec ~ > card ~ &Effectful",
+            "'card 'uuid 9883b420-f7be-468d-95f6-000000000000 1 ~ 'card 'uuid 9883b420-f7be-468d-95f6-000000000001 2",
+        ] {
+            assert_round_trip(&parse_hacker(source));
+        }
+    }
+
+    #[test]
+    fn round_trips_what_hacker_syntax_cannot_express() {
+        assert_round_trip(&dummy_span(Expr::Product(vec![
+            dummy_span(Expr::Literal(Literal::Float(1.5))),
+            dummy_span(Expr::Literal(Literal::Float(-1e-20))),
+            dummy_span(Expr::Import {
+                ty: dummy_span(Type::Variable("let".into())),
+                link_name: LinkName::Card(uuid::Uuid::from_u128(1)),
+            }),
+            dummy_span(Expr::Export {
+                ty: dummy_span(Type::Variable("a `b`".into())),
+            }),
+            dummy_span(Expr::Apply {
+                function: dummy_span(Type::Number),
+                link_name: LinkName::Version(uuid::Uuid::from_u128(2)),
+                arguments: vec![],
+            }),
+            dummy_span(Expr::Brand {
+                brands: vec!["number".into(), "_".into()],
+                item: Box::new(dummy_span(Expr::Hole)),
+            }),
+        ])));
+    }
+
+    #[test]
+    fn reports_lexer_error() {
+        assert!(matches!(parse("\"a"), Err(RustLikeError::Lexer(_))));
+    }
+
+    #[test]
+    fn reports_parser_error() {
+        assert!(matches!(parse("let 1"), Err(RustLikeError::Parser(_))));
+    }
+}
@@ -0,0 +1,530 @@
+// chumsky requires closures returning `Simple<Token>` as the error.
+#![allow(clippy::result_large_err)]
+
+use ast::{
+    expr::{Expr, Handler, LinkName, Literal, MatchCase},
+    span::WithSpan,
+    ty::{CommentPosition, Effect, EffectExpr, Type},
+};
+use chumsky::prelude::*;
+use ids::NodeId;
+use uuid::Uuid;
+
+use crate::token::Token;
+
+pub fn parser() -> impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + Clone {
+    recursive(|expr| {
+        let type_ = ty_parser(expr.clone());
+        let hole = just(Token::Hole).to(Expr::Hole);
+        let int64 = filter_map(|span, token| match token {
+            Token::Int(int) => Ok(int),
+            _ => Err(Simple::custom(span, "expected int literal")),
+        });
+        let rational = int64
+            .then_ignore(just(Token::Divide))
+            .then(int64)
+            .map(|(a, b)| Expr::Literal(Literal::Rational(a, b)));
+        let float = filter_map(|span, token| match token {
+            Token::Float(float) => float
+                .parse()
+                .map(|float| Expr::Literal(Literal::Float(float)))
+                .map_err(|_| Simple::custom(span, "invalid float literal")),
+            _ => Err(Simple::custom(span, "expected float literal")),
+        });
+        let string = filter_map(|span, token| match token {
+            Token::Str(string) => Ok(Expr::Literal(Literal::String(string))),
+            _ => Err(Simple::custom(span, "expected string literal")),
+        });
+        let literal = rational
+            .or(int64.map(|int| Expr::Literal(Literal::Integer(int))))
+            .or(float)
+            .or(string);
+        let let_in = just(Token::Let)
+            .ignore_then(expr.clone())
+            .then(
+                just(Token::Colon)
+                    .ignore_then(type_.clone())
+                    .or_not()
+                    .map(|ty| {
+                        ty.unwrap_or(WithSpan {
+                            id: NodeId::new(),
+                            value: Type::Infer,
+                            span: 0..0,
+                        })
+                    }),
+            )
+            .then_ignore(just(Token::Semicolon))
+            .then(expr.clone())
+            .map(|((definition, ty), body)| Expr::Let {
+                ty,
+                definition: Box::new(definition),
+                body: Box::new(body),
+            });
+        let perform = just(Token::Perform)
+            .ignore_then(expr.clone())
+            .then_ignore(just(Token::EArrow))
+            .then(type_.clone())
+            .map(|(input, output)| Expr::Perform {
+                input: Box::new(input),
+                output,
+            });
+        let continue_ = just(Token::Continue)
+            .ignore_then(expr.clone())
+            .then(just(Token::EArrow).ignore_then(type_.clone()).or_not())
+            .map(|(input, output)| Expr::Continue {
+                input: Box::new(input),
+                output,
+            });
+        let handle = just(Token::Handle)
+            .ignore_then(expr.clone())
+            .then(block(
+                type_
+                    .clone()
+                    .then_ignore(just(Token::EArrow))
+                    .then(type_.clone())
+                    .then_ignore(just(Token::Arrow))
+                    .then(expr.clone())
+                    .map(|((input, output), handler)| Handler {
+                        input,
+                        output,
+                        handler,
+                    }),
+            ))
+            .map(|(expr, handlers)| Expr::Handle {
+                expr: Box::new(expr),
+                handlers,
+            });
+        let match_ = just(Token::Match)
+            .ignore_then(expr.clone())
+            .then(block(
+                type_
+                    .clone()
+                    .then_ignore(just(Token::EArrow))
+                    .then(expr.clone())
+                    .map(|(ty, expr)| MatchCase { ty, expr }),
+            ))
+            .map(|(of, cases)| Expr::Match {
+                of: Box::new(of),
+                cases,
+            });
+        let typed = expr
+            .clone()
+            .then_ignore(just(Token::As))
+            .then(type_.clone())
+            .delimited_by(just(Token::ParenBegin), just(Token::ParenEnd))
+            .map(|(item, ty)| Expr::Typed {
+                ty,
+                item: Box::new(item),
+            });
+        let function = type_
+            .clone()
+            .separated_by(just(Token::Comma))
+            .delimited_by(just(Token::Bar), just(Token::Bar))
+            .then(expr.clone())
+            .map(|(parameters, body)| Expr::Function {
+                parameters,
+                body: Box::new(body),
+            });
+        let vector = list(Token::ArrayBegin, expr.clone(), Token::ArrayEnd).map(Expr::Vector);
+        let set = list(Token::SetBegin, expr.clone(), Token::SetEnd).map(Expr::Set);
+
+        let export = macro_call("export")
+            .ignore_then(
+                type_
+                    .clone()
+                    .delimited_by(just(Token::ParenBegin), just(Token::ParenEnd)),
+            )
+            .map(|ty| Expr::Export { ty });
+        let attribute = parse_attr(expr.clone())
+            .then(expr.clone())
+            .map(|(attr, item)| Expr::Attribute {
+                attr: Box::new(attr),
+                item: Box::new(item),
+            });
+        let brand = just(Token::Brand)
+            .ignore_then(parse_ident().separated_by(just(Token::Comma)))
+            .then_ignore(just(Token::Semicolon))
+            .then(expr.clone())
+            .map(|(brands, item)| Expr::Brand {
+                brands,
+                item: Box::new(item),
+            });
+        let label = just(Token::Label)
+            .ignore_then(parse_ident())
+            .then(expr.clone())
+            .map(|(label, item)| Expr::Label {
+                label,
+                item: Box::new(item),
+            });
+        let unbrand = macro_call("unbrand")
+            .ignore_then(
+                expr.clone()
+                    .delimited_by(just(Token::ParenBegin), just(Token::ParenEnd)),
+            )
+            .map(|item| Expr::Unbrand {
+                item: Box::new(item),
+            });
+        let newtype = just(Token::Type)
+            .ignore_then(parse_ident())
+            .then_ignore(just(Token::Equal))
+            .then(type_.clone())
+            .then_ignore(just(Token::Semicolon))
+            .then(expr.clone())
+            .map(|((ident, ty), expr)| Expr::NewType {
+                ident,
+                ty,
+                expr: Box::new(expr),
+            });
+        let prefix_comment = parse_comment()
+            .then(expr.clone())
+            .map(|(text, item)| Expr::Comment {
+                position: CommentPosition::Prefix,
+                text,
+                item: Box::new(item),
+            });
+        let card = macro_call("card")
+            .ignore_then(parse_uuid().delimited_by(just(Token::ParenBegin), just(Token::ParenEnd)))
+            .then(
+                expr.clone()
+                    .delimited_by(just(Token::SetBegin), just(Token::SetEnd)),
+            )
+            .then(expr.clone().or_not())
+            .map(|((uuid, item), next)| Expr::Card {
+                uuid,
+                item: Box::new(item),
+                next: next.map(Box::new),
+            });
+        let link_name = macro_call("card")
+            .to(LinkName::Card as fn(Uuid) -> LinkName)
+            .or(macro_call("version").to(LinkName::Version as fn(Uuid) -> LinkName))
+            .then(parse_uuid().delimited_by(just(Token::ParenBegin), just(Token::ParenEnd)))
+            .then_ignore(just(Token::PathSeparator))
+            .map(|(link_name, uuid)| link_name(uuid))
+            .or_not()
+            .map(|link_name| link_name.unwrap_or(LinkName::None));
+        let import = link_name
+            .clone()
+            .then_ignore(macro_call("import"))
+            .then(
+                type_
+                    .clone()
+                    .delimited_by(just(Token::ParenBegin), just(Token::ParenEnd)),
+            )
+            .map(|(link_name, ty)| Expr::Import { ty, link_name });
+        let reference = just(Token::Reference)
+            .ignore_then(link_name.clone())
+            .then(type_.clone())
+            .map(|(link_name, function)| Expr::Apply {
+                function,
+                link_name,
+                arguments: vec![],
+            });
+        let apply = link_name
+            .then(
+                type_
+                    .clone()
+                    .delimited_by(just(Token::AngleBegin), just(Token::AngleEnd))
+                    .or(type_.clone()),
+            )
+            .then(
+                expr.clone()
+                    .separated_by(just(Token::Comma))
+                    .allow_trailing()
+                    .at_least(1)
+                    .delimited_by(just(Token::ParenBegin), just(Token::ParenEnd)),
+            )
+            .map(|((link_name, function), arguments)| Expr::Apply {
+                function,
+                link_name,
+                arguments,
+            });
+        let product = list(Token::ParenBegin, expr.clone(), Token::ParenEnd).map(Expr::Product);
+        hole.or(prefix_comment)
+            .or(literal.labelled("literal"))
+            .or(let_in.labelled("let"))
+            .or(perform.labelled("perform"))
+            .or(continue_.labelled("continue"))
+            .or(handle.labelled("handle"))
+            .or(match_.labelled("match"))
+            .or(typed.labelled("typed"))
+            .or(function.labelled("function"))
+            .or(vector.labelled("vector"))
+            .or(set.labelled("set"))
+            .or(import.labelled("import"))
+            .or(export.labelled("export"))
+            .or(attribute.labelled("attribute"))
+            .or(brand.labelled("brand"))
+            .or(label.labelled("label"))
+            .or(unbrand.labelled("unbrand"))
+            .or(newtype.labelled("newtype"))
+            .or(card.labelled("card"))
+            .or(reference.labelled("reference"))
+            .or(apply.labelled("apply"))
+            .or(product.labelled("product"))
+            .map_with_span(|value, span| WithSpan {
+                id: NodeId::new(),
+                value,
+                span,
+            })
+            .then(parse_comment().or_not())
+            .map_with_span(|(expr, comment), span| match comment {
+                Some(text) => WithSpan {
+                    id: NodeId::new(),
+                    value: Expr::Comment {
+                        position: CommentPosition::Suffix,
+                        text,
+                        item: Box::new(expr),
+                    },
+                    span,
+                },
+                None => expr,
+            })
+    })
+}
+
+fn ty_parser(
+    expr: impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + Clone + 'static,
+) -> impl Parser<Token, WithSpan<Type>, Error = Simple<Token>> + Clone {
+    recursive(|type_| {
+        let infer = just(Token::Infer).to(Type::Infer);
+        let this = just(Token::This).to(Type::This);
+        let number = just(Token::NumberType).to(Type::Number);
+        let string = just(Token::StringType).to(Type::String);
+        let trait_ = just(Token::Trait)
+            .ignore_then(list(Token::SetBegin, type_.clone(), Token::SetEnd))
+            .map(Type::Trait);
+        let sum = just(Token::Enum)
+            .ignore_then(list(Token::SetBegin, type_.clone(), Token::SetEnd))
+            .map(Type::Sum);
+        let effectful = keyword("Effectful")
+            .ignore_then(
+                type_
+                    .clone()
+                    .then_ignore(just(Token::Comma))
+                    .then(effects_parser(type_.clone()))
+                    .delimited_by(just(Token::AngleBegin), just(Token::AngleEnd)),
+            )
+            .map(|(ty, effects)| Type::Effectful {
+                ty: Box::new(ty),
+                effects,
+            });
+        let vector = keyword("Vec")
+            .ignore_then(
+                type_
+                    .clone()
+                    .delimited_by(just(Token::AngleBegin), just(Token::AngleEnd)),
+            )
+            .map(|ty| Type::Vector(Box::new(ty)));
+        let set = keyword("Set")
+            .ignore_then(
+                type_
+                    .clone()
+                    .delimited_by(just(Token::AngleBegin), just(Token::AngleEnd)),
+            )
+            .map(|ty| Type::Set(Box::new(ty)));
+        let function = just(Token::Fn)
+            .ignore_then(list(Token::ParenBegin, type_.clone(), Token::ParenEnd))
+            .then_ignore(just(Token::Arrow))
+            .then(type_.clone())
+            .map(|(parameters, body)| Type::Function {
+                parameters,
+                body: Box::new(body),
+            });
+        let bound = parse_ident()
+            .then_ignore(just(Token::Colon))
+            .then(type_.clone())
+            .delimited_by(just(Token::ParenBegin), just(Token::ParenEnd))
+            .map(|(identifier, bound)| Type::BoundedVariable {
+                bound: Box::new(bound),
+                identifier,
+            });
+        let product = list(Token::ParenBegin, type_.clone(), Token::ParenEnd).map(Type::Product);
+        let let_in = just(Token::Let)
+            .ignore_then(parse_ident())
+            .then_ignore(just(Token::Semicolon))
+            .then(type_.clone())
+            .map(|(variable, body)| Type::Let {
+                variable,
+                body: Box::new(body),
+            });
+        let brand = just(Token::Label)
+            .ignore_then(parse_ident())
+            .then(type_.clone())
+            .map(|(brand, item)| Type::Brand {
+                brand,
+                item: Box::new(item),
+            });
+        let brands = just(Token::Brand)
+            .ignore_then(parse_ident().separated_by(just(Token::Comma)))
+            .then_ignore(just(Token::Semicolon))
+            .then(type_.clone())
+            .map(|(brands, item)| Type::Brands {
+                brands,
+                item: Box::new(item),
+            });
+        let attribute = parse_attr(expr)
+            .then(type_.clone())
+            .map(|(attr, ty)| Type::Attribute {
+                attr: Box::new(attr),
+                ty: Box::new(ty),
+            });
+        let variable = parse_ident().map(Type::Variable);
+        let prefix_comment =
+            parse_comment()
+                .then(type_.clone())
+                .map(|(text, item)| Type::Comment {
+                    position: CommentPosition::Prefix,
+                    text,
+                    item: Box::new(item),
+                });
+        infer
+            .or(prefix_comment)
+            .or(this)
+            .or(number)
+            .or(string)
+            .or(trait_)
+            .or(sum)
+            .or(effectful)
+            .or(vector)
+            .or(set)
+            .or(function)
+            .or(bound)
+            .or(product)
+            .or(let_in)
+            .or(brand)
+            .or(brands)
+            .or(attribute)
+            .or(variable)
+            .map_with_span(|value, span| WithSpan {
+                id: NodeId::new(),
+                value,
+                span,
+            })
+            .then(parse_comment().or_not())
+            .map_with_span(|(ty, comment), span| match comment {
+                Some(text) => WithSpan {
+                    id: NodeId::new(),
+                    value: Type::Comment {
+                        position: CommentPosition::Suffix,
+                        text,
+                        item: Box::new(ty),
+                    },
+                    span,
+                },
+                None => ty,
+            })
+    })
+}
+
+fn effects_parser(
+    type_: impl Parser<Token, WithSpan<Type>, Error = Simple<Token>> + Clone + 'static,
+) -> impl Parser<Token, WithSpan<EffectExpr>, Error = Simple<Token>> + Clone {
+    recursive(|effects| {
+        let set = list(
+            Token::SetBegin,
+            type_
+                .clone()
+                .then_ignore(just(Token::EArrow))
+                .then(type_.clone())
+                .map_with_span(|(input, output), span| WithSpan {
+                    id: NodeId::new(),
+                    value: Effect { input, output },
+                    span,
+                }),
+            Token::SetEnd,
+        )
+        .map(EffectExpr::Effects);
+        let add = keyword("add")
+            .ignore_then(list(Token::ParenBegin, effects.clone(), Token::ParenEnd))
+            .map(EffectExpr::Add);
+        let sub = keyword("sub")
+            .ignore_then(
+                effects
+                    .clone()
+                    .then_ignore(just(Token::Comma))
+                    .then(effects)
+                    .delimited_by(just(Token::ParenBegin), just(Token::ParenEnd)),
+            )
+            .map(|(minuend, subtrahend)| EffectExpr::Sub {
+                minuend: Box::new(minuend),
+                subtrahend: Box::new(subtrahend),
+            });
+        let apply = keyword("apply")
+            .ignore_then(
+                type_
+                    .clone()
+                    .then(just(Token::Comma).ignore_then(type_).repeated())
+                    .delimited_by(just(Token::ParenBegin), just(Token::ParenEnd)),
+            )
+            .map(|(function, arguments)| EffectExpr::Apply {
+                function: Box::new(function),
+                arguments,
+            });
+        set.or(add)
+            .or(sub)
+            .or(apply)
+            .map_with_span(|value, span| WithSpan {
+                id: NodeId::new(),
+                value,
+                span,
+            })
+    })
+}
+
+fn list<T>(
+    begin: Token,
+    item: impl Parser<Token, T, Error = Simple<Token>> + Clone,
+    end: Token,
+) -> impl Parser<Token, Vec<T>, Error = Simple<Token>> + Clone {
+    item.separated_by(just(Token::Comma))
+        .allow_trailing()
+        .delimited_by(just(begin), just(end))
+}
+
+fn block<T>(
+    item: impl Parser<Token, T, Error = Simple<Token>> + Clone,
+) -> impl Parser<Token, Vec<T>, Error = Simple<Token>> + Clone {
+    item.separated_by(just(Token::Comma))
+        .allow_trailing()
+        .at_least(1)
+        .delimited_by(just(Token::SetBegin), just(Token::SetEnd))
+}
+
+fn parse_attr(
+    expr: impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + Clone,
+) -> impl Parser<Token, WithSpan<Expr>, Error = Simple<Token>> + Clone {
+    just(Token::Attribute)
+        .ignore_then(expr.delimited_by(just(Token::ArrayBegin), just(Token::ArrayEnd)))
+}
+
+fn parse_comment() -> impl Parser<Token, String, Error = Simple<Token>> + Clone {
+    filter_map(|span, token| match token {
+        Token::Comment(text) => Ok(text),
+        _ => Err(Simple::custom(span, "expected comment")),
+    })
+}
+
+fn parse_ident() -> impl Parser<Token, String, Error = Simple<Token>> + Clone {
+    filter_map(|span, token| match token {
+        Token::Ident(ident) => Ok(ident),
+        _ => Err(Simple::custom(span, "expected identifier")),
+    })
+}
+
+fn parse_uuid() -> impl Parser<Token, Uuid, Error = Simple<Token>> + Clone {
+    filter_map(|span, token| match token {
+        Token::Str(uuid) => uuid
+            .parse()
+            .map_err(|_| Simple::custom(span, "invalid uuid")),
+        _ => Err(Simple::custom(span, "expected uuid")),
+    })
+}
+
+/// Identifiers which are keywords only in some contexts such as `Vec` in `Vec<number>`.
+fn keyword(keyword: &'static str) -> impl Parser<Token, Token, Error = Simple<Token>> + Clone {
+    just(Token::Ident(keyword.into()))
+}
+
+fn macro_call(name: &'static str) -> impl Parser<Token, Token, Error = Simple<Token>> + Clone {
+    keyword(name).then_ignore(just(Token::Bang))
+}
@@ -0,0 +1,445 @@
+use ast::{
+    expr::{Expr, Handler, LinkName, Literal, MatchCase},
+    span::WithSpan,
+    ty::{CommentPosition, Effect, EffectExpr, Type},
+};
+use uuid::Uuid;
+
+use crate::token::KEYWORDS;
+
+const INDENT: usize = 4;
+
+#[derive(Default)]
+pub(crate) struct Printer {
+    pub out: String,
+    indent: usize,
+}
+
+impl Printer {
+    pub fn expr(&mut self, expr: &WithSpan<Expr>) {
+        match &expr.value {
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Hole => self.push("?"),
+            Expr::Let {
+                ty,
+                definition,
+                body,
+            } => {
+                self.push("let ");
+                self.expr(definition);
+                if ty.value != Type::Infer {
+                    self.push(": ");
+                    self.ty(ty);
+                }
+                self.push(";");
+                self.newline();
+                self.expr(body);
+            }
+            Expr::Perform { input, output } => {
+                self.push("perform ");
+                self.expr(input);
+                self.push(" => ");
+                self.ty(output);
+            }
+            Expr::Continue { input, output } => {
+                self.push("continue ");
+                self.expr(input);
+                if let Some(output) = output {
+                    self.push(" => ");
+                    self.ty(output);
+                }
+            }
+            Expr::Handle { expr, handlers } => {
+                self.push("handle ");
+                self.expr(expr);
+                self.block(
+                    handlers,
+                    |p,
+                     Handler {
+                         input,
+                         output,
+                         handler,
+                     }| {
+                        p.ty(input);
+                        p.push(" => ");
+                        p.ty(output);
+                        p.push(" -> ");
+                        p.expr(handler);
+                    },
+                );
+            }
+            Expr::Apply {
+                function,
+                link_name,
+                arguments,
+            } => {
+                if arguments.is_empty() {
+                    self.push("&");
+                    self.link_name(link_name);
+                    self.ty(function);
+                } else {
+                    self.link_name(link_name);
+                    // Without angle brackets, it would be parsed as a label, an attribute, etc. of
+                    // the application, or hard to read like `fn() -> number(1)`.
+                    let needs_angle = matches!(
+                        function.value,
+                        Type::Function { .. }
+                            | Type::Brand { .. }
+                            | Type::Brands { .. }
+                            | Type::Attribute { .. }
+                            | Type::Let { .. }
+                            | Type::Comment {
+                                position: CommentPosition::Prefix,
+                                ..
+                            }
+                    );
+                    if needs_angle {
+                        self.push("<");
+                        self.ty(function);
+                        self.push(">");
+                    } else {
+                        self.ty(function);
+                    }
+                    self.list("(", arguments, ")", Self::expr);
+                }
+            }
+            Expr::Product(items) => self.tuple(items, Self::expr),
+            Expr::Match { of, cases } => {
+                self.push("match ");
+                self.expr(of);
+                self.block(cases, |p, MatchCase { ty, expr }| {
+                    p.ty(ty);
+                    p.push(" => ");
+                    p.expr(expr);
+                });
+            }
+            Expr::Typed { ty, item } => {
+                self.push("(");
+                self.expr(item);
+                self.push(" as ");
+                self.ty(ty);
+                self.push(")");
+            }
+            Expr::Function { parameters, body } => {
+                self.list("|", parameters, "| ", Self::ty);
+                self.expr(body);
+            }
+            Expr::Vector(items) => self.list("[", items, "]", Self::expr),
+            Expr::Set(items) => self.list("{", items, "}", Self::expr),
+            Expr::Import { ty, link_name } => {
+                self.link_name(link_name);
+                self.push("import!(");
+                self.ty(ty);
+                self.push(")");
+            }
+            Expr::Export { ty } => {
+                self.push("export!(");
+                self.ty(ty);
+                self.push(")");
+            }
+            Expr::Attribute { attr, item } => {
+                self.attr(attr);
+                self.expr(item);
+            }
+            Expr::Brand { brands, item } => {
+                self.push("brand");
+                for (index, brand) in brands.iter().enumerate() {
+                    self.push(if index == 0 { " " } else { ", " });
+                    self.ident(brand);
+                }
+                self.push(";");
+                self.newline();
+                self.expr(item);
+            }
+            Expr::Label { label, item } => {
+                self.push("@");
+                self.ident(label);
+                self.push(" ");
+                self.expr(item);
+            }
+            Expr::Unbrand { item } => {
+                self.push("unbrand!(");
+                self.expr(item);
+                self.push(")");
+            }
+            Expr::NewType { ident, ty, expr } => {
+                self.push("type ");
+                self.ident(ident);
+                self.push(" = ");
+                self.ty(ty);
+                self.push(";");
+                self.newline();
+                self.expr(expr);
+            }
+            Expr::Comment {
+                position,
+                text,
+                item,
+            } => self.comment(position, text, |p| p.expr(item)),
+            Expr::Card { uuid, item, next } => {
+                self.push("card!(");
+                self.uuid(uuid);
+                self.push(") {");
+                self.indent += INDENT;
+                self.newline();
+                self.expr(item);
+                self.indent -= INDENT;
+                self.newline();
+                self.push("}");
+                if let Some(next) = next {
+                    self.newline();
+                    self.expr(next);
+                }
+            }
+        }
+    }
+
+    pub fn ty(&mut self, ty: &WithSpan<Type>) {
+        match &ty.value {
+            Type::Brand { brand, item } => {
+                self.push("@");
+                self.ident(brand);
+                self.push(" ");
+                self.ty(item);
+            }
+            Type::Brands { brands, item } => {
+                self.push("brand");
+                for (index, brand) in brands.iter().enumerate() {
+                    self.push(if index == 0 { " " } else { ", " });
+                    self.ident(brand);
+                }
+                self.push("; ");
+                self.ty(item);
+            }
+            Type::Number => self.push("number"),
+            Type::String => self.push("string"),
+            Type::Trait(types) => {
+                self.push("trait ");
+                self.list("{ ", types, " }", Self::ty);
+            }
+            Type::Effectful { ty, effects } => {
+                self.push("Effectful<");
+                self.ty(ty);
+                self.push(", ");
+                self.effects(effects);
+                self.push(">");
+            }
+            Type::Infer => self.push("_"),
+            Type::This => self.push("Self"),
+            Type::Product(types) => self.tuple(types, Self::ty),
+            Type::Sum(types) => {
+                self.push("enum ");
+                self.list("{ ", types, " }", Self::ty);
+            }
+            Type::Function { parameters, body } => {
+                self.push("fn");
+                self.list("(", parameters, ") -> ", Self::ty);
+                self.ty(body);
+            }
+            Type::Vector(item) => {
+                self.push("Vec<");
+                self.ty(item);
+                self.push(">");
+            }
+            Type::Set(item) => {
+                self.push("Set<");
+                self.ty(item);
+                self.push(">");
+            }
+            Type::Let { variable, body } => {
+                self.push("let ");
+                self.ident(variable);
+                self.push("; ");
+                self.ty(body);
+            }
+            Type::Variable(ident) => self.ident(ident),
+            Type::BoundedVariable { bound, identifier } => {
+                self.push("(");
+                self.ident(identifier);
+                self.push(": ");
+                self.ty(bound);
+                self.push(")");
+            }
+            Type::Attribute { attr, ty } => {
+                self.attr(attr);
+                self.ty(ty);
+            }
+            Type::Comment {
+                position,
+                text,
+                item,
+            } => self.comment(position, text, |p| p.ty(item)),
+        }
+    }
+
+    fn effects(&mut self, effects: &WithSpan<EffectExpr>) {
+        match &effects.value {
+            EffectExpr::Effects(effects) => self.list(
+                "{",
+                effects,
+                "}",
+                |p,
+                 WithSpan {
+                     value: Effect { input, output },
+                     ..
+                 }| {
+                    p.ty(input);
+                    p.push(" => ");
+                    p.ty(output);
+                },
+            ),
+            EffectExpr::Add(effects) => {
+                self.push("add");
+                self.list("(", effects, ")", Self::effects);
+            }
+            EffectExpr::Sub {
+                minuend,
+                subtrahend,
+            } => {
+                self.push("sub(");
+                self.effects(minuend);
+                self.push(", ");
+                self.effects(subtrahend);
+                self.push(")");
+            }
+            EffectExpr::Apply {
+                function,
+                arguments,
+            } => {
+                self.push("apply(");
+                self.ty(function);
+                for argument in arguments {
+                    self.push(", ");
+                    self.ty(argument);
+                }
+                self.push(")");
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        match literal {
+            Literal::String(string) => {
+                self.push("\"");
+                self.escaped(string, '"');
+                self.push("\"");
+            }
+            Literal::Integer(integer) => self.push(&integer.to_string()),
+            Literal::Rational(a, b) => self.push(&format!("{}/{}", a, b)),
+            Literal::Float(float) => self.push(&format!("{:?}", float)),
+        }
+    }
+
+    fn link_name(&mut self, link_name: &LinkName) {
+        let (name, uuid) = match link_name {
+            LinkName::None => return,
+            LinkName::Version(uuid) => ("version", uuid),
+            LinkName::Card(uuid) => ("card", uuid),
+        };
+        self.push(name);
+        self.push("!(");
+        self.uuid(uuid);
+        self.push(")::");
+    }
+
+    fn attr(&mut self, attr: &WithSpan<Expr>) {
+        self.push("#[");
+        self.expr(attr);
+        self.push("] ");
+    }
+
+    fn comment(&mut self, position: &CommentPosition, text: &str, item: impl FnOnce(&mut Self)) {
+        // Comments in AST are wrapped by parentheses as in Hacker syntax.
+        let text = text
+            .strip_prefix('(')
+            .and_then(|text| text.strip_suffix(')'))
+            .unwrap_or(text);
+        match position {
+            CommentPosition::Prefix => {
+                self.push(&format!("/*{}*/ ", text));
+                item(self);
+            }
+            CommentPosition::Suffix => {
+                item(self);
+                self.push(&format!(" /*{}*/", text));
+            }
+        }
+    }
+
+    fn ident(&mut self, ident: &str) {
+        let mut chars = ident.chars();
+        let simple = chars
+            .next()
+            .map(|c| c.is_ascii_alphabetic() || c == '_')
+            .unwrap_or(false)
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && ident != "_"
+            && !KEYWORDS.iter().any(|(keyword, _)| *keyword == ident);
+        if simple {
+            self.push(ident);
+        } else {
+            self.push("`");
+            self.escaped(ident, '`');
+            self.push("`");
+        }
+    }
+
+    fn uuid(&mut self, uuid: &Uuid) {
+        self.push(&format!("\"{}\"", uuid));
+    }
+
+    fn escaped(&mut self, string: &str, quote: char) {
+        for c in string.chars() {
+            match c {
+                '\\' => self.push(r"\\"),
+                '\n' => self.push(r"\n"),
+                '\r' => self.push(r"\r"),
+                '\t' => self.push(r"\t"),
+                c if c == quote => {
+                    self.out.push('\\');
+                    self.out.push(c);
+                }
+                c => self.out.push(c),
+            }
+        }
+    }
+
+    fn block<T>(&mut self, items: &[T], mut f: impl FnMut(&mut Self, &T)) {
+        self.push(" {");
+        self.indent += INDENT;
+        for item in items {
+            self.newline();
+            f(self, item);
+            self.push(",");
+        }
+        self.indent -= INDENT;
+        self.newline();
+        self.push("}");
+    }
+
+    fn list<T>(&mut self, begin: &str, items: &[T], end: &str, mut f: impl FnMut(&mut Self, &T)) {
+        self.push(begin);
+        for (index, item) in items.iter().enumerate() {
+            if index != 0 {
+                self.push(", ");
+            }
+            f(self, item);
+        }
+        self.push(end);
+    }
+
+    fn tuple<T>(&mut self, items: &[T], f: impl FnMut(&mut Self, &T)) {
+        // `(a)` is also parsed as a tuple, but `(a,)` is more familiar.
+        let end = if items.len() == 1 { ",)" } else { ")" };
+        self.list("(", items, end, f);
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        self.out.push_str(&" ".repeat(self.indent));
+    }
+
+    fn push(&mut self, str: &str) {
+        self.out.push_str(str);
+    }
+}
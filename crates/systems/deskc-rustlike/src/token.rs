@@ -0,0 +1,68 @@
+use std::ops::Range;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Token {
+    // Always wrapped by parentheses like the comments in Hacker syntax.
+    Comment(String),
+    Ident(String),
+    Int(i64),
+    // Keeps the text because f64 is not Eq.
+    Float(String),
+    Str(String),
+    Let,
+    Perform,
+    Continue,
+    Handle,
+    Match,
+    As,
+    Fn,
+    Trait,
+    Enum,
+    Brand,
+    Type,
+    NumberType,
+    StringType,
+    This,
+    Infer,
+    ParenBegin,
+    ParenEnd,
+    ArrayBegin,
+    ArrayEnd,
+    SetBegin,
+    SetEnd,
+    AngleBegin,
+    AngleEnd,
+    Comma,
+    Semicolon,
+    Colon,
+    PathSeparator,
+    Equal,
+    Arrow,
+    EArrow,
+    Bar,
+    Reference,
+    Label,
+    Attribute,
+    Bang,
+    Hole,
+    Divide,
+}
+
+pub type Tokens = Vec<(Token, Range<usize>)>;
+
+pub(crate) const KEYWORDS: [(&str, Token); 14] = [
+    ("let", Token::Let),
+    ("perform", Token::Perform),
+    ("continue", Token::Continue),
+    ("handle", Token::Handle),
+    ("match", Token::Match),
+    ("as", Token::As),
+    ("fn", Token::Fn),
+    ("trait", Token::Trait),
+    ("enum", Token::Enum),
+    ("brand", Token::Brand),
+    ("type", Token::Type),
+    ("number", Token::NumberType),
+    ("string", Token::StringType),
+    ("Self", Token::This),
+];
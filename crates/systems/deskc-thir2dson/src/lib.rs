@@ -36,11 +36,13 @@ pub fn thir_to_dson(thir: &TypedHir) -> Result<Dson, HirToJsonError> {
         ),
         Expr::Let { .. } => return Err(HirToJsonError::NotAllowed("let".into())),
         Expr::Perform { .. } => return Err(HirToJsonError::NotAllowed("perform".into())),
+        Expr::Continue { .. } => return Err(HirToJsonError::NotAllowed("continue".into())),
         Expr::Handle { .. } => return Err(HirToJsonError::NotAllowed("handle".into())),
         Expr::Apply { .. } => return Err(HirToJsonError::NotAllowed("apply".into())),
         Expr::Match { .. } => return Err(HirToJsonError::NotAllowed("match".into())),
         Expr::Function { .. } => return Err(HirToJsonError::NotAllowed("function".into())),
         Expr::Op { .. } => return Err(HirToJsonError::NotAllowed("op".into())),
+        Expr::TraitObject(_) => return Err(HirToJsonError::NotAllowed("trait object".into())),
         Expr::Label { label, item: expr } => Dson::Labeled {
             label: label.clone(),
             expr: Box::new(thir_to_dson(expr)?),
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use ast::{expr::Expr, span::WithSpan};
+use codebase::code::Code;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use deskc::card::{CardQueries, CardsCompiler};
+use ids::CardId;
+
+fn parse(source: &str) -> WithSpan<Expr> {
+    parser::parse(lexer::scan(source).unwrap()).unwrap()
+}
+
+/// A card with `size` items, each of which is typed apart from the others.
+fn card(size: usize) -> WithSpan<Expr> {
+    let items = (0..size)
+        .map(|i| format!(r#"$ {i}: x ~ * &x, "a", \ 'number -> &'number."#))
+        .collect::<Vec<_>>()
+        .join(", ");
+    parse(&format!("* {items}"))
+}
+
+/// Replaces the first item like editing a workspace node, so the other items keep their ids.
+fn edit(card: &WithSpan<Expr>, source: &str) -> WithSpan<Expr> {
+    let mut card = card.clone();
+    let Expr::Product(items) = &mut card.value else {
+        unreachable!()
+    };
+    items[0] = parse(source);
+    card
+}
+
+fn typeinfer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("typeinfer");
+    group.sample_size(20);
+    for size in [100, 400] {
+        let card = card(size);
+        let edits = [
+            edit(&card, r#"$ 1: x ~ * &x, "b"."#),
+            edit(&card, r#"$ "c": x ~ * &x, 2."#),
+        ];
+
+        group.bench_with_input(BenchmarkId::new("whole card", size), &edits, |b, edits| {
+            let mut i = 0;
+            b.iter(|| {
+                let (genhir, hir) = hirgen::gen_hir(&edits[i % 2]).unwrap();
+                i += 1;
+                typeinfer::synth(genhir.next_id(), &hir).unwrap()
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("edited node", size), &edits, |b, edits| {
+            let mut cards = CardsCompiler::default();
+            let id = CardId::new();
+            cards.set_code(id.clone(), Code::Ast(Arc::new(card.clone())));
+            cards.typeinfer(id.clone()).unwrap();
+            let mut i = 0;
+            b.iter(|| {
+                cards.set_code(id.clone(), Code::Ast(Arc::new(edits[i % 2].clone())));
+                i += 1;
+                cards.typeinfer(id.clone()).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, typeinfer);
+criterion_main!(benches);
@@ -1,58 +1,132 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use ast::span::WithSpan;
-use codebase::code::Code;
+use codebase::code::{Code, SyntaxKind};
 use hir::meta::WithMeta;
-use ids::CardId;
+use hirgen::import::{normalize_type, resolve_imports};
+use ids::{CardId, LinkName, NodeId};
 use mir::mir::Mir;
 use thir::TypedHir;
 use tokens::Tokens;
+use typeinfer::{
+    hole::TypedHoles,
+    subtree::{collect_subtrees, synth_subtree, SubtreeTypes},
+};
+use types::Types;
+use uuid::Uuid;
 
-use crate::query_result::QueryResult;
+use crate::{
+    query_result::{QueryError, QueryResult},
+    syntax,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HirResult {
-    hir: WithMeta<hir::expr::Expr>,
-    next_id: usize,
+    pub hir: WithMeta<hir::expr::Expr>,
+    pub next_id: usize,
+    pub type_aliases: HashMap<String, hir::ty::Type>,
+    pub exports: Vec<WithMeta<hir::ty::Type>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeinferResult {
+    pub types: Types,
+    pub holes: TypedHoles,
+    pub next_id: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 // AST might be inputed as a code, so it doesn't need to output tokens.
 pub enum TokensOrAst {
     Token(QueryResult<Tokens>),
-    Ast(Arc<WithSpan<ast::expr::Expr>>),
+    Ast(QueryResult<WithSpan<ast::expr::Expr>>),
 }
 
 #[salsa::query_group(CardStorage)]
 pub trait CardQueries {
     #[salsa::input]
     fn code(&self, card_id: CardId) -> Code;
+    /// All cards in the database, which are searched for exports.
+    ///
+    /// Empty by default.
+    #[salsa::input]
+    fn card_ids(&self) -> Arc<Vec<CardId>>;
     fn tokens_or_ast(&self, id: CardId) -> TokensOrAst;
     fn ast(&self, id: CardId) -> QueryResult<WithSpan<ast::expr::Expr>>;
+    fn exports(&self, id: CardId) -> QueryResult<Vec<hir::ty::Type>>;
+    /// Resolved imports of the card, which are linked by `LinkName::Card`.
+    fn imports(&self, id: CardId) -> QueryResult<HashMap<NodeId, LinkName>>;
     fn hir(&self, id: CardId) -> QueryResult<HirResult>;
+    /// Subexpressions of the card by their node ids.
+    fn hir_nodes(&self, id: CardId)
+        -> QueryResult<HashMap<NodeId, Arc<WithMeta<hir::expr::Expr>>>>;
+    /// A subexpression of the card, which stays equal while the node is not edited.
+    fn hir_node(&self, id: CardId, node_id: NodeId) -> Option<Arc<WithMeta<hir::expr::Expr>>>;
+    /// Types of a subexpression inferred apart from the card.
+    ///
+    /// `None` if the subexpression depends on the enclosing expression.
+    /// Only cards given as AST benefit from this, because source code is parsed into new node ids.
+    fn typeinfer_node(&self, id: CardId, node_id: NodeId) -> QueryResult<Option<SubtreeTypes>>;
+    /// Re-infers only the nodes whose subtrees are edited, and reuses `typeinfer_node` for the rest.
+    fn typeinfer(&self, id: CardId) -> QueryResult<TypeinferResult>;
     fn thir(&self, id: CardId) -> QueryResult<TypedHir>;
+    /// The content address of the card, which is referred by `LinkName::Version`.
+    fn card_hash(&self, id: CardId) -> QueryResult<Uuid>;
+    /// Cards with the same normalized THIR share the same key, so they are compiled only once.
+    #[salsa::interned]
+    fn intern_thir(&self, thir: Arc<TypedHir>) -> ThirKey;
+    fn mir_by_thir(&self, key: ThirKey) -> QueryResult<Mir>;
     fn mir(&self, id: CardId) -> QueryResult<Mir>;
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ThirKey(salsa::InternId);
+
+impl salsa::InternKey for ThirKey {
+    fn from_intern_id(id: salsa::InternId) -> Self {
+        ThirKey(id)
+    }
+
+    fn as_intern_id(&self) -> salsa::InternId {
+        self.0
+    }
+}
+
 #[salsa::database(CardStorage)]
-#[derive(Default)]
 pub struct CardsCompiler {
     storage: salsa::Storage<Self>,
 }
 
+impl Default for CardsCompiler {
+    fn default() -> Self {
+        let mut compiler = CardsCompiler {
+            storage: Default::default(),
+        };
+        compiler.set_card_ids(Default::default());
+        compiler
+    }
+}
+
 impl salsa::Database for CardsCompiler {}
 
 fn tokens_or_ast(db: &dyn CardQueries, id: CardId) -> TokensOrAst {
     let code = db.code(id);
     match code {
-        Code::SourceCode { syntax: _, source } => {
+        Code::SourceCode {
+            syntax: SyntaxKind::Hacker,
+            source,
+        } => {
             let result = || {
                 let tokens = lexer::scan(&source)?;
                 Ok(Arc::new(tokens))
             };
             TokensOrAst::Token(result())
         }
-        Code::Ast(ast) => TokensOrAst::Ast(ast),
+        // Other syntaxes don't share the tokens with Hacker syntax.
+        Code::SourceCode { syntax, source } => {
+            TokensOrAst::Ast(syntax::parse(&syntax, &source).map(Arc::new))
+        }
+        Code::Ast(ast) => TokensOrAst::Ast(Ok(ast)),
     }
 }
 
@@ -63,28 +137,190 @@ fn ast(db: &dyn CardQueries, id: CardId) -> QueryResult<WithSpan<ast::expr::Expr
             let ast = parser::parse(tokens.as_ref().clone())?;
             Ok(Arc::new(ast))
         }
-        TokensOrAst::Ast(ast) => Ok(ast),
+        TokensOrAst::Ast(ast) => ast,
     }
 }
 
-fn hir(db: &dyn CardQueries, id: CardId) -> QueryResult<HirResult> {
+fn exports(db: &dyn CardQueries, id: CardId) -> QueryResult<Vec<hir::ty::Type>> {
     let ast = db.ast(id)?;
-    let (genhir, hir) = hirgen::gen_hir(&ast).unwrap();
+    let (genhir, _) = hirgen::gen_hir(&ast)?;
+    let exports = genhir.exports.borrow().iter().map(normalize_type).collect();
+    Ok(Arc::new(exports))
+}
+
+fn imports(db: &dyn CardQueries, id: CardId) -> QueryResult<HashMap<NodeId, LinkName>> {
+    let ast = db.ast(id.clone())?;
+    let (genhir, _) = hirgen::gen_hir(&ast)?;
+    let imports = genhir.imports.take();
+    if imports.is_empty() {
+        return Ok(Default::default());
+    }
+    // A card never imports itself, and broken cards export nothing.
+    let exports = db
+        .card_ids()
+        .iter()
+        .filter(|card_id| **card_id != id)
+        .filter_map(|card_id| Some((card_id.clone(), db.exports(card_id.clone()).ok()?)))
+        .collect::<Vec<_>>();
+    let resolved = resolve_imports(
+        &imports,
+        exports
+            .iter()
+            .map(|(card_id, types)| (card_id, types.as_slice())),
+        |card_id| db.card_hash(card_id.clone()).ok().map(|hash| *hash),
+    )?;
+    Ok(Arc::new(resolved))
+}
+
+fn hir(db: &dyn CardQueries, id: CardId) -> QueryResult<HirResult> {
+    let ast = db.ast(id.clone())?;
+    let imports = db.imports(id)?;
+    let (genhir, hir) = hirgen::gen_hir_with_imports(&ast, imports.as_ref().clone())?;
     Ok(Arc::new(HirResult {
         hir,
         next_id: genhir.next_id(),
+        type_aliases: genhir.type_aliases.take(),
+        exports: genhir.exports.take(),
     }))
 }
 
-fn thir(db: &dyn CardQueries, id: CardId) -> QueryResult<TypedHir> {
+fn hir_nodes(
+    db: &dyn CardQueries,
+    id: CardId,
+) -> QueryResult<HashMap<NodeId, Arc<WithMeta<hir::expr::Expr>>>> {
+    fn insert(
+        nodes: &mut HashMap<NodeId, Arc<WithMeta<hir::expr::Expr>>>,
+        expr: &WithMeta<hir::expr::Expr>,
+    ) {
+        for child in hir::helper::children(expr) {
+            // Literals are never typed apart from the card.
+            if !matches!(child.value, hir::expr::Expr::Literal(_)) {
+                nodes.insert(child.id.clone(), Arc::new(child.clone()));
+                insert(nodes, child);
+            }
+        }
+    }
     let hir_result = db.hir(id)?;
-    let (ctx, _ty) = typeinfer::synth(hir_result.next_id, &hir_result.hir)?;
-    let thir = thirgen::gen_typed_hir(ctx.next_id(), ctx.get_types(), &hir_result.hir);
+    let mut nodes = HashMap::new();
+    insert(&mut nodes, &hir_result.hir);
+    Ok(Arc::new(nodes))
+}
+
+fn hir_node(
+    db: &dyn CardQueries,
+    id: CardId,
+    node_id: NodeId,
+) -> Option<Arc<WithMeta<hir::expr::Expr>>> {
+    db.hir_nodes(id).ok()?.get(&node_id).cloned()
+}
+
+fn typeinfer_node(
+    db: &dyn CardQueries,
+    id: CardId,
+    node_id: NodeId,
+) -> QueryResult<Option<SubtreeTypes>> {
+    let types = match db.hir_node(id, node_id) {
+        Some(expr) => synth_subtree(&expr)?,
+        None => None,
+    };
+    Ok(Arc::new(types))
+}
+
+fn typeinfer(db: &dyn CardQueries, id: CardId) -> QueryResult<TypeinferResult> {
+    let hir_result = db.hir(id.clone())?;
+    // A subtree which fails alone might be typed in the card, for example by a signature.
+    let subtrees = collect_subtrees(&hir_result.hir, &mut |expr| {
+        Ok::<_, QueryError>(
+            db.typeinfer_node(id.clone(), expr.id.clone())
+                .ok()
+                .and_then(|types| types.as_ref().clone()),
+        )
+    })?;
+    let (ctx, ty) = typeinfer::synth_with_subtrees(hir_result.next_id, &hir_result.hir, subtrees)
+        .map_err(|error| {
+        // Types the card again to report all errors at once.
+        let (_, _, errors) = typeinfer::synth_with_recovery(hir_result.next_id, &hir_result.hir);
+        if errors.0.is_empty() {
+            QueryError::from(error)
+        } else {
+            errors.into()
+        }
+    })?;
+    typeinfer::check_exports(&ctx, &ty, &hir_result.exports)?;
+    Ok(Arc::new(TypeinferResult {
+        types: ctx.get_types(),
+        holes: ctx.get_holes(),
+        next_id: ctx.next_id(),
+    }))
+}
+
+fn thir(db: &dyn CardQueries, id: CardId) -> QueryResult<TypedHir> {
+    let hir_result = db.hir(id.clone())?;
+    let typeinfer_result = db.typeinfer(id)?;
+    thirgen::exhaustiveness::check_matches(&typeinfer_result.types, &hir_result.hir)?;
+    let thir = thirgen::gen_typed_hir(
+        typeinfer_result.next_id,
+        typeinfer_result.types.clone(),
+        &hir_result.hir,
+    );
     Ok(Arc::new(thir))
 }
 
-fn mir(db: &dyn CardQueries, id: CardId) -> QueryResult<Mir> {
+fn card_hash(db: &dyn CardQueries, id: CardId) -> QueryResult<Uuid> {
     let thir = db.thir(id)?;
-    let mir = mirgen::gen_mir(&thir).unwrap();
-    Ok(Arc::new(mir))
+    Ok(Arc::new(thir::hash::card_hash(&thir)))
+}
+
+fn mir_by_thir(db: &dyn CardQueries, key: ThirKey) -> QueryResult<Mir> {
+    let thir = db.lookup_intern_thir(key);
+    Ok(Arc::new(mirgen::gen_mir(&thir)?))
+}
+
+fn mir(db: &dyn CardQueries, id: CardId) -> QueryResult<Mir> {
+    let thir = db.thir(id.clone())?;
+    let normalized = thir::hash::normalize(thir.as_ref().clone());
+    db.mir_by_thir(db.intern_thir(Arc::new(normalized)))
+        .or_else(|error| match mirgen::gen_mir(&thir) {
+            // The normalized THIR has no node ids, so the error is made again from this card.
+            Err(error) => {
+                let spans = hir::helper::spans(&db.hir(id)?.hir);
+                Err(error.with_spans(&spans).into())
+            }
+            Ok(_) => Err(error),
+        })
+}
+
+/// Links the entrypoint card with the cards, which are referred by `LinkName::Card` or `LinkName::Version`.
+pub fn link(
+    db: &dyn CardQueries,
+    entrypoint: CardId,
+    cards: impl IntoIterator<Item = CardId>,
+) -> Result<Mir, QueryError> {
+    let cards: Vec<_> = cards.into_iter().collect();
+    let spans = || {
+        std::iter::once(&entrypoint)
+            .chain(cards.iter())
+            .map(|id| Ok(hir::helper::link_spans(&db.hir(id.clone())?.hir)))
+            .collect::<Result<Vec<_>, QueryError>>()
+            .map(|spans| spans.into_iter().flatten().collect::<HashMap<_, _>>())
+    };
+    let mir = db.mir(entrypoint.clone())?.as_ref().clone();
+    let linkable = cards
+        .iter()
+        .map(|id| {
+            let mir = db.mir(id.clone())?.as_ref().clone();
+            let hash = *db.card_hash(id.clone())?;
+            Ok([
+                (LinkName::Card(id.0), mir.clone()),
+                (LinkName::Version(hash), mir),
+            ])
+        })
+        .collect::<Result<Vec<_>, QueryError>>()?
+        .into_iter()
+        .flatten()
+        .collect::<HashMap<_, _>>();
+    match linker::link(mir, &linkable) {
+        Ok(mir) => Ok(mir),
+        Err(errors) => Err(errors.with_spans(&spans()?).into()),
+    }
 }
@@ -1,16 +1,17 @@
-mod card;
-mod query_result;
+pub mod card;
+pub mod query_result;
+pub mod syntax;
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
 
     use ast::{
         expr::{Expr, Literal},
         span::WithSpan,
     };
     use codebase::code::{Code, SyntaxKind};
-    use ids::{CardId, NodeId};
+    use ids::{CardId, LinkName, NodeId};
     use mir::{
         block::BasicBlock,
         mir::{ControlFlowGraph, ControlFlowGraphId, Mir},
@@ -18,9 +19,10 @@ mod tests {
         stmt::{Const, Stmt, StmtBind, Terminator},
         var::{Var, VarId, Vars},
     };
+    use textual_diagnostics::TextualDiagnostics;
     use types::Type;
 
-    use crate::card::{CardQueries, CardsCompiler};
+    use crate::card::{link, CardQueries, CardStorage, CardsCompiler};
 
     #[test]
     fn compiles_source_code() {
@@ -54,11 +56,34 @@ mod tests {
                         terminator: Terminator::Return(VarId(0))
                     }],
                     links: vec![]
-                }]
+                }],
+                links: Default::default(),
             })
         );
     }
 
+    #[test]
+    fn compiles_rust_like_source_code() {
+        let mut cards = CardsCompiler::default();
+        let hacker = CardId::new();
+        let rust_like = CardId::new();
+        cards.set_code(
+            hacker.clone(),
+            Code::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: Arc::new("> \\ 'number, 'number -> @sum 'number ~ 1, 2".into()),
+            },
+        );
+        cards.set_code(
+            rust_like.clone(),
+            Code::SourceCode {
+                syntax: SyntaxKind::RustLike,
+                source: Arc::new("<fn(number, number) -> @sum number>(1, 2)".into()),
+            },
+        );
+        assert_eq!(cards.mir(rust_like).unwrap(), cards.mir(hacker).unwrap());
+    }
+
     #[test]
     fn compiles_ast() {
         let mut cards = CardsCompiler::default();
@@ -92,8 +117,302 @@ mod tests {
                         terminator: Terminator::Return(VarId(0))
                     }],
                     links: vec![]
-                }]
+                }],
+                links: Default::default(),
             })
         );
     }
+
+    #[test]
+    fn links_cards() {
+        let mut cards = CardsCompiler::default();
+        let entrypoint = CardId::new();
+        let card = CardId::new();
+        cards.set_code(
+            entrypoint.clone(),
+            Code::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: Arc::new(format!("&'number 'card 'uuid {}", card.0)),
+            },
+        );
+        cards.set_code(
+            card.clone(),
+            Code::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: Arc::new("1".into()),
+            },
+        );
+        let mir = link(&cards, entrypoint.clone(), [card]).unwrap();
+        assert_eq!(mir.cfgs.len(), 2);
+        assert_eq!(
+            mir.links.values().collect::<Vec<_>>(),
+            vec![&ControlFlowGraphId(1)]
+        );
+
+        let error = link(&cards, entrypoint, []).unwrap_err();
+        assert_eq!(TextualDiagnostics::from(&error).title, "Link error");
+    }
+
+    #[test]
+    fn reports_mirgen_error_at_span() {
+        let mut cards = CardsCompiler::default();
+        let card_id = CardId::new();
+        cards.set_code(
+            card_id.clone(),
+            Code::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: Arc::new(
+                    r#"
+$ \ 'number -> ^"a": 'string ~
+$ \ 'string -> ^1: 'number ~
+> \ % \ 'this -> 'string., % \ 'this -> 'number. -> 'string ~ 1, "s""#
+                        .into(),
+                ),
+            },
+        );
+        let diagnostics = TextualDiagnostics::from(&cards.mir(card_id).unwrap_err());
+        assert_eq!(diagnostics.title, "Mirgen error");
+        assert_eq!(diagnostics.reports[0].span, 123..124);
+    }
+
+    #[test]
+    fn identical_cards_have_the_same_hash() {
+        let mut cards = CardsCompiler::default();
+        let a = CardId::new();
+        let b = CardId::new();
+        let c = CardId::new();
+        for (id, source) in [(&a, "* 1, 2"), (&b, "*   1,2"), (&c, "* 2, 1")] {
+            cards.set_code(
+                id.clone(),
+                Code::SourceCode {
+                    syntax: SyntaxKind::Hacker,
+                    source: Arc::new(source.into()),
+                },
+            );
+        }
+        assert_eq!(
+            cards.card_hash(a.clone()).unwrap(),
+            cards.card_hash(b.clone()).unwrap()
+        );
+        assert_ne!(
+            cards.card_hash(a.clone()).unwrap(),
+            cards.card_hash(c).unwrap()
+        );
+        assert!(Arc::ptr_eq(&cards.mir(a).unwrap(), &cards.mir(b).unwrap()));
+    }
+
+    #[test]
+    fn links_cards_by_hash() {
+        let mut cards = CardsCompiler::default();
+        let entrypoint = CardId::new();
+        let card = CardId::new();
+        cards.set_code(
+            card.clone(),
+            Code::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: Arc::new("1".into()),
+            },
+        );
+        let hash = cards.card_hash(card.clone()).unwrap();
+        cards.set_code(
+            entrypoint.clone(),
+            Code::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: Arc::new(format!("&'number 'uuid {}", hash)),
+            },
+        );
+        let mir = link(&cards, entrypoint.clone(), [card]).unwrap();
+        assert_eq!(mir.cfgs.len(), 2);
+
+        // Re-created card with the same code is still linked.
+        let recreated = CardId::new();
+        cards.set_code(
+            recreated.clone(),
+            Code::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: Arc::new("1".into()),
+            },
+        );
+        let mir = link(&cards, entrypoint, [recreated]).unwrap();
+        assert_eq!(mir.cfgs.len(), 2);
+    }
+
+    #[test]
+    fn imports_exported_cards() {
+        let mut cards = CardsCompiler::default();
+        let exporter = CardId::new();
+        let importer = CardId::new();
+        let set = |cards: &mut CardsCompiler, id: &CardId, source: &str| {
+            cards.set_code(
+                id.clone(),
+                Code::SourceCode {
+                    syntax: SyntaxKind::Hacker,
+                    source: Arc::new(source.into()),
+                },
+            )
+        };
+        set(&mut cards, &exporter, "$ 'export 'number ~ 1");
+        set(&mut cards, &importer, "'import 'number");
+        cards.set_card_ids(Arc::new(vec![exporter.clone(), importer.clone()]));
+        let mir = link(&cards, importer.clone(), [exporter.clone()]).unwrap();
+        assert_eq!(mir.cfgs.len(), 2);
+
+        set(
+            &mut cards,
+            &importer,
+            &format!("'import 'string 'card 'uuid {}", exporter.0),
+        );
+        let error = cards.hir(importer.clone()).unwrap_err();
+        assert_eq!(TextualDiagnostics::from(&error).title, "Import error");
+
+        let another = CardId::new();
+        set(&mut cards, &another, "$ 'export 'number ~ 2");
+        set(&mut cards, &importer, "'import 'number");
+        cards.set_card_ids(Arc::new(vec![
+            exporter.clone(),
+            importer.clone(),
+            another.clone(),
+        ]));
+        let error = cards.hir(importer.clone()).unwrap_err();
+        assert_eq!(TextualDiagnostics::from(&error).reports.len(), 1);
+        assert!(TextualDiagnostics::from(&error).reports[0]
+            .text
+            .contains("exported by 2 cards"));
+        assert_eq!(TextualDiagnostics::from(&error).reports[0].code, "E0313");
+        assert_eq!(
+            TextualDiagnostics::from(&error).reports[0]
+                .suggestions
+                .len(),
+            2
+        );
+
+        set(
+            &mut cards,
+            &importer,
+            &format!("'import 'number 'card 'uuid {}", another.0),
+        );
+        let mir = link(&cards, importer, [another]).unwrap();
+        assert_eq!(mir.cfgs.len(), 2);
+    }
+
+    #[test]
+    fn checks_exported_type() {
+        let mut cards = CardsCompiler::default();
+        let card_id = CardId::new();
+        cards.set_code(
+            card_id.clone(),
+            Code::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: Arc::new("$ 'export 'string ~ 1".into()),
+            },
+        );
+        let error = cards.typeinfer(card_id).unwrap_err();
+        assert_eq!(TextualDiagnostics::from(&error).title, "Typeinfer error");
+    }
+
+    #[test]
+    fn imports_each_export_of_a_card() {
+        let mut cards = CardsCompiler::default();
+        let exporter = CardId::new();
+        let importer = CardId::new();
+        let set = |cards: &mut CardsCompiler, id: &CardId, source: &str| {
+            cards.set_code(
+                id.clone(),
+                Code::SourceCode {
+                    syntax: SyntaxKind::Hacker,
+                    source: Arc::new(source.into()),
+                },
+            )
+        };
+        set(&mut cards, &importer, "'import 'string");
+        // No cards are searched before `set_card_ids`.
+        let error = cards.hir(importer.clone()).unwrap_err();
+        assert_eq!(TextualDiagnostics::from(&error).title, "Import error");
+
+        set(
+            &mut cards,
+            &exporter,
+            r#"$ 'export 'number ~ $ 'export 'string ~ * 1, "a""#,
+        );
+        cards.set_card_ids(Arc::new(vec![exporter.clone(), importer.clone()]));
+        cards.typeinfer(exporter.clone()).unwrap();
+        // The output of the exporter is cast by an adapter.
+        let mir = link(&cards, importer.clone(), [exporter.clone()]).unwrap();
+        assert_eq!(mir.cfgs.len(), 3);
+
+        let hash = cards.card_hash(exporter.clone()).unwrap();
+        set(
+            &mut cards,
+            &importer,
+            &format!("'import 'number 'uuid {}", hash),
+        );
+        assert_eq!(
+            cards.imports(importer.clone()).unwrap().values().next(),
+            Some(&LinkName::Version(*hash))
+        );
+        link(&cards, importer.clone(), [exporter]).unwrap();
+
+        set(
+            &mut cards,
+            &importer,
+            &format!("'import 'string 'uuid {}", importer.0),
+        );
+        let error = cards.hir(importer).unwrap_err();
+        assert!(TextualDiagnostics::from(&error).reports[0]
+            .text
+            .contains("does not export 'string"));
+    }
+
+    #[salsa::database(CardStorage)]
+    #[derive(Default)]
+    struct LoggingCompiler {
+        storage: salsa::Storage<Self>,
+        executed: Mutex<Vec<String>>,
+    }
+
+    impl salsa::Database for LoggingCompiler {
+        fn salsa_event(&self, event: salsa::Event) {
+            if let salsa::EventKind::WillExecute { database_key } = event.kind {
+                self.executed
+                    .lock()
+                    .unwrap()
+                    .push(format!("{:?}", database_key.debug(self)));
+            }
+        }
+    }
+
+    #[test]
+    fn re_infers_only_edited_nodes() {
+        let parse = |source: &str| parser::parse(lexer::scan(source).unwrap()).unwrap();
+        let mut card = parse(r#"* $ 1: x ~ * &x, "a"., $ "b": y ~ * &y, 2."#);
+        let mut cards = LoggingCompiler::default();
+        let card_id = CardId::new();
+        cards.set_code(card_id.clone(), Code::Ast(Arc::new(card.clone())));
+        let before = cards.typeinfer(card_id.clone()).unwrap();
+        let typeinfer_nodes = |cards: &LoggingCompiler| {
+            cards
+                .executed
+                .lock()
+                .unwrap()
+                .drain(..)
+                .filter(|key| key.starts_with("typeinfer_node"))
+                .count()
+        };
+        assert_eq!(typeinfer_nodes(&cards), 2);
+
+        // Edits the first item like a workspace node, so the second item keeps its ids.
+        let Expr::Product(items) = &mut card.value else {
+            panic!()
+        };
+        items[0] = parse(r#"$ 3: x ~ * &x, "c"."#);
+        cards.set_code(card_id.clone(), Code::Ast(Arc::new(card.clone())));
+        let after = cards.typeinfer(card_id.clone()).unwrap();
+        assert_eq!(typeinfer_nodes(&cards), 1);
+        assert_ne!(before, after);
+
+        // Types are the same as the ones inferred on the whole card.
+        let hir = cards.hir(card_id).unwrap();
+        let (ctx, _) = typeinfer::synth(hir.next_id, &hir.hir).unwrap();
+        assert_eq!(after.types, ctx.get_types());
+    }
 }
@@ -1,24 +1,89 @@
-use std::sync::Arc;
+use std::{any::Any, sync::Arc};
+
+use hirgen::{error::HirGenError, import::ImportErrors};
+use lexer::error::LexerError;
+use linker::LinkErrors;
+use mirgen::GenMirError;
+use parser::ParserError;
+use rustlike::error::RustLikeError;
+use textual_diagnostics::{Report, TextualDiagnostics};
+use thirgen::exhaustiveness::MatchErrors;
+use typeinfer::error::{ExprTypeError, ExprTypeErrors};
 
 pub type QueryResult<T> = Result<Arc<T>, QueryError>;
 
 #[derive(Debug, Clone)]
-pub struct QueryError(pub Arc<Box<dyn std::error::Error + Send + Sync + 'static>>);
+pub struct QueryError(pub Arc<dyn DynError>);
+
+/// An object safe version of `Error + PartialEq`, so salsa can backdate a query which fails with the same error.
+pub trait DynError: std::error::Error + Send + Sync + 'static {
+    fn as_any(&self) -> &dyn Any;
+    fn dyn_eq(&self, other: &dyn DynError) -> bool;
+}
+
+impl<T> DynError for T
+where
+    T: std::error::Error + PartialEq + Send + Sync + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn DynError) -> bool {
+        other.as_any().downcast_ref::<T>() == Some(self)
+    }
+}
+
+impl dyn DynError {
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+}
 
 impl PartialEq for QueryError {
-    fn eq(&self, _other: &Self) -> bool {
-        // FIXME: this is not a good solution: we need Eq object safe
-        // always returns false to occur recomputation always on error
-        false
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0.dyn_eq(other.0.as_ref())
     }
 }
 impl Eq for QueryError {}
 
 impl<T> From<T> for QueryError
 where
-    T: std::error::Error + Send + Sync + 'static,
+    T: DynError,
 {
     fn from(error: T) -> Self {
-        QueryError(Arc::new(Box::new(error)))
+        QueryError(Arc::new(error))
+    }
+}
+
+impl From<&QueryError> for TextualDiagnostics {
+    fn from(error: &QueryError) -> Self {
+        let error = error.0.as_ref();
+        if let Some(error) = error.downcast_ref::<LexerError>() {
+            error.clone().into()
+        } else if let Some(error) = error.downcast_ref::<ParserError>() {
+            error.clone().into()
+        } else if let Some(error) = error.downcast_ref::<RustLikeError>() {
+            error.clone().into()
+        } else if let Some(error) = error.downcast_ref::<HirGenError>() {
+            error.clone().into()
+        } else if let Some(error) = error.downcast_ref::<ImportErrors>() {
+            error.clone().into()
+        } else if let Some(error) = error.downcast_ref::<ExprTypeError>() {
+            error.clone().into()
+        } else if let Some(error) = error.downcast_ref::<ExprTypeErrors>() {
+            error.clone().into()
+        } else if let Some(error) = error.downcast_ref::<MatchErrors>() {
+            error.clone().into()
+        } else if let Some(error) = error.downcast_ref::<GenMirError>() {
+            error.clone().into()
+        } else if let Some(error) = error.downcast_ref::<LinkErrors>() {
+            error.clone().into()
+        } else {
+            TextualDiagnostics {
+                title: "Compile error".into(),
+                reports: vec![Report::error("E0001", 0..0, error.to_string())],
+            }
+        }
     }
 }
@@ -0,0 +1,69 @@
+use ast::{expr::Expr, span::WithSpan};
+use codebase::code::{SyntaxKind, UnsupportedSyntax};
+use lexer::error::LexerError;
+use parser::ParserError;
+use rustlike::error::RustLikeError;
+
+use crate::query_result::QueryError;
+
+/// Parses a source code in the syntax.
+///
+/// This is generic over the error, so the workspace can share it with its own query error.
+pub fn parse<E>(syntax: &SyntaxKind, source: &str) -> Result<WithSpan<Expr>, E>
+where
+    E: From<LexerError> + From<ParserError> + From<RustLikeError> + From<UnsupportedSyntax>,
+{
+    match syntax {
+        SyntaxKind::Hacker => Ok(parser::parse(lexer::scan(source)?)?),
+        SyntaxKind::RustLike => Ok(rustlike::parse(source)?),
+        syntax => Err(UnsupportedSyntax(syntax.clone()).into()),
+    }
+}
+
+pub fn format(syntax: &SyntaxKind, expr: &WithSpan<Expr>) -> Result<String, QueryError> {
+    match syntax {
+        SyntaxKind::Hacker => Ok(deskc_fmt::format(expr)),
+        SyntaxKind::RustLike => Ok(rustlike::format(expr)),
+        syntax => Err(UnsupportedSyntax(syntax.clone()).into()),
+    }
+}
+
+/// Converts a source code for `ContentPatch::ChangeSourceCodeSyntax`.
+pub fn convert(source: &str, from: &SyntaxKind, to: &SyntaxKind) -> Result<String, QueryError> {
+    format(to, &parse::<QueryError>(from, source)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::remove_span::remove_span;
+
+    use super::*;
+
+    #[test]
+    fn converts_between_syntaxes() {
+        let hacker = "$ 1: 'number ~ > \\ 'number -> 'number ~ &'number";
+        let rust_like = convert(hacker, &SyntaxKind::Hacker, &SyntaxKind::RustLike).unwrap();
+        assert_eq!(rust_like, "let 1: number;\n<fn(number) -> number>(&number)");
+
+        let mut expected = parse::<QueryError>(&SyntaxKind::Hacker, hacker).unwrap();
+        let mut actual = parse::<QueryError>(
+            &SyntaxKind::Hacker,
+            &convert(&rust_like, &SyntaxKind::RustLike, &SyntaxKind::Hacker).unwrap(),
+        )
+        .unwrap();
+        remove_span(&mut expected);
+        remove_span(&mut actual);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn rejects_unsupported_syntax() {
+        let error = parse::<QueryError>(&SyntaxKind::OCamlLike, "1")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.0.downcast_ref::<UnsupportedSyntax>(),
+            Some(&UnsupportedSyntax(SyntaxKind::OCamlLike))
+        );
+    }
+}
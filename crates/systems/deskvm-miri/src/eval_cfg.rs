@@ -2,11 +2,12 @@ use std::collections::HashMap;
 
 use mir::block::BlockId;
 use mir::mir::ControlFlowGraph;
-use mir::stmt::{Stmt, Terminator};
+use mir::stmt::{LinkId, Stmt, Terminator};
 use serde::{Deserialize, Serialize};
-use types::{Effect, Type};
+use types::{Effect, EffectExpr, Type};
 
 use crate::const_stmt;
+use crate::op_stmt::OpEffect;
 
 use crate::value::{Closure, FnRef, Value};
 use mir::stmt::StmtBind;
@@ -22,13 +23,11 @@ pub struct EvalCfg {
     pub pc_stmt_idx: usize,
     // Before handling apply stmt, save the var to here, and used when returned.
     pub return_register: Option<VarId>,
-    pub handlers: HashMap<Effect, Handler>,
-}
-
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum Handler {
-    Handler(Closure),
-    Continuation(Vec<EvalCfg>),
+    pub handlers: HashMap<Effect, Closure>,
+    /// Frames from the handled expression to the perform, which are resumed by `Continue`.
+    ///
+    /// Only a frame of a handler has this, and the frames are cloned on each resumption.
+    pub continuation: Option<Vec<EvalCfg>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -38,6 +37,9 @@ pub enum InnerOutput {
         input: Value,
         effect: Effect,
     },
+    Continue {
+        input: Value,
+    },
     RunOther {
         fn_ref: FnRef,
         parameters: HashMap<Type, Value>,
@@ -86,11 +88,18 @@ impl EvalCfg {
                         .map(|var| (self.get_var_ty(var).clone(), self.load_value(var).clone()))
                         .collect(),
                 ),
-                Stmt::Vector(_) => todo!(),
-                Stmt::Set(_) => todo!(),
+                Stmt::Vector(values) => Value::Vector(
+                    values
+                        .iter()
+                        .map(|var| self.load_value(var).clone())
+                        .collect(),
+                ),
+                Stmt::Set(values) => {
+                    Value::set(values.iter().map(|var| self.load_value(var).clone()))
+                }
                 Stmt::Fn(fn_ref) => {
                     let fn_ref = match fn_ref {
-                        mir::stmt::FnRef::Link(_) => todo!(),
+                        mir::stmt::FnRef::Link(link) => FnRef::Link(link.clone()),
                         mir::stmt::FnRef::Closure {
                             mir,
                             captured,
@@ -100,7 +109,12 @@ impl EvalCfg {
                             captured: captured
                                 .iter()
                                 .map(|var| {
-                                    (self.get_var_ty(var).clone(), self.load_value(var).clone())
+                                    // Named variables are referred without effects.
+                                    let ty = match self.get_var_ty(var) {
+                                        Type::Effectful { ty, effects: _ } => ty,
+                                        ty => ty,
+                                    };
+                                    (ty.without_quantifiers(), self.load_value(var).clone())
                                 })
                                 .collect(),
                             handlers: handlers
@@ -111,13 +125,14 @@ impl EvalCfg {
                                         if let Value::FnRef(FnRef::Closure(closure)) =
                                             self.load_value(handler).clone()
                                         {
-                                            Handler::Handler(closure)
+                                            closure
                                         } else {
                                             panic!("handler must be FnRef::Closure")
                                         },
                                     )
                                 })
                                 .collect(),
+                            continuation: self.continuation.clone(),
                         }),
                     };
                     Value::FnRef(fn_ref)
@@ -144,6 +159,14 @@ impl EvalCfg {
                         panic!("type should be effectful")
                     }
                 }
+                Stmt::Continue(var) => {
+                    // Save the return register to get result of the handled expression.
+                    self.return_register = Some(*bind_var);
+                    self.pc_stmt_idx += 1;
+                    return InnerOutput::Continue {
+                        input: self.load_value(var).clone(),
+                    };
+                }
                 Stmt::Apply {
                     function,
                     arguments,
@@ -151,7 +174,7 @@ impl EvalCfg {
                     if let Value::FnRef(fn_ref) = self.registers.get(function).cloned().unwrap() {
                         let mut parameters = HashMap::new();
                         arguments.iter().for_each(|arg| {
-                            let ty = self.get_var_ty(arg).clone();
+                            let ty = self.get_var_ty(arg).without_quantifiers();
                             let value = self.load_value(arg).clone();
                             parameters.insert(ty, value);
                         });
@@ -164,10 +187,71 @@ impl EvalCfg {
                         panic!("fn_ref");
                     }
                 }
-                Stmt::Op { op, operands } => self.eval_op(op, operands),
+                Stmt::Dispatch {
+                    object,
+                    method,
+                    arguments,
+                } => {
+                    if let Value::TraitObject { ty, value, methods } = self.load_value(object) {
+                        let fn_ref = methods
+                            .get(method)
+                            .cloned()
+                            .unwrap_or_else(|| panic!("method must be implemented {:?}", method));
+                        // The implementation takes the inner value instead of the trait object.
+                        let mut parameters: HashMap<_, _> = arguments
+                            .iter()
+                            .filter(|arg| *arg != object)
+                            .map(|arg| {
+                                (
+                                    self.get_var_ty(arg).without_quantifiers(),
+                                    self.load_value(arg).clone(),
+                                )
+                            })
+                            .collect();
+                        parameters.insert(ty.clone(), *value.clone());
+                        // Save the return register.
+                        self.return_register = Some(*bind_var);
+                        // Increment pc before return output is important
+                        self.pc_stmt_idx += 1;
+                        return InnerOutput::RunOther { fn_ref, parameters };
+                    } else {
+                        panic!("trait object");
+                    }
+                }
+                Stmt::TraitObject { value, methods } => Value::TraitObject {
+                    ty: self.get_var_ty(value).clone(),
+                    value: Box::new(self.load_value(value).clone()),
+                    methods: methods
+                        .iter()
+                        .map(|(method, var)| {
+                            if let Value::FnRef(fn_ref) = self.load_value(var) {
+                                (method.clone(), fn_ref.clone())
+                            } else {
+                                panic!("method must be FnRef")
+                            }
+                        })
+                        .collect(),
+                },
+                Stmt::Op { op, operands } => match self.eval_op(op, operands) {
+                    Ok(value) => value,
+                    // The overflow effect is performed only if the signature declares it.
+                    Err(OpEffect::Overflow(approx))
+                        if !declares_overflow(self.get_var_ty(bind_var)) =>
+                    {
+                        Value::Float(approx)
+                    }
+                    Err(op_effect) => {
+                        // Continues with the output of the effect like a perform.
+                        let (input, effect) = op_effect.into_perform();
+                        self.return_register = Some(*bind_var);
+                        self.pc_stmt_idx += 1;
+                        return InnerOutput::Perform { input, effect };
+                    }
+                },
                 Stmt::Parameter => {
                     // unwrap is safe because typeinfer ensures that a parameter must be exist.
-                    let ty = self.get_var_ty(bind_var);
+                    // Polymorphic types are passed without the quantifiers.
+                    let ty = &self.get_var_ty(bind_var).without_quantifiers();
                     self.parameters
                         .get(ty)
                         .or_else(|| self.captured.get(ty))
@@ -177,10 +261,21 @@ impl EvalCfg {
                         .clone()
                 }
                 Stmt::Recursion => Value::FnRef(FnRef::Recursion),
-                Stmt::Link(_link) => {
-                    todo!()
+                Stmt::Link(name) => {
+                    let link = LinkId {
+                        ty: self.get_var_ty(bind_var).clone(),
+                        name: name.clone(),
+                    };
+                    // Save the return register.
+                    self.return_register = Some(*bind_var);
+                    // Increment pc before return output is important
+                    self.pc_stmt_idx += 1;
+                    return InnerOutput::RunOther {
+                        fn_ref: FnRef::Link(link),
+                        parameters: HashMap::new(),
+                    };
                 }
-                Stmt::MatchResult(_) => todo!(),
+                Stmt::MatchResult(var) => self.load_value(var).clone(),
                 Stmt::Cast(var) => self.cast(var, self.get_var_ty(bind_var)),
             };
             let var = *bind_var;
@@ -191,8 +286,12 @@ impl EvalCfg {
     }
 
     // After perform, continue with this function.
-    pub fn eval_continue(&mut self, _output: Value) {
-        todo!()
+    pub fn eval_continue(&mut self, output: Value) {
+        let var = self
+            .return_register
+            .take()
+            .expect("perform saves the return register");
+        self.store_value(var, output);
     }
 
     // After call another mir, continue with this function.
@@ -219,23 +318,73 @@ impl EvalCfg {
         let ty = self.get_var_ty(var);
         match (value, ty, target) {
             (value, a, b) if a == b => value.clone(),
+            // Labels and brands don't change the value.
+            (value, a, b) if without_label(a) == without_label(b) => value.clone(),
+            // A type variable is instantiated with any value.
+            (value, _, Type::Variable(_)) => value.clone(),
             (value, ty, Type::Sum(_)) if !matches!(value, Value::Variant { .. }) => {
                 Value::Variant {
                     ty: ty.clone(),
                     value: Box::new(value.clone()),
                 }
             }
-            (_value, Type::Product(_), Type::Product(_types)) => {
-                todo!()
+            // A variant is regrouped into the case of the target sum which contains it.
+            (Value::Variant { ty, value }, Type::Sum(_), Type::Sum(cases)) => {
+                let case = cases
+                    .iter()
+                    .find(|case| case == &ty)
+                    .or_else(|| {
+                        cases.iter().find(
+                            |case| matches!(case, Type::Sum(variants) if variants.contains(ty)),
+                        )
+                    })
+                    .unwrap_or_else(|| panic!("unable to cast {:?} to {:?}", ty, target));
+                if case == ty {
+                    Value::Variant {
+                        ty: ty.clone(),
+                        value: value.clone(),
+                    }
+                } else {
+                    Value::Variant {
+                        ty: case.clone(),
+                        value: Box::new(Value::Variant {
+                            ty: ty.clone(),
+                            value: value.clone(),
+                        }),
+                    }
+                }
             }
-            (_value, Type::Product(_types), _ty) => {
+            (_value, Type::Product(_), Type::Product(_types)) => {
                 todo!()
             }
+            // A product is cast to one of its components.
+            (Value::Product(values), Type::Product(_), target) => values
+                .iter()
+                .find(|(ty, _)| without_label(ty) == without_label(target))
+                .map(|(_, value)| value.clone())
+                .unwrap_or_else(|| panic!("unable to cast {:?} to {:?}", ty, target)),
             (_value, a, b) => panic!("unable to cast {:?} to {:?}", a, b),
         }
     }
 }
 
+fn without_label(mut ty: &Type) -> &Type {
+    while let Type::Label { label: _, item } | Type::Brand { brand: _, item } = ty {
+        ty = item;
+    }
+    ty
+}
+
+fn declares_overflow(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Effectful {
+            effects: EffectExpr::Effects(effects),
+            ..
+        } if effects.contains(&Effect::overflow())
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use mir::{
@@ -277,6 +426,7 @@ mod tests {
             captured: HashMap::new(),
             return_register: None,
             handlers: HashMap::new(),
+            continuation: None,
         };
 
         assert_eq!(eval.eval_next(), InnerOutput::Running);
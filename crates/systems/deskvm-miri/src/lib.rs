@@ -12,17 +12,21 @@ use std::{
 
 use dprocess::{
     interpreter::Interpreter,
+    interpreter_builder::InterpreterBuilder,
     interpreter_output::InterpreterOutput,
     value::{Number, Value},
 };
 use mir::{
+    artifact::{Artifact, ArtifactError},
     block::BlockId,
     mir::{ControlFlowGraph, ControlFlowGraphId, Mir},
+    stmt::LinkId,
 };
-use types::Effect;
+
+use types::{Effect, Type};
 
 use crate::{
-    eval_cfg::{EvalCfg, Handler, InnerOutput},
+    eval_cfg::{EvalCfg, InnerOutput},
     value::Closure,
 };
 
@@ -30,6 +34,7 @@ pub fn eval_mir(mirs: Mir) -> EvalMir {
     let cfg = mirs.cfgs.get(mirs.entrypoint.0).cloned().unwrap();
     EvalMir {
         cfgs: mirs.cfgs,
+        links: mirs.links,
         stack: vec![EvalCfg {
             cfg,
             registers: HashMap::new(),
@@ -39,13 +44,41 @@ pub fn eval_mir(mirs: Mir) -> EvalMir {
             pc_stmt_idx: 0,
             return_register: None,
             handlers: HashMap::new(),
+            continuation: None,
         }],
     }
 }
 
+/// Builds miri from a compiled artifact.
+#[derive(Clone, Debug)]
+pub struct MiriBuilder {
+    mir: Mir,
+}
+
+impl MiriBuilder {
+    pub fn new(mir: Mir) -> Self {
+        Self { mir }
+    }
+
+    pub fn from_artifact(artifact: Artifact) -> Self {
+        Self::new(artifact.mir)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ArtifactError> {
+        Ok(Self::from_artifact(Artifact::from_bytes(bytes)?))
+    }
+}
+
+impl InterpreterBuilder for MiriBuilder {
+    fn build(&self) -> Box<dyn Interpreter> {
+        Box::new(eval_mir(self.mir.clone()))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct EvalMir {
     cfgs: Vec<ControlFlowGraph>,
+    links: HashMap<LinkId, ControlFlowGraphId>,
     stack: Vec<EvalCfg>,
 }
 
@@ -59,6 +92,27 @@ impl EvalMir {
     }
 }
 
+impl EvalMir {
+    fn resume(&mut self, continuation: Vec<EvalCfg>, input: value::Value) -> InterpreterOutput {
+        self.stack.extend(continuation);
+        self.stack().eval_continue(input);
+        InterpreterOutput::Running
+    }
+}
+
+/// In a handler, `! x => ty` resumes the continuation as `<! x` does, if the effect is from the
+/// output of the handled effect to the output of the handled expression.
+fn resumed_continuation(eval_cfg: &EvalCfg, effect: &Effect) -> Option<Vec<EvalCfg>> {
+    let continuation = eval_cfg.continuation.as_ref()?;
+    let performed = continuation.last()?;
+    let handled_output = match performed.get_var_ty(&performed.return_register?) {
+        Type::Effectful { ty, .. } => ty.as_ref(),
+        ty => ty,
+    };
+    (*handled_output == effect.input && continuation[0].cfg.output == effect.output)
+        .then(|| continuation.clone())
+}
+
 impl Interpreter for EvalMir {
     fn reduce(&mut self, _target_duration: &Duration) -> Result<InterpreterOutput> {
         let output = match self.stack().eval_next() {
@@ -78,11 +132,16 @@ impl Interpreter for EvalMir {
                     if let Some(eval_mir) = self.stack.pop() {
                         // find handler
                         let handler = eval_mir.handlers.get(&effect).cloned();
+                        let resumed = resumed_continuation(&eval_mir, &effect);
                         // push eval_mir to continuation
                         continuation_from_handler.push_front(eval_mir);
                         if let Some(handler) = handler {
                             break handler;
                         }
+                        if let Some(continuation) = resumed {
+                            self.stack.extend(continuation_from_handler);
+                            return Ok(self.resume(continuation, input));
+                        }
                     } else {
                         // When handler are not found, push back to continuation stack and perform
                         self.stack.extend(continuation_from_handler);
@@ -92,50 +151,64 @@ impl Interpreter for EvalMir {
                         });
                     }
                 };
-                match handler {
-                    eval_cfg::Handler::Handler(Closure {
-                        mir,
-                        mut captured,
-                        // Really ignorable??
-                        handlers: _,
-                    }) => {
-                        captured.insert(effect.input.clone(), input);
-                        let eval_mir = EvalCfg {
-                            cfg: self.get_mir(&mir).clone(),
-                            registers: Default::default(),
-                            parameters: Default::default(),
-                            captured,
-                            pc_block: BlockId(0),
-                            pc_stmt_idx: 0,
-                            return_register: None,
-                            handlers: [(
-                                Effect {
-                                    input: effect.output,
-                                    output: continuation_from_handler[0].cfg.output.clone(),
-                                },
-                                Handler::Continuation(continuation_from_handler.into()),
-                            )]
-                            .into_iter()
-                            .collect(),
-                        };
-                        self.stack.push(eval_mir);
-                        InterpreterOutput::Running
-                    }
-                    eval_cfg::Handler::Continuation(continuation) => {
-                        self.stack.extend(continuation_from_handler);
-                        self.stack.extend(continuation);
-                        // path input to continuation
-                        self.stack().return_or_continue_with_value(input);
-                        InterpreterOutput::Running
-                    }
-                }
+                let Closure {
+                    mir,
+                    mut captured,
+                    handlers,
+                    continuation: _,
+                } = handler;
+                captured.insert(effect.input.without_quantifiers(), input);
+                // Deep handler: the handled expression with its handlers is in the continuation.
+                let eval_mir = EvalCfg {
+                    cfg: self.get_mir(&mir).clone(),
+                    registers: Default::default(),
+                    parameters: Default::default(),
+                    captured,
+                    pc_block: BlockId(0),
+                    pc_stmt_idx: 0,
+                    return_register: None,
+                    handlers,
+                    continuation: Some(continuation_from_handler.into()),
+                };
+                self.stack.push(eval_mir);
+                InterpreterOutput::Running
+            }
+            InnerOutput::Continue { input } => {
+                // The nearest handler may be below the frames of closures called in the handler.
+                let continuation = self
+                    .stack
+                    .iter()
+                    .rev()
+                    .find_map(|eval_cfg| eval_cfg.continuation.clone())
+                    .expect("continue must be in a handler");
+                self.resume(continuation, input)
             }
             InnerOutput::RunOther { fn_ref, parameters } => match fn_ref {
-                value::FnRef::Link(_) => todo!(),
+                // A link runs the entrypoint of the linked card.
+                value::FnRef::Link(link) => {
+                    let mir =
+                        self.links.get(&link).copied().unwrap_or_else(|| {
+                            panic!("link must be resolved by linker {:?}", link)
+                        });
+                    let eval_mir = EvalCfg {
+                        cfg: self.get_mir(&mir).clone(),
+                        registers: Default::default(),
+                        parameters,
+                        captured: Default::default(),
+                        pc_block: Default::default(),
+                        pc_stmt_idx: Default::default(),
+                        return_register: None,
+                        handlers: Default::default(),
+                        continuation: None,
+                    };
+                    self.stack.push(eval_mir);
+                    InterpreterOutput::Running
+                }
                 value::FnRef::Closure(Closure {
                     mir,
                     captured,
                     handlers,
+                    continuation,
                 }) => {
                     let eval_mir = EvalCfg {
                         cfg: self.get_mir(&mir).clone(),
@@ -146,6 +219,7 @@ impl Interpreter for EvalMir {
                         pc_stmt_idx: Default::default(),
                         return_register: None,
                         handlers,
+                        continuation,
                     };
                     self.stack.push(eval_mir);
                     InterpreterOutput::Running
@@ -160,6 +234,7 @@ impl Interpreter for EvalMir {
                         pc_stmt_idx: Default::default(),
                         return_register: None,
                         handlers: self.stack().handlers.clone(),
+                        continuation: self.stack().continuation.clone(),
                     };
                     self.stack.push(eval_mir);
                     InterpreterOutput::Running
@@ -171,8 +246,7 @@ impl Interpreter for EvalMir {
     }
 
     fn effect_output(&mut self, value: Value) {
-        self.stack()
-            .return_or_continue_with_value(from_sendable(value));
+        self.stack().eval_continue(from_sendable(value));
     }
 }
 
@@ -196,8 +270,15 @@ fn to_sendable(value: crate::value::Value) -> dprocess::value::Value {
         value::Value::Vector(values) => dprocess::value::Value::Vector(
             values.into_iter().map(|value| to_sendable(value)).collect(),
         ),
+        value::Value::Set(values) => {
+            dprocess::value::Value::Set(values.into_iter().map(to_sendable).collect())
+        }
         value::Value::FnRef(_) => panic!(),
-        value::Value::TraitObject { ty, value } => dprocess::value::Value::TraitObject {
+        value::Value::TraitObject {
+            ty,
+            value,
+            methods: _,
+        } => dprocess::value::Value::TraitObject {
             ty,
             value: Box::new(to_sendable(*value)),
         },
@@ -229,9 +310,62 @@ fn from_sendable(value: Value) -> value::Value {
                 .map(|value| from_sendable(value))
                 .collect(),
         ),
+        Value::Set(values) => value::Value::Set(values.into_iter().map(from_sendable).collect()),
         Value::TraitObject { ty, value } => value::Value::TraitObject {
             ty,
             value: Box::new(from_sendable(*value)),
+            methods: Default::default(),
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mir::{
+        block::BasicBlock,
+        scope::{Scope, ScopeId},
+        stmt::{Const, Stmt, StmtBind, Terminator},
+        var::{Var, VarId, Vars},
+    };
+    use types::Type;
+
+    use super::*;
+
+    #[test]
+    fn builds_from_artifact() {
+        let mir = Mir {
+            entrypoint: ControlFlowGraphId(0),
+            cfgs: vec![ControlFlowGraph {
+                parameters: vec![],
+                captured: vec![],
+                output: Type::Number,
+                vars: Vars(vec![Var {
+                    ty: Type::Number,
+                    scope: ScopeId(0),
+                }]),
+                scopes: vec![Scope { super_scope: None }],
+                blocks: vec![BasicBlock {
+                    stmts: vec![StmtBind {
+                        var: VarId(0),
+                        stmt: Stmt::Const(Const::Int(1)),
+                    }],
+                    terminator: Terminator::Return(VarId(0)),
+                }],
+                links: vec![],
+            }],
+            links: Default::default(),
+        };
+        let bytes = Artifact::new(mir, 0).to_bytes().unwrap();
+        let mut miri = MiriBuilder::from_bytes(&bytes).unwrap().build();
+        let output = loop {
+            match miri.reduce(&Duration::from_secs(1)).unwrap() {
+                InterpreterOutput::Running => continue,
+                output => break output,
+            }
+        };
+        assert_eq!(
+            output,
+            InterpreterOutput::Returned(Value::Number(Number::Integer(1)))
+        );
+    }
+}
@@ -1,45 +1,99 @@
 mod add;
+mod bit;
+mod cmp;
+mod collection;
+mod div;
 mod eq;
+mod modulo;
+mod mul;
+mod number;
+mod pow;
+mod rem;
 mod sub;
+mod unary;
 
 use mir::{stmt::Op, var::VarId};
+use types::{Effect, Type};
 
 use crate::{eval_cfg::EvalCfg, value::Value};
 
+/// An effect performed by an operator instead of returning a value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpEffect {
+    /// Performed with the dividend.
+    DivisionByZero(Box<Value>),
+    /// Performed with the result approximated by a float.
+    Overflow(f64),
+    /// Performed with the index, and the output is used as the item.
+    IndexOutOfBounds { index: Box<Value>, item: Box<Type> },
+}
+
+impl OpEffect {
+    pub fn into_perform(self) -> (Value, Effect) {
+        match self {
+            OpEffect::DivisionByZero(dividend) => (*dividend, Effect::division_by_zero()),
+            OpEffect::Overflow(approx) => (Value::Float(approx), Effect::overflow()),
+            OpEffect::IndexOutOfBounds { index, item } => {
+                (*index, Effect::index_out_of_bounds(*item))
+            }
+        }
+    }
+}
+
+type Binary = fn(&Value, &Value) -> Result<Value, OpEffect>;
+type Unary = fn(&Value) -> Result<Value, OpEffect>;
+
 impl EvalCfg {
-    pub fn eval_op(&self, op: &Op, operands: &[VarId]) -> Value {
+    pub fn eval_op(&self, op: &Op, operands: &[VarId]) -> Result<Value, OpEffect> {
+        let binary = |calc: Binary| {
+            assert!(operands.len() == 2);
+            calc(self.load_value(&operands[0]), self.load_value(&operands[1]))
+        };
+        let unary = |calc: Unary| {
+            assert!(operands.len() == 1);
+            calc(self.load_value(&operands[0]))
+        };
         match op {
-            Op::Add => {
-                assert!(operands.len() == 2);
-                add::calc(self.load_value(&operands[0]), self.load_value(&operands[1]))
-            }
-            Op::Sub => {
-                assert!(operands.len() == 2);
-                sub::calc(self.load_value(&operands[0]), self.load_value(&operands[1]))
-            }
-            Op::Mul => todo!(),
-            Op::Div => todo!(),
-            Op::Rem => todo!(),
-            Op::Mod => todo!(),
-            Op::Pow => todo!(),
-            Op::Eq => {
+            Op::Add => binary(add::calc),
+            Op::Sub => binary(sub::calc),
+            Op::Mul => binary(mul::calc),
+            Op::Div => binary(div::calc),
+            Op::Rem => binary(rem::calc),
+            Op::Mod => binary(modulo::calc),
+            Op::Pow => binary(pow::calc),
+            Op::Eq => binary(eq::eq),
+            Op::Neq => binary(eq::neq),
+            Op::Lt => binary(cmp::lt),
+            Op::Le => binary(cmp::le),
+            Op::Gt => binary(cmp::gt),
+            Op::Ge => binary(cmp::ge),
+            Op::Cmp => binary(cmp::cmp),
+            Op::Not => unary(unary::not),
+            Op::Neg => unary(unary::neg),
+            Op::Pos => unary(unary::pos),
+            Op::Shl => binary(bit::shl),
+            Op::Shr => binary(bit::shr),
+            Op::BitAnd => binary(bit::and),
+            Op::BitOr => binary(bit::or),
+            Op::BitXor => binary(bit::xor),
+            Op::BitNot => unary(bit::not),
+            Op::Length => unary(collection::length),
+            Op::Index => {
                 assert!(operands.len() == 2);
-                eq::calc(self.load_value(&operands[0]), self.load_value(&operands[1]))
+                let item = match self.get_var_ty(&operands[0]) {
+                    Type::Vector(item) => item,
+                    ty => panic!("operand should be a vector but {:?}", ty),
+                };
+                collection::index(
+                    self.load_value(&operands[0]),
+                    self.load_value(&operands[1]),
+                    item,
+                )
             }
-            Op::Neq => todo!(),
-            Op::Lt => todo!(),
-            Op::Le => todo!(),
-            Op::Gt => todo!(),
-            Op::Ge => todo!(),
-            Op::Not => todo!(),
-            Op::Neg => todo!(),
-            Op::Pos => todo!(),
-            Op::Shl => todo!(),
-            Op::Shr => todo!(),
-            Op::BitAnd => todo!(),
-            Op::BitOr => todo!(),
-            Op::BitXor => todo!(),
-            Op::BitNot => todo!(),
+            Op::Push => binary(collection::push),
+            Op::Member => binary(collection::member),
+            Op::Union => binary(collection::union),
+            Op::Intersection => binary(collection::intersection),
         }
     }
 }
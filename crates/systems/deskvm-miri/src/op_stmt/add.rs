@@ -1,30 +1,23 @@
 use crate::value::Value;
 
-pub(crate) fn calc(lhs: &Value, rhs: &Value) -> Value {
-    match (lhs, rhs) {
-        (Value::Int(int), Value::Int(int2)) => Value::Int(int + int2),
-        (Value::Float(float), Value::Float(float2)) => Value::Float(float + float2),
-        (Value::Rational(a, b), Value::Rational(a2, b2)) => {
-            // TODO: use GCD and LCM
-            let a = a * b2 + a2 * b;
-            let b = b * b2;
-            if a % b == 0 {
-                Value::Int(a / b)
-            } else {
-                Value::Rational(a, b)
-            }
-        }
-        (Value::Int(int), Value::Float(float)) | (Value::Float(float), Value::Int(int)) => {
-            Value::Float(*int as f64 + float)
-        }
-        (Value::Int(int), Value::Rational(a, b)) | (Value::Rational(a, b), Value::Int(int)) => {
-            Value::Rational(a + int * b, *b)
-        }
-        (Value::Float(float), Value::Rational(a, b))
-        | (Value::Rational(a, b), Value::Float(float)) => {
-            Value::Float(float + *a as f64 / *b as f64)
+use super::{
+    number::{overflow, promote, rational, Operands},
+    OpEffect,
+};
+
+pub(crate) fn calc(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    match promote(lhs, rhs)? {
+        Operands::Int(a, b) => a
+            .checked_add(b)
+            .map(Value::Int)
+            .ok_or_else(|| overflow(a as f64 + b as f64)),
+        Operands::Rational((a, b), (a2, b2)) => {
+            let numerator = (a * b2)
+                .checked_add(a2 * b)
+                .ok_or_else(|| overflow(a as f64 / b as f64 + a2 as f64 / b2 as f64))?;
+            rational(numerator, b * b2)
         }
-        _ => panic!("adds not numbers"),
+        Operands::Float(a, b) => Ok(Value::Float(a + b)),
     }
 }
 
@@ -35,19 +28,31 @@ mod tests {
     pub fn rational() {
         assert_eq!(
             calc(&Value::Rational(1, 2), &Value::Rational(2, 3)),
-            Value::Rational(7, 6)
+            Ok(Value::Rational(7, 6))
         );
         assert_eq!(
             calc(&Value::Rational(2, 3), &Value::Rational(1, 3)),
-            Value::Int(1)
+            Ok(Value::Int(1))
         );
         assert_eq!(
             calc(&Value::Rational(1, 2), &Value::Int(3)),
-            Value::Rational(7, 2)
+            Ok(Value::Rational(7, 2))
         );
         assert_eq!(
             calc(&Value::Rational(1, 2), &Value::Float(3.0)),
-            Value::Float(3.5)
+            Ok(Value::Float(3.5))
+        );
+        assert_eq!(
+            calc(&Value::Rational(1, 6), &Value::Rational(1, 3)),
+            Ok(Value::Rational(1, 2))
+        );
+    }
+
+    #[test]
+    pub fn overflow() {
+        assert_eq!(
+            calc(&Value::Int(i64::MAX), &Value::Int(1)),
+            Err(super::overflow(i64::MAX as f64 + 1.0))
         );
     }
 }
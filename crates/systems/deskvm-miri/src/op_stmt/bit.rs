@@ -0,0 +1,100 @@
+use crate::value::Value;
+
+use super::{
+    number::{as_float, as_rational, overflow},
+    OpEffect,
+};
+
+/// Bitwise operators work on integers, so rationals and floats are truncated toward zero.
+fn truncate(value: &Value) -> Result<i64, OpEffect> {
+    match value {
+        Value::Int(int) => Ok(*int),
+        Value::Float(float) => {
+            // `as` saturates, so check the range first.
+            if float.trunc() >= i64::MIN as f64 && float.trunc() < i64::MAX as f64 {
+                Ok(*float as i64)
+            } else {
+                Err(overflow(float.trunc()))
+            }
+        }
+        _ => {
+            let (numerator, denominator) = as_rational(value)?;
+            // The quotient is never larger than the numerator.
+            Ok((numerator / denominator) as i64)
+        }
+    }
+}
+
+/// Shifts are multiplication and floored division by powers of two.
+fn shift(value: &Value, amount: &Value) -> Result<Value, OpEffect> {
+    let int = truncate(value)?;
+    let amount = truncate(amount)?;
+    let approx = || overflow(as_float(value).trunc() * 2f64.powf(amount as f64));
+    if amount >= 0 {
+        let amount = u32::try_from(amount).map_err(|_| approx())?;
+        2i128
+            .checked_pow(amount)
+            .and_then(|power| (int as i128).checked_mul(power))
+            .and_then(|shifted| i64::try_from(shifted).ok())
+            .map(Value::Int)
+            .ok_or_else(approx)
+    } else {
+        // Shifting out every bit leaves only the sign.
+        let amount = u32::try_from(amount.unsigned_abs())
+            .unwrap_or(u32::MAX)
+            .min(63);
+        Ok(Value::Int(int >> amount))
+    }
+}
+
+pub(crate) fn shl(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    shift(lhs, rhs)
+}
+
+pub(crate) fn shr(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    let amount = truncate(rhs)?;
+    shift(lhs, &Value::Int(amount.checked_neg().unwrap_or(i64::MAX)))
+}
+
+pub(crate) fn and(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    Ok(Value::Int(truncate(lhs)? & truncate(rhs)?))
+}
+
+pub(crate) fn or(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    Ok(Value::Int(truncate(lhs)? | truncate(rhs)?))
+}
+
+pub(crate) fn xor(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    Ok(Value::Int(truncate(lhs)? ^ truncate(rhs)?))
+}
+
+pub(crate) fn not(operand: &Value) -> Result<Value, OpEffect> {
+    Ok(Value::Int(!truncate(operand)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    pub fn bitwise() {
+        assert_eq!(and(&Value::Int(6), &Value::Int(3)), Ok(Value::Int(2)));
+        assert_eq!(
+            or(&Value::Rational(7, 2), &Value::Int(4)),
+            Ok(Value::Int(7))
+        );
+        assert_eq!(xor(&Value::Float(6.9), &Value::Int(3)), Ok(Value::Int(5)));
+        assert_eq!(not(&Value::Int(0)), Ok(Value::Int(-1)));
+    }
+
+    #[test]
+    pub fn shifts() {
+        assert_eq!(shl(&Value::Int(3), &Value::Int(2)), Ok(Value::Int(12)));
+        assert_eq!(shl(&Value::Int(12), &Value::Int(-2)), Ok(Value::Int(3)));
+        assert_eq!(shr(&Value::Int(-5), &Value::Int(1)), Ok(Value::Int(-3)));
+        assert_eq!(shr(&Value::Int(-5), &Value::Int(100)), Ok(Value::Int(-1)));
+        assert_eq!(
+            shl(&Value::Int(1), &Value::Int(63)),
+            Err(overflow(2f64.powf(63.0)))
+        );
+    }
+}
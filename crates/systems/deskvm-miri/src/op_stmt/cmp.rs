@@ -0,0 +1,73 @@
+use std::cmp::Ordering;
+
+use crate::value::Value;
+
+use super::{
+    number::{as_float, boolean, compare, variant},
+    OpEffect,
+};
+
+fn calc(lhs: &Value, rhs: &Value, pred: fn(Ordering) -> bool) -> Result<Value, OpEffect> {
+    // NaN is not ordered, so every comparison with it is false.
+    Ok(boolean(compare(lhs, rhs)?.is_some_and(pred)))
+}
+
+pub(crate) fn lt(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    calc(lhs, rhs, Ordering::is_lt)
+}
+
+pub(crate) fn le(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    calc(lhs, rhs, Ordering::is_le)
+}
+
+pub(crate) fn gt(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    calc(lhs, rhs, Ordering::is_gt)
+}
+
+pub(crate) fn ge(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    calc(lhs, rhs, Ordering::is_ge)
+}
+
+/// NaN is compared in the total order of floats.
+pub(crate) fn cmp(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    let ordering = compare(lhs, rhs)?.unwrap_or_else(|| as_float(lhs).total_cmp(&as_float(rhs)));
+    Ok(variant(match ordering {
+        Ordering::Greater => "greater",
+        Ordering::Less => "less",
+        Ordering::Equal => "equal",
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    pub fn compares() {
+        assert_eq!(
+            lt(&Value::Rational(1, 3), &Value::Rational(1, 2)),
+            Ok(boolean(true))
+        );
+        assert_eq!(le(&Value::Int(1), &Value::Float(1.0)), Ok(boolean(true)));
+        assert_eq!(
+            gt(&Value::Int(-1), &Value::Rational(-1, 2)),
+            Ok(boolean(false))
+        );
+        assert_eq!(
+            ge(&Value::Float(f64::NAN), &Value::Int(0)),
+            Ok(boolean(false))
+        );
+    }
+
+    #[test]
+    pub fn returns_ordering() {
+        assert_eq!(
+            cmp(&Value::Int(3), &Value::Rational(5, 2)),
+            Ok(variant("greater"))
+        );
+        assert_eq!(
+            cmp(&Value::Float(1.0), &Value::Int(1)),
+            Ok(variant("equal"))
+        );
+        assert_eq!(cmp(&Value::Int(-1), &Value::Int(0)), Ok(variant("less")));
+    }
+}
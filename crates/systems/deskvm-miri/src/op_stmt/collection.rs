@@ -0,0 +1,112 @@
+use types::Type;
+
+use crate::value::Value;
+
+use super::{number::boolean, OpEffect};
+
+pub(crate) fn length(collection: &Value) -> Result<Value, OpEffect> {
+    match collection {
+        Value::Vector(values) | Value::Set(values) => Ok(Value::Int(values.len() as i64)),
+        _ => panic!("operand should be a vector or a set"),
+    }
+}
+
+/// Performs the index out of bounds effect with the index if it's not an integer in range.
+pub(crate) fn index(vector: &Value, index: &Value, item: &Type) -> Result<Value, OpEffect> {
+    let out_of_bounds = || OpEffect::IndexOutOfBounds {
+        index: Box::new(index.clone()),
+        item: Box::new(item.clone()),
+    };
+    match (vector, index) {
+        (Value::Vector(values), Value::Int(int)) => usize::try_from(*int)
+            .ok()
+            .and_then(|int| values.get(int))
+            .cloned()
+            .ok_or_else(out_of_bounds),
+        (Value::Vector(_), _) => Err(out_of_bounds()),
+        _ => panic!("operand should be a vector"),
+    }
+}
+
+pub(crate) fn push(vector: &Value, item: &Value) -> Result<Value, OpEffect> {
+    match vector {
+        Value::Vector(values) => {
+            let mut values = values.clone();
+            values.push(item.clone());
+            Ok(Value::Vector(values))
+        }
+        _ => panic!("operand should be a vector"),
+    }
+}
+
+pub(crate) fn member(set: &Value, item: &Value) -> Result<Value, OpEffect> {
+    Ok(boolean(items(set).contains(item)))
+}
+
+pub(crate) fn union(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    Ok(Value::set(items(lhs).iter().chain(items(rhs)).cloned()))
+}
+
+pub(crate) fn intersection(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    let rhs = items(rhs);
+    Ok(Value::set(
+        items(lhs)
+            .iter()
+            .filter(|value| rhs.contains(value))
+            .cloned(),
+    ))
+}
+
+fn items(set: &Value) -> &[Value] {
+    match set {
+        Value::Set(values) => values,
+        _ => panic!("operand should be a set"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(values: &[i64]) -> Value {
+        Value::set(values.iter().map(|int| Value::Int(*int)))
+    }
+
+    #[test]
+    pub fn vector() {
+        let vector = Value::Vector(vec![Value::Int(1), Value::Int(2)]);
+        assert_eq!(length(&vector), Ok(Value::Int(2)));
+        assert_eq!(
+            index(&vector, &Value::Int(1), &Type::Number),
+            Ok(Value::Int(2))
+        );
+        assert_eq!(
+            index(&vector, &Value::Int(2), &Type::Number),
+            Err(OpEffect::IndexOutOfBounds {
+                index: Box::new(Value::Int(2)),
+                item: Box::new(Type::Number)
+            })
+        );
+        assert_eq!(
+            push(&vector, &Value::Int(3)),
+            Ok(Value::Vector(vec![
+                Value::Int(1),
+                Value::Int(2),
+                Value::Int(3)
+            ]))
+        );
+    }
+
+    #[test]
+    pub fn set_operations() {
+        assert_eq!(
+            set(&[1, 2, 1]),
+            Value::Set(vec![Value::Int(1), Value::Int(2)])
+        );
+        assert_eq!(length(&set(&[1, 1])), Ok(Value::Int(1)));
+        assert_eq!(member(&set(&[1, 2]), &Value::Int(2)), Ok(boolean(true)));
+        assert_eq!(member(&set(&[1, 2]), &Value::Int(3)), Ok(boolean(false)));
+        assert_eq!(union(&set(&[1, 2]), &set(&[2, 3])), Ok(set(&[1, 2, 3])));
+        assert_eq!(intersection(&set(&[1, 2]), &set(&[2, 3])), Ok(set(&[2])));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::value::Value;
+
+use super::{
+    number::{division_by_zero, is_zero, promote, rational, Operands},
+    OpEffect,
+};
+
+/// Division of integers results in an exact rational.
+pub(crate) fn calc(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    if is_zero(rhs) {
+        return Err(division_by_zero(lhs.clone()));
+    }
+    match promote(lhs, rhs)? {
+        Operands::Int(a, b) => rational(a as i128, b as i128),
+        Operands::Rational((a, b), (a2, b2)) => rational(a * b2, b * a2),
+        Operands::Float(a, b) => Ok(Value::Float(a / b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    pub fn rational() {
+        assert_eq!(calc(&Value::Int(6), &Value::Int(3)), Ok(Value::Int(2)));
+        assert_eq!(
+            calc(&Value::Int(2), &Value::Int(-4)),
+            Ok(Value::Rational(-1, 2))
+        );
+        assert_eq!(
+            calc(&Value::Rational(2, 3), &Value::Rational(4, 3)),
+            Ok(Value::Rational(1, 2))
+        );
+        assert_eq!(
+            calc(&Value::Float(3.0), &Value::Int(2)),
+            Ok(Value::Float(1.5))
+        );
+    }
+
+    #[test]
+    pub fn division_by_zero() {
+        assert_eq!(
+            calc(&Value::Int(1), &Value::Int(0)),
+            Err(super::division_by_zero(Value::Int(1)))
+        );
+        assert_eq!(
+            calc(&Value::Float(1.0), &Value::Float(0.0)),
+            Err(super::division_by_zero(Value::Float(1.0)))
+        );
+    }
+}
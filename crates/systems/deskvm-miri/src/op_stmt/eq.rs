@@ -1,40 +1,68 @@
-use types::Type;
+use std::cmp::Ordering;
 
 use crate::value::Value;
 
-pub(crate) fn calc(lhs: &Value, rhs: &Value) -> Value {
-    let eq = |pred| {
-        if pred {
-            Value::Variant {
-                ty: Type::Label {
-                    label: "equal".to_string(),
-                    item: Box::new(Type::Product(vec![])),
-                },
-                value: Box::new(Value::Unit),
-            }
-        } else {
-            Value::Variant {
-                ty: Type::Label {
-                    label: "unequal".to_string(),
-                    item: Box::new(Type::Product(vec![])),
-                },
-                value: Box::new(Value::Unit),
-            }
-        }
-    };
-    match (lhs, rhs) {
-        (x, y) if x == y => eq(true),
-        (Value::Int(int), Value::Int(int2)) => eq(int == int2),
-        (Value::Float(float), Value::Float(float2)) => eq(float == float2),
-        (Value::Rational(a, b), Value::Rational(a2, b2)) => eq(a * b2 == a2 * b),
-        (Value::Int(int), Value::Float(float)) | (Value::Float(float), Value::Int(int)) => {
-            eq(*int as f64 == *float)
-        }
-        (Value::Int(int), Value::Rational(a, b)) | (Value::Rational(a, b), Value::Int(int)) => {
-            eq(*a as f64 / *b as f64 == *int as f64)
-        }
-        (Value::Float(float), Value::Rational(a, b))
-        | (Value::Rational(a, b), Value::Float(float)) => eq(*float == *a as f64 / *b as f64),
-        _ => panic!("adds not numbers"),
+use super::{
+    number::{compare, is_number, variant},
+    OpEffect,
+};
+
+fn equals(lhs: &Value, rhs: &Value) -> Result<bool, OpEffect> {
+    if is_number(lhs) && is_number(rhs) {
+        Ok(compare(lhs, rhs)? == Some(Ordering::Equal))
+    } else {
+        Ok(lhs == rhs)
+    }
+}
+
+pub(crate) fn eq(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    Ok(variant(if equals(lhs, rhs)? {
+        "equal"
+    } else {
+        "unequal"
+    }))
+}
+
+pub(crate) fn neq(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    Ok(variant(if equals(lhs, rhs)? {
+        "unequal"
+    } else {
+        "equal"
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    pub fn numbers() {
+        assert_eq!(
+            eq(&Value::Int(1), &Value::Rational(2, 2)),
+            Ok(variant("equal"))
+        );
+        assert_eq!(
+            eq(&Value::Rational(1, 2), &Value::Float(0.5)),
+            Ok(variant("equal"))
+        );
+        assert_eq!(
+            eq(&Value::Rational(1, 3), &Value::Rational(2, 3)),
+            Ok(variant("unequal"))
+        );
+        assert_eq!(
+            neq(&Value::Int(1), &Value::Float(1.0)),
+            Ok(variant("unequal"))
+        );
+    }
+
+    #[test]
+    pub fn not_numbers() {
+        assert_eq!(
+            eq(&Value::String("a".into()), &Value::String("a".into())),
+            Ok(variant("equal"))
+        );
+        assert_eq!(
+            eq(&Value::String("a".into()), &Value::Int(1)),
+            Ok(variant("unequal"))
+        );
     }
 }
@@ -0,0 +1,57 @@
+use crate::value::Value;
+
+use super::{
+    number::{division_by_zero, is_zero, promote, rational, Operands},
+    OpEffect,
+};
+
+/// The remainder of floored division, which has the sign of the divisor.
+pub(crate) fn calc(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    if is_zero(rhs) {
+        return Err(division_by_zero(lhs.clone()));
+    }
+    match promote(lhs, rhs)? {
+        Operands::Int(a, b) => rational(floored(a as i128, b as i128), 1),
+        Operands::Rational((a, b), (a2, b2)) => rational(floored(a * b2, a2 * b), b * b2),
+        Operands::Float(a, b) => {
+            let rem = a % b;
+            if rem != 0.0 && (rem < 0.0) != (b < 0.0) {
+                Ok(Value::Float(rem + b))
+            } else {
+                Ok(Value::Float(rem))
+            }
+        }
+    }
+}
+
+fn floored(a: i128, b: i128) -> i128 {
+    let rem = a % b;
+    if rem != 0 && (rem < 0) != (b < 0) {
+        rem + b
+    } else {
+        rem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    pub fn rational() {
+        assert_eq!(calc(&Value::Int(7), &Value::Int(3)), Ok(Value::Int(1)));
+        assert_eq!(calc(&Value::Int(-7), &Value::Int(3)), Ok(Value::Int(2)));
+        assert_eq!(calc(&Value::Int(7), &Value::Int(-3)), Ok(Value::Int(-2)));
+        assert_eq!(
+            calc(&Value::Rational(-1, 2), &Value::Int(1)),
+            Ok(Value::Rational(1, 2))
+        );
+        assert_eq!(
+            calc(&Value::Float(-7.5), &Value::Int(2)),
+            Ok(Value::Float(0.5))
+        );
+        assert_eq!(
+            calc(&Value::Int(1), &Value::Int(0)),
+            Err(division_by_zero(Value::Int(1)))
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use crate::value::Value;
+
+use super::{
+    number::{overflow, promote, rational, Operands},
+    OpEffect,
+};
+
+pub(crate) fn calc(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    match promote(lhs, rhs)? {
+        Operands::Int(a, b) => a
+            .checked_mul(b)
+            .map(Value::Int)
+            .ok_or_else(|| overflow(a as f64 * b as f64)),
+        Operands::Rational((a, b), (a2, b2)) => rational(a * a2, b * b2),
+        Operands::Float(a, b) => Ok(Value::Float(a * b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    pub fn rational() {
+        assert_eq!(calc(&Value::Int(3), &Value::Int(4)), Ok(Value::Int(12)));
+        assert_eq!(
+            calc(&Value::Rational(2, 3), &Value::Rational(3, 4)),
+            Ok(Value::Rational(1, 2))
+        );
+        assert_eq!(
+            calc(&Value::Rational(1, 2), &Value::Int(4)),
+            Ok(Value::Int(2))
+        );
+        assert_eq!(
+            calc(&Value::Rational(1, 2), &Value::Float(3.0)),
+            Ok(Value::Float(1.5))
+        );
+    }
+
+    #[test]
+    pub fn overflow() {
+        assert_eq!(
+            calc(&Value::Int(i64::MAX), &Value::Int(2)),
+            Err(super::overflow(i64::MAX as f64 * 2.0))
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::cmp::Ordering;
+
+use types::Type;
+
+use crate::value::Value;
+
+use super::OpEffect;
+
+/// Operands promoted to a common representation.
+///
+/// Ints are promoted to rationals, and anything with a float is promoted to floats.
+pub(crate) enum Operands {
+    Int(i64, i64),
+    Rational((i128, i128), (i128, i128)),
+    Float(f64, f64),
+}
+
+pub(crate) fn promote(lhs: &Value, rhs: &Value) -> Result<Operands, OpEffect> {
+    let operands = match (lhs, rhs) {
+        (Value::Int(a), Value::Int(b)) => Operands::Int(*a, *b),
+        (Value::Float(_), _) | (_, Value::Float(_)) => {
+            Operands::Float(as_float(lhs), as_float(rhs))
+        }
+        _ => Operands::Rational(as_rational(lhs)?, as_rational(rhs)?),
+    };
+    Ok(operands)
+}
+
+/// Returns the numerator and the positive denominator of an integer or a rational.
+pub(crate) fn as_rational(value: &Value) -> Result<(i128, i128), OpEffect> {
+    match value {
+        Value::Int(int) => Ok((*int as i128, 1)),
+        Value::Rational(_, 0) => Err(division_by_zero(value.clone())),
+        Value::Rational(a, b) if *b < 0 => Ok((-(*a as i128), -(*b as i128))),
+        Value::Rational(a, b) => Ok((*a as i128, *b as i128)),
+        _ => panic!("operand should be an integer or a rational"),
+    }
+}
+
+pub(crate) fn as_float(value: &Value) -> f64 {
+    match value {
+        Value::Int(int) => *int as f64,
+        Value::Float(float) => *float,
+        Value::Rational(a, b) => *a as f64 / *b as f64,
+        _ => panic!("operand should be a number"),
+    }
+}
+
+pub(crate) fn is_number(value: &Value) -> bool {
+    matches!(value, Value::Int(_) | Value::Float(_) | Value::Rational(..))
+}
+
+pub(crate) fn is_zero(value: &Value) -> bool {
+    match value {
+        Value::Int(int) => *int == 0,
+        Value::Float(float) => *float == 0.0,
+        Value::Rational(a, _) => *a == 0,
+        _ => false,
+    }
+}
+
+/// Makes a normalized rational, which is an int if the denominator is one.
+///
+/// The denominator must not be zero.
+pub(crate) fn rational(numerator: i128, denominator: i128) -> Result<Value, OpEffect> {
+    let approx = numerator as f64 / denominator as f64;
+    let sign = denominator.signum();
+    let gcd = gcd(numerator, denominator);
+    let (numerator, denominator) = (sign * numerator / gcd, sign * denominator / gcd);
+    let numerator = i64::try_from(numerator).map_err(|_| overflow(approx))?;
+    if denominator == 1 {
+        Ok(Value::Int(numerator))
+    } else {
+        let denominator = i64::try_from(denominator).map_err(|_| overflow(approx))?;
+        Ok(Value::Rational(numerator, denominator))
+    }
+}
+
+pub(crate) fn compare(lhs: &Value, rhs: &Value) -> Result<Option<Ordering>, OpEffect> {
+    let ordering = match promote(lhs, rhs)? {
+        Operands::Int(a, b) => Some(a.cmp(&b)),
+        Operands::Rational((a, b), (a2, b2)) => Some((a * b2).cmp(&(a2 * b))),
+        Operands::Float(a, b) => a.partial_cmp(&b),
+    };
+    Ok(ordering)
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    // The denominator is never zero, so it fits in i128 unless both are i128::MIN.
+    a as i128
+}
+
+pub(crate) fn division_by_zero(dividend: Value) -> OpEffect {
+    OpEffect::DivisionByZero(Box::new(dividend))
+}
+
+/// The input is the result approximated by a float.
+pub(crate) fn overflow(approx: f64) -> OpEffect {
+    OpEffect::Overflow(approx)
+}
+
+pub(crate) fn variant(label: &str) -> Value {
+    Value::Variant {
+        ty: Type::label(label, Type::unit()),
+        value: Box::new(Value::Unit),
+    }
+}
+
+pub(crate) fn boolean(pred: bool) -> Value {
+    variant(if pred { "true" } else { "false" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_rational() {
+        assert_eq!(rational(4, 6), Ok(Value::Rational(2, 3)));
+        assert_eq!(rational(4, -6), Ok(Value::Rational(-2, 3)));
+        assert_eq!(rational(-6, -3), Ok(Value::Int(2)));
+        assert_eq!(rational(0, 5), Ok(Value::Int(0)));
+        assert_eq!(
+            rational(i64::MAX as i128 + 1, 1),
+            Err(overflow(i64::MAX as f64 + 1.0))
+        );
+    }
+
+    #[test]
+    fn promotes_operands() {
+        assert!(matches!(
+            promote(&Value::Int(1), &Value::Rational(1, 2)),
+            Ok(Operands::Rational((1, 1), (1, 2)))
+        ));
+        assert!(matches!(
+            promote(&Value::Rational(1, 2), &Value::Float(1.0)),
+            Ok(Operands::Float(a, b)) if a == 0.5 && b == 1.0
+        ));
+        assert!(matches!(
+            promote(&Value::Rational(1, 0), &Value::Int(1)),
+            Err(OpEffect::DivisionByZero(_))
+        ));
+    }
+}
@@ -0,0 +1,61 @@
+use crate::value::Value;
+
+use super::{
+    number::{as_float, as_rational, division_by_zero, overflow, rational},
+    OpEffect,
+};
+
+/// An integer exponent gives an exact result for integers and rationals.
+pub(crate) fn calc(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    match (lhs, rhs) {
+        (Value::Int(_) | Value::Rational(..), Value::Int(exp)) => {
+            let (mut numerator, mut denominator) = as_rational(lhs)?;
+            if *exp < 0 {
+                if numerator == 0 {
+                    return Err(division_by_zero(Value::Int(1)));
+                }
+                (numerator, denominator) = (denominator, numerator);
+            }
+            let approx = || overflow(as_float(lhs).powf(*exp as f64));
+            let exp = u32::try_from(exp.unsigned_abs()).map_err(|_| approx())?;
+            let numerator = numerator.checked_pow(exp).ok_or_else(approx)?;
+            let denominator = denominator.checked_pow(exp).ok_or_else(approx)?;
+            rational(numerator, denominator)
+        }
+        _ => Ok(Value::Float(as_float(lhs).powf(as_float(rhs)))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    pub fn rational() {
+        assert_eq!(calc(&Value::Int(2), &Value::Int(10)), Ok(Value::Int(1024)));
+        assert_eq!(
+            calc(&Value::Int(-2), &Value::Int(-3)),
+            Ok(Value::Rational(-1, 8))
+        );
+        assert_eq!(
+            calc(&Value::Rational(2, 3), &Value::Int(2)),
+            Ok(Value::Rational(4, 9))
+        );
+        assert_eq!(calc(&Value::Int(3), &Value::Int(0)), Ok(Value::Int(1)));
+        assert_eq!(
+            calc(&Value::Int(4), &Value::Rational(1, 2)),
+            Ok(Value::Float(2.0))
+        );
+    }
+
+    #[test]
+    pub fn effects() {
+        assert_eq!(
+            calc(&Value::Int(0), &Value::Int(-1)),
+            Err(division_by_zero(Value::Int(1)))
+        );
+        assert_eq!(
+            calc(&Value::Int(2), &Value::Int(64)),
+            Err(overflow(2f64.powf(64.0)))
+        );
+    }
+}
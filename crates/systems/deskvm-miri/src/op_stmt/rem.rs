@@ -0,0 +1,45 @@
+use crate::value::Value;
+
+use super::{
+    number::{division_by_zero, is_zero, promote, rational, Operands},
+    OpEffect,
+};
+
+/// The remainder of truncated division, which has the sign of the dividend.
+pub(crate) fn calc(lhs: &Value, rhs: &Value) -> Result<Value, OpEffect> {
+    if is_zero(rhs) {
+        return Err(division_by_zero(lhs.clone()));
+    }
+    match promote(lhs, rhs)? {
+        // wrapping_rem never wraps except `i64::MIN % -1`, which is 0.
+        Operands::Int(a, b) => Ok(Value::Int(a.wrapping_rem(b))),
+        Operands::Rational((a, b), (a2, b2)) => rational((a * b2) % (a2 * b), b * b2),
+        Operands::Float(a, b) => Ok(Value::Float(a % b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    pub fn rational() {
+        assert_eq!(calc(&Value::Int(7), &Value::Int(3)), Ok(Value::Int(1)));
+        assert_eq!(calc(&Value::Int(-7), &Value::Int(3)), Ok(Value::Int(-1)));
+        assert_eq!(
+            calc(&Value::Int(i64::MIN), &Value::Int(-1)),
+            Ok(Value::Int(0))
+        );
+        assert_eq!(
+            calc(&Value::Rational(7, 2), &Value::Int(1)),
+            Ok(Value::Rational(1, 2))
+        );
+        assert_eq!(
+            calc(&Value::Float(-7.5), &Value::Int(2)),
+            Ok(Value::Float(-1.5))
+        );
+        assert_eq!(
+            calc(&Value::Int(1), &Value::Rational(0, 1)),
+            Err(division_by_zero(Value::Int(1)))
+        );
+    }
+}
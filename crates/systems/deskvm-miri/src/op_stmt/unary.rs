@@ -0,0 +1,51 @@
+use types::Type;
+
+use crate::value::Value;
+
+use super::{
+    number::{as_rational, boolean, overflow, rational},
+    OpEffect,
+};
+
+pub(crate) fn not(operand: &Value) -> Result<Value, OpEffect> {
+    match operand {
+        Value::Variant {
+            ty: Type::Label { label, item: _ },
+            value: _,
+        } if label == "true" || label == "false" => Ok(boolean(label == "false")),
+        _ => panic!("operand should be a boolean"),
+    }
+}
+
+pub(crate) fn neg(operand: &Value) -> Result<Value, OpEffect> {
+    match operand {
+        Value::Int(int) => int
+            .checked_neg()
+            .map(Value::Int)
+            .ok_or_else(|| overflow(-(*int as f64))),
+        Value::Float(float) => Ok(Value::Float(-float)),
+        _ => {
+            let (numerator, denominator) = as_rational(operand)?;
+            rational(-numerator, denominator)
+        }
+    }
+}
+
+pub(crate) fn pos(operand: &Value) -> Result<Value, OpEffect> {
+    Ok(operand.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    pub fn unary() {
+        assert_eq!(not(&boolean(true)), Ok(boolean(false)));
+        assert_eq!(neg(&Value::Rational(1, -2)), Ok(Value::Rational(1, 2)));
+        assert_eq!(
+            neg(&Value::Int(i64::MIN)),
+            Err(overflow(-(i64::MIN as f64)))
+        );
+        assert_eq!(pos(&Value::Float(1.5)), Ok(Value::Float(1.5)));
+    }
+}
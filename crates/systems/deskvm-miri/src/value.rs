@@ -1,12 +1,12 @@
 use std::collections::HashMap;
 
-use mir::mir::ControlFlowGraphId;
+use mir::{mir::ControlFlowGraphId, stmt::LinkId};
 use serde::{Deserialize, Serialize};
 use types::{Effect, Type};
 
-use crate::eval_cfg::Handler;
+use crate::eval_cfg::EvalCfg;
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Value {
     // empty product
     Unit,
@@ -15,15 +15,26 @@ pub enum Value {
     Float(f64),
     Rational(i64, i64),
     Product(HashMap<Type, Value>),
-    Variant { ty: Type, value: Box<Value> },
+    Variant {
+        ty: Type,
+        value: Box<Value>,
+    },
     Vector(Vec<Self>),
+    /// Items are unique and in the order of insertion.
+    Set(Vec<Self>),
     FnRef(FnRef),
-    TraitObject { ty: Type, value: Box<Value> },
+    TraitObject {
+        ty: Type,
+        value: Box<Value>,
+        /// Implementations of the methods, which are not sendable like closures.
+        methods: HashMap<Type, FnRef>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FnRef {
-    Link(Type),
+    /// Runs the entrypoint of the linked card.
+    Link(LinkId),
     Closure(Closure),
     Recursion,
 }
@@ -32,5 +43,80 @@ pub enum FnRef {
 pub struct Closure {
     pub mir: ControlFlowGraphId,
     pub captured: HashMap<Type, Value>,
-    pub handlers: HashMap<Effect, Handler>,
+    pub handlers: HashMap<Effect, Closure>,
+    /// The continuation of the handler in which the closure is made, so it can continue after the handler returned.
+    pub continuation: Option<Vec<EvalCfg>>,
+}
+
+// Sets are equal regardless of the order of insertion.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Unit, Value::Unit) => true,
+            (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
+            (Value::Int(lhs), Value::Int(rhs)) => lhs == rhs,
+            (Value::Float(lhs), Value::Float(rhs)) => lhs == rhs,
+            (Value::Rational(lhs, lhs2), Value::Rational(rhs, rhs2)) => lhs == rhs && lhs2 == rhs2,
+            (Value::Product(lhs), Value::Product(rhs)) => lhs == rhs,
+            (
+                Value::Variant { ty, value },
+                Value::Variant {
+                    ty: ty2,
+                    value: value2,
+                },
+            ) => ty == ty2 && value == value2,
+            (Value::Vector(lhs), Value::Vector(rhs)) => lhs == rhs,
+            (Value::Set(lhs), Value::Set(rhs)) => {
+                lhs.len() == rhs.len() && lhs.iter().all(|value| rhs.contains(value))
+            }
+            (Value::FnRef(lhs), Value::FnRef(rhs)) => lhs == rhs,
+            (
+                Value::TraitObject { ty, value, methods },
+                Value::TraitObject {
+                    ty: ty2,
+                    value: value2,
+                    methods: methods2,
+                },
+            ) => ty == ty2 && value == value2 && methods == methods2,
+            _ => false,
+        }
+    }
+}
+
+impl Value {
+    /// Makes a set by removing the duplicated items with value-based equality.
+    pub fn set(values: impl IntoIterator<Item = Value>) -> Self {
+        let mut set = vec![];
+        for value in values {
+            if !set.contains(&value) {
+                set.push(value);
+            }
+        }
+        Value::Set(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(values: &[i64]) -> Value {
+        Value::set(values.iter().map(|int| Value::Int(*int)))
+    }
+
+    #[test]
+    fn nested_sets_are_unordered() {
+        assert_eq!(
+            Value::set([set(&[1, 2]), set(&[2, 1])]),
+            Value::Set(vec![set(&[1, 2])])
+        );
+        assert_eq!(
+            Value::set([set(&[1, 2]), set(&[3])]),
+            Value::set([set(&[3]), set(&[2, 1])])
+        );
+        assert_ne!(
+            Value::set([set(&[1, 2]), set(&[3])]),
+            Value::set([set(&[1]), set(&[2, 3])])
+        );
+    }
 }
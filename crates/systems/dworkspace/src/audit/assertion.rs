@@ -1,6 +1,9 @@
 use components::{
     content::ContentKind,
+    patch::ContentPatch,
     rules::{NodeOperation, SpaceOperation},
+    snapshot::Snapshot,
+    user::UserId,
 };
 use deskc_ids::NodeId;
 
@@ -13,6 +16,8 @@ pub enum Assertion<'a> {
     },
     Owner,
     NoOwner,
+    /// The user is an owner, and not the last one.
+    OwnerRemovable(&'a UserId),
     NodeExists(&'a NodeId),
     NotReferenced(&'a NodeId),
     NoOperandLoop {
@@ -27,6 +32,14 @@ pub enum Assertion<'a> {
         node_id: &'a NodeId,
         kind: ContentKind,
     },
+    ContentPatchApplies {
+        node_id: &'a NodeId,
+        patch: &'a ContentPatch,
+    },
+    /// The index is of the last handled event.
+    LatestIndex(usize),
+    /// The snapshot is the same as the current one.
+    CurrentSnapshot(&'a Snapshot),
     All(Vec<Assertion<'a>>),
     Any(Vec<Assertion<'a>>),
 }
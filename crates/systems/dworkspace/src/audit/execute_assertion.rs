@@ -1,5 +1,6 @@
 use components::{
     content::ContentKind,
+    patch::PatchError,
     rules::{NodeOperation, SpaceOperation},
     user::UserId,
 };
@@ -21,6 +22,8 @@ pub enum AssertionError {
         operation: NodeOperation,
     },
     NotOwner,
+    OwnerNotFound(UserId),
+    LastOwner(UserId),
     NodeNotFound(NodeId),
     Referenced(NodeId),
     OperandLoop {
@@ -32,6 +35,15 @@ pub enum AssertionError {
         expected: ContentKind,
         actual: ContentKind,
     },
+    PatchFailed {
+        node_id: NodeId,
+        error: PatchError,
+    },
+    IndexMismatch {
+        expected: Option<usize>,
+        actual: usize,
+    },
+    SnapshotMismatch,
     // All(Vec<AssertionError>) must not be here, because it causes file system's crash
     Any(Vec<AssertionError>),
     InsufficientOperands {
@@ -93,6 +105,16 @@ impl Workspace {
                     Err(AssertionError::NotOwner)
                 }
             }
+            Assertion::OwnerRemovable(owner_id) => {
+                if !self.snapshot.owners.contains(owner_id) {
+                    Err(AssertionError::OwnerNotFound(owner_id.clone()))
+                } else if self.snapshot.owners.len() == 1 {
+                    // A space without owners is open to anyone.
+                    Err(AssertionError::LastOwner(owner_id.clone()))
+                } else {
+                    Ok(())
+                }
+            }
             Assertion::NodeExists(node_id) => {
                 if self.snapshot.flat_nodes.contains_key(node_id) {
                     Ok(())
@@ -167,6 +189,30 @@ impl Workspace {
                     })
                 }
             }
+            Assertion::ContentPatchApplies { node_id, patch } => {
+                // Dry run on a copy, so the event is rejected before it changes anything.
+                let mut flat_node = self.snapshot.flat_nodes.get(node_id).unwrap().clone();
+                flat_node
+                    .patch_content(patch)
+                    .map_err(|error| AssertionError::PatchFailed {
+                        node_id: node_id.clone(),
+                        error,
+                    })
+            }
+            Assertion::LatestIndex(index) => match self.latest_index {
+                Some(latest) if latest == index => Ok(()),
+                latest => Err(AssertionError::IndexMismatch {
+                    expected: latest,
+                    actual: index,
+                }),
+            },
+            Assertion::CurrentSnapshot(snapshot) => {
+                if *snapshot == self.snapshot {
+                    Ok(())
+                } else {
+                    Err(AssertionError::SnapshotMismatch)
+                }
+            }
             Assertion::All(assertions) => {
                 let result: Result<Vec<_>, _> = assertions
                     .into_iter()
@@ -203,7 +249,7 @@ mod tests {
         content::Content,
         event::Event,
         flat_node::FlatNode,
-        patch::OperandPatch,
+        patch::{ContentPatch, OperandPatch},
         rules::{Rules, SpaceOperation},
         user::UserId,
     };
@@ -686,6 +732,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn owner_removable() {
+        let mut kernel = Workspace::new(TestRepository::default());
+        let user_a = UserId("a".into());
+        let user_b = UserId("b".into());
+        kernel.snapshot.owners.insert(user_a.clone());
+        assert_eq!(
+            kernel.execute_assertion(&user_a, Assertion::OwnerRemovable(&user_b)),
+            Err(AssertionError::OwnerNotFound(user_b.clone()))
+        );
+        assert_eq!(
+            kernel.execute_assertion(&user_a, Assertion::OwnerRemovable(&user_a)),
+            Err(AssertionError::LastOwner(user_a.clone()))
+        );
+        kernel.snapshot.owners.insert(user_b.clone());
+        assert_eq!(
+            kernel.execute_assertion(&user_b, Assertion::OwnerRemovable(&user_a)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn latest_index() {
+        let mut kernel = Workspace::new(TestRepository::default());
+        assert_eq!(
+            kernel.execute_assertion(&UserId("a".into()), Assertion::LatestIndex(3)),
+            Err(AssertionError::IndexMismatch {
+                expected: None,
+                actual: 3
+            })
+        );
+        kernel.latest_index = Some(2);
+        assert_eq!(
+            kernel.execute_assertion(&UserId("a".into()), Assertion::LatestIndex(2)),
+            Ok(())
+        );
+        assert_eq!(
+            kernel.execute_assertion(&UserId("a".into()), Assertion::LatestIndex(1)),
+            Err(AssertionError::IndexMismatch {
+                expected: Some(2),
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn content_patch_fails() {
+        let mut kernel = Workspace::new(TestRepository::default());
+        let node_id = NodeId::new();
+        kernel.handle_event(&Event::CreateNode {
+            node_id: node_id.clone(),
+            content: Content::Rational(1, 2),
+        });
+        assert_eq!(
+            kernel.execute_assertion(
+                &UserId("a".into()),
+                Assertion::ContentPatchApplies {
+                    node_id: &node_id,
+                    patch: &ContentPatch::UpdateRational(1, 0),
+                }
+            ),
+            Err(AssertionError::PatchFailed {
+                node_id: node_id.clone(),
+                error: PatchError::ZeroDenominator,
+            })
+        );
+        assert_eq!(
+            kernel.snapshot.flat_nodes.get(&node_id).unwrap().content,
+            Content::Rational(1, 2)
+        );
+    }
+
     #[test]
     fn all_allows() {
         let mut kernel = Workspace::new(TestRepository::default());
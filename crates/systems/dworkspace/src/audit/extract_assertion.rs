@@ -16,7 +16,10 @@ pub fn extract_assertion(event: &Event) -> Assertion {
             Assertion::Owner,
             Assertion::SpaceAllows(AddOwner),
         ]),
-        Event::RemoveOwner { .. } => Assertion::SpaceAllows(RemoveOwner),
+        Event::RemoveOwner { user_id } => Assertion::All(vec![
+            Assertion::OwnerRemovable(user_id),
+            Assertion::Any(vec![Assertion::Owner, Assertion::SpaceAllows(RemoveOwner)]),
+        ]),
         Event::CreateNode { .. } => Assertion::Any(vec![
             Assertion::Owner,
             Assertion::SpaceAllows(SpaceOperation::CreateNode),
@@ -58,6 +61,7 @@ pub fn extract_assertion(event: &Event) -> Assertion {
             Assertion::All(vec![
                 Assertion::NodeExists(node_id),
                 Assertion::ContentKind { node_id, kind },
+                Assertion::ContentPatchApplies { node_id, patch },
                 Assertion::Any(vec![
                     Assertion::Owner,
                     Assertion::NodeAllows { operation, node_id },
@@ -132,9 +136,15 @@ pub fn extract_assertion(event: &Event) -> Assertion {
                 ]),
             ])
         }
-        Event::AddSnapshot { .. } => {
-            Assertion::Any(vec![Assertion::Owner, Assertion::SpaceAllows(AddSnapshot)])
-        }
+        Event::AddSnapshot { index, snapshot } => Assertion::All(vec![
+            Assertion::LatestIndex(*index),
+            Assertion::CurrentSnapshot(snapshot),
+            Assertion::Any(vec![
+                Assertion::NoOwner,
+                Assertion::Owner,
+                Assertion::SpaceAllows(AddSnapshot),
+            ]),
+        ]),
         Event::UpdateSpaceRules { rules: _ } => Assertion::Owner,
         Event::UpdateNodeRules { node_id, rules: _ } => Assertion::All(vec![
             Assertion::NodeExists(node_id),
@@ -166,6 +176,7 @@ mod tests {
         content::{Content, ContentKind},
         patch::StringPatch,
         rules::Rules,
+        snapshot::Snapshot,
         user::UserId,
     };
     use deskc_hir::expr::{Expr, Literal};
@@ -191,12 +202,19 @@ mod tests {
 
     #[test]
     fn extract_assertion_for_remove_owner() {
+        let user_id = UserId("owner_id".into());
         let event = Event::RemoveOwner {
-            user_id: UserId("owner_id".into()),
+            user_id: user_id.clone(),
         };
         assert_eq!(
             extract_assertion(&event),
-            Assertion::SpaceAllows(SpaceOperation::RemoveOwner)
+            Assertion::All(vec![
+                Assertion::OwnerRemovable(&user_id),
+                Assertion::Any(vec![
+                    Assertion::Owner,
+                    Assertion::SpaceAllows(SpaceOperation::RemoveOwner)
+                ]),
+            ])
         );
     }
 
@@ -262,9 +280,10 @@ mod tests {
     #[test]
     fn extract_assertion_for_patch_source_code() {
         let node_id = NodeId::new();
+        let patch = ContentPatch::PatchSourceCode(StringPatch::Replace("1".into()));
         let event = Event::PatchContent {
             node_id: node_id.clone(),
-            patch: ContentPatch::PatchSourceCode(StringPatch::Replace("1".into())),
+            patch: patch.clone(),
         };
         assert_eq!(
             extract_assertion(&event),
@@ -274,6 +293,10 @@ mod tests {
                     node_id: &node_id,
                     kind: ContentKind::SourceCode,
                 },
+                Assertion::ContentPatchApplies {
+                    node_id: &node_id,
+                    patch: &patch,
+                },
                 Assertion::Any(vec![
                     Assertion::Owner,
                     Assertion::NodeAllows {
@@ -288,12 +311,13 @@ mod tests {
     #[test]
     fn extract_assertion_for_change_source_code_syntax() {
         let node_id = NodeId::new();
+        let patch = ContentPatch::ChangeSourceCodeSyntax {
+            syntax: SyntaxKind::Hacker,
+            source: "1".into(),
+        };
         let event = Event::PatchContent {
             node_id: node_id.clone(),
-            patch: ContentPatch::ChangeSourceCodeSyntax {
-                syntax: SyntaxKind::Hacker,
-                source: "1".into(),
-            },
+            patch: patch.clone(),
         };
         assert_eq!(
             extract_assertion(&event),
@@ -303,6 +327,10 @@ mod tests {
                     node_id: &node_id,
                     kind: ContentKind::SourceCode,
                 },
+                Assertion::ContentPatchApplies {
+                    node_id: &node_id,
+                    patch: &patch,
+                },
                 Assertion::Any(vec![
                     Assertion::Owner,
                     Assertion::NodeAllows {
@@ -317,9 +345,10 @@ mod tests {
     #[test]
     fn extract_assertion_for_patch_string() {
         let node_id = NodeId::new();
+        let patch = ContentPatch::PatchString(StringPatch::Replace("a".into()));
         let event = Event::PatchContent {
             node_id: node_id.clone(),
-            patch: ContentPatch::PatchString(StringPatch::Replace("a".into())),
+            patch: patch.clone(),
         };
         assert_eq!(
             extract_assertion(&event),
@@ -329,6 +358,10 @@ mod tests {
                     node_id: &node_id,
                     kind: ContentKind::String,
                 },
+                Assertion::ContentPatchApplies {
+                    node_id: &node_id,
+                    patch: &patch,
+                },
                 Assertion::Any(vec![
                     Assertion::Owner,
                     Assertion::NodeAllows {
@@ -343,9 +376,10 @@ mod tests {
     #[test]
     fn extract_assertion_for_update_integer() {
         let node_id = NodeId::new();
+        let patch = ContentPatch::UpdateInteger(1);
         let event = Event::PatchContent {
             node_id: node_id.clone(),
-            patch: ContentPatch::UpdateInteger(1),
+            patch: patch.clone(),
         };
         assert_eq!(
             extract_assertion(&event),
@@ -355,6 +389,10 @@ mod tests {
                     node_id: &node_id,
                     kind: ContentKind::Integer,
                 },
+                Assertion::ContentPatchApplies {
+                    node_id: &node_id,
+                    patch: &patch,
+                },
                 Assertion::Any(vec![
                     Assertion::Owner,
                     Assertion::NodeAllows {
@@ -369,9 +407,10 @@ mod tests {
     #[test]
     fn extract_assertion_for_update_float() {
         let node_id = NodeId::new();
+        let patch = ContentPatch::UpdateFloat(1.0);
         let event = Event::PatchContent {
             node_id: node_id.clone(),
-            patch: ContentPatch::UpdateFloat(1.0),
+            patch: patch.clone(),
         };
         assert_eq!(
             extract_assertion(&event),
@@ -381,6 +420,10 @@ mod tests {
                     node_id: &node_id,
                     kind: ContentKind::Float,
                 },
+                Assertion::ContentPatchApplies {
+                    node_id: &node_id,
+                    patch: &patch,
+                },
                 Assertion::Any(vec![
                     Assertion::Owner,
                     Assertion::NodeAllows {
@@ -395,9 +438,10 @@ mod tests {
     #[test]
     fn extract_assertion_for_update_rational() {
         let node_id = NodeId::new();
+        let patch = ContentPatch::UpdateRational(1, 2);
         let event = Event::PatchContent {
             node_id: node_id.clone(),
-            patch: ContentPatch::UpdateRational(1, 2),
+            patch: patch.clone(),
         };
         assert_eq!(
             extract_assertion(&event),
@@ -407,6 +451,10 @@ mod tests {
                     node_id: &node_id,
                     kind: ContentKind::Rational,
                 },
+                Assertion::ContentPatchApplies {
+                    node_id: &node_id,
+                    patch: &patch,
+                },
                 Assertion::Any(vec![
                     Assertion::Owner,
                     Assertion::NodeAllows {
@@ -421,12 +469,13 @@ mod tests {
     #[test]
     fn extract_assertion_for_update_apply_type() {
         let node_id = NodeId::new();
+        let patch = ContentPatch::UpdateApply {
+            ty: Type::Number,
+            link_name: LinkName::None,
+        };
         let event = Event::PatchContent {
             node_id: node_id.clone(),
-            patch: ContentPatch::UpdateApply {
-                ty: Type::Number,
-                link_name: LinkName::None,
-            },
+            patch: patch.clone(),
         };
         assert_eq!(
             extract_assertion(&event),
@@ -436,6 +485,10 @@ mod tests {
                     node_id: &node_id,
                     kind: ContentKind::Apply,
                 },
+                Assertion::ContentPatchApplies {
+                    node_id: &node_id,
+                    patch: &patch,
+                },
                 Assertion::Any(vec![
                     Assertion::Owner,
                     Assertion::NodeAllows {
@@ -609,15 +662,21 @@ mod tests {
 
     #[test]
     fn extract_assertion_for_add_snapshot() {
+        let snapshot = Snapshot::default();
         let event = Event::AddSnapshot {
             index: 0,
-            snapshot: Default::default(),
+            snapshot: Box::new(snapshot.clone()),
         };
         assert_eq!(
             extract_assertion(&event),
-            Assertion::Any(vec![
-                Assertion::Owner,
-                Assertion::SpaceAllows(SpaceOperation::AddSnapshot)
+            Assertion::All(vec![
+                Assertion::LatestIndex(0),
+                Assertion::CurrentSnapshot(&snapshot),
+                Assertion::Any(vec![
+                    Assertion::NoOwner,
+                    Assertion::Owner,
+                    Assertion::SpaceAllows(SpaceOperation::AddSnapshot)
+                ]),
             ]),
         );
     }
@@ -1,8 +1,914 @@
-use components::{event::Event, snapshot::Snapshot};
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use components::{
+    content::Content,
+    event::{Event, EventEntry},
+    patch::{AttributePatch, ContentPatch, OperandPatch, StringPatch},
+    rules::{NodeOperation, Rules, SpaceOperation},
+    snapshot::Snapshot,
+    user::UserId,
+};
+use deskc_hir::expr::Expr;
+use deskc_ids::NodeId;
+use deskc_types::Type;
+
+/// Undo and redo stacks for each user.
+#[derive(Default)]
+pub struct History {
+    users: HashMap<UserId, UserHistory>,
+}
 
 #[derive(Default)]
-pub struct History {}
+struct UserHistory {
+    /// Reverts of each change, the latest last.
+    undo: Vec<Vec<Revert>>,
+    redo: Vec<Vec<Revert>>,
+    /// Undo and redo events which are committed but not handled yet.
+    pending: VecDeque<Pending>,
+}
+
+struct Pending {
+    direction: Direction,
+    events: VecDeque<Event>,
+    inverse: Vec<Revert>,
+    /// The undo or redo entry, which is taken back if the events fail to be committed.
+    reverts: Vec<Revert>,
+}
+
+enum Direction {
+    Undo,
+    Redo,
+}
+
+/// A revert of an event, which is made into events by the snapshot at the time of undo.
+///
+/// Others may have changed the workspace after the event, so operands are found by their ids,
+/// and rules and attributes are restored only where nobody else changed them.
+#[derive(Debug, Clone, PartialEq)]
+enum Revert {
+    Event(Event),
+    RemoveOperand {
+        node_id: NodeId,
+        operand_id: NodeId,
+    },
+    /// Inserts the operand next to the one which preceded it, or first if none.
+    InsertOperand {
+        node_id: NodeId,
+        operand_id: NodeId,
+        after: Option<NodeId>,
+    },
+    MoveOperand {
+        node_id: NodeId,
+        operand_id: NodeId,
+        after: Option<NodeId>,
+    },
+    Attribute {
+        node_id: NodeId,
+        key: Type,
+        before: Option<Box<Expr>>,
+        after: Option<Box<Expr>>,
+    },
+    SpaceRules {
+        before: Rules<SpaceOperation>,
+        after: Rules<SpaceOperation>,
+    },
+    NodeRules {
+        node_id: NodeId,
+        before: Rules<NodeOperation>,
+        after: Rules<NodeOperation>,
+    },
+    OperandRules {
+        node_id: NodeId,
+        before: Rules<NodeOperation>,
+        after: Rules<NodeOperation>,
+    },
+}
 
 impl History {
-    pub fn handle_event(&mut self, _snapshot: &Snapshot, _log: &Event) {}
+    /// Records the inverse of the audited event, which is computed from the snapshot before the event.
+    pub fn handle_event(&mut self, snapshot: &Snapshot, entry: &EventEntry) {
+        let inverse = inverse(snapshot, &entry.event);
+        let history = self.users.entry(entry.user_id.clone()).or_default();
+        if history.is_pending(&entry.event) {
+            let pending = history.pending.front_mut().unwrap();
+            pending.events.pop_front();
+            // Inverse events are committed in the reverse order.
+            pending.inverse.splice(0..0, inverse);
+            if pending.events.is_empty() {
+                history.finish_pending();
+            }
+        } else if !inverse.is_empty() {
+            history.undo.push(inverse);
+            history.redo.clear();
+        }
+    }
+
+    /// Gives up the undo or redo which the rejected event belongs to.
+    pub fn handle_rejected(&mut self, entry: &EventEntry) {
+        if let Some(history) = self.users.get_mut(&entry.user_id) {
+            if history.is_pending(&entry.event) {
+                // Events already handled can still be reverted.
+                history.finish_pending();
+            }
+        }
+    }
+
+    /// Events which undo the latest change of the user on the current snapshot.
+    ///
+    /// Parts which conflict with later changes by others are left out.
+    pub fn undo(&mut self, snapshot: &Snapshot, user_id: &UserId) -> Option<Vec<Event>> {
+        let history = self.users.get_mut(user_id)?;
+        let reverts = history.undo.pop()?;
+        Some(history.push_pending(Direction::Undo, snapshot, reverts))
+    }
+
+    /// Events which redo the latest undone change of the user on the current snapshot.
+    pub fn redo(&mut self, snapshot: &Snapshot, user_id: &UserId) -> Option<Vec<Event>> {
+        let history = self.users.get_mut(user_id)?;
+        let reverts = history.redo.pop()?;
+        Some(history.push_pending(Direction::Redo, snapshot, reverts))
+    }
+
+    /// Takes back the latest undo or redo of the user, whose events after the first `committed` ones
+    /// failed to be committed.
+    pub fn cancel(&mut self, user_id: &UserId, committed: usize) {
+        let Some(history) = self.users.get_mut(user_id) else {
+            return;
+        };
+        let Some(pending) = history.pending.back_mut() else {
+            return;
+        };
+        pending.events.truncate(committed);
+        let reverts = pending.reverts.clone();
+        match pending.direction {
+            Direction::Undo => history.undo.push(reverts),
+            Direction::Redo => history.redo.push(reverts),
+        }
+        // Events already committed are still handled as the undo or redo.
+        if pending.events.is_empty() {
+            history.pending.pop_back();
+        }
+    }
+}
+
+impl UserHistory {
+    fn is_pending(&self, event: &Event) -> bool {
+        self.pending
+            .front()
+            .and_then(|pending| pending.events.front())
+            == Some(event)
+    }
+
+    fn push_pending(
+        &mut self,
+        direction: Direction,
+        snapshot: &Snapshot,
+        reverts: Vec<Revert>,
+    ) -> Vec<Event> {
+        // Each revert is made on the snapshot changed by the previous ones.
+        let mut snapshot = snapshot.clone();
+        let mut events = vec![];
+        for revert in &reverts {
+            for event in revert.events(&snapshot) {
+                snapshot.handle_event(&event);
+                events.push(event);
+            }
+        }
+        if !events.is_empty() {
+            self.pending.push_back(Pending {
+                direction,
+                events: events.iter().cloned().collect(),
+                inverse: vec![],
+                reverts,
+            });
+        }
+        events
+    }
+
+    fn finish_pending(&mut self) {
+        let pending = self.pending.pop_front().unwrap();
+        if pending.inverse.is_empty() {
+            return;
+        }
+        match pending.direction {
+            Direction::Undo => self.redo.push(pending.inverse),
+            Direction::Redo => self.undo.push(pending.inverse),
+        }
+    }
+}
+
+impl Revert {
+    fn events(&self, snapshot: &Snapshot) -> Vec<Event> {
+        let operands = |node_id: &NodeId| {
+            snapshot
+                .flat_nodes
+                .get(node_id)
+                .map(|node| node.operands.clone())
+        };
+        let position = |operands: &[NodeId], operand_id: &NodeId| {
+            operands.iter().position(|id| id == operand_id)
+        };
+        // The operand goes next to the one which preceded it, or last if that one is gone.
+        let index_after = |operands: &[NodeId], after: &Option<NodeId>| match after {
+            Some(after) => position(operands, after).map_or(operands.len(), |index| index + 1),
+            None => 0,
+        };
+        let patch_operand = |node_id: &NodeId, patch| {
+            vec![Event::PatchOperand {
+                node_id: node_id.clone(),
+                patch,
+            }]
+        };
+        match self {
+            Revert::Event(event) => vec![event.clone()],
+            Revert::RemoveOperand {
+                node_id,
+                operand_id,
+            } => match operands(node_id).and_then(|operands| position(&operands, operand_id)) {
+                Some(index) => patch_operand(node_id, OperandPatch::Remove { index }),
+                None => vec![],
+            },
+            Revert::InsertOperand {
+                node_id,
+                operand_id,
+                after,
+            } => match operands(node_id) {
+                Some(operands) if position(&operands, operand_id).is_none() => patch_operand(
+                    node_id,
+                    OperandPatch::Insert {
+                        index: index_after(&operands, after),
+                        node_id: operand_id.clone(),
+                    },
+                ),
+                _ => vec![],
+            },
+            Revert::MoveOperand {
+                node_id,
+                operand_id,
+                after,
+            } => {
+                let mut operands = operands(node_id).unwrap_or_default();
+                let from = match position(&operands, operand_id) {
+                    Some(from) => from,
+                    None => return vec![],
+                };
+                operands.remove(from);
+                let to = index_after(&operands, after);
+                if from == to {
+                    return vec![];
+                }
+                patch_operand(node_id, OperandPatch::Move { from, to })
+            }
+            Revert::Attribute {
+                node_id,
+                key,
+                before,
+                after,
+            } => {
+                let current = snapshot
+                    .flat_nodes
+                    .get(node_id)
+                    .map(|node| node.attributes.get(key));
+                // Another change of the attribute conflicts with the revert.
+                if current != Some(after.as_deref()) {
+                    return vec![];
+                }
+                let patch = match before {
+                    Some(value) => AttributePatch::Update {
+                        key: key.clone(),
+                        value: value.clone(),
+                    },
+                    None => AttributePatch::Remove { key: key.clone() },
+                };
+                vec![Event::PatchAttribute {
+                    node_id: node_id.clone(),
+                    patch,
+                }]
+            }
+            Revert::SpaceRules { before, after } => {
+                match restore_rules(&snapshot.rules, before, after) {
+                    Some(rules) => vec![Event::UpdateSpaceRules { rules }],
+                    None => vec![],
+                }
+            }
+            Revert::NodeRules {
+                node_id,
+                before,
+                after,
+            } => match snapshot
+                .flat_nodes
+                .get(node_id)
+                .and_then(|node| restore_rules(&node.rules, before, after))
+            {
+                Some(rules) => vec![Event::UpdateNodeRules {
+                    node_id: node_id.clone(),
+                    rules,
+                }],
+                None => vec![],
+            },
+            Revert::OperandRules {
+                node_id,
+                before,
+                after,
+            } => match snapshot
+                .flat_nodes
+                .get(node_id)
+                .and_then(|node| restore_rules(&node.operand_rules, before, after))
+            {
+                Some(rules) => vec![Event::UpdateOperandRules {
+                    node_id: node_id.clone(),
+                    rules,
+                }],
+                None => vec![],
+            },
+        }
+    }
+}
+
+/// Restores the entries of the rules which were changed from `before` to `after`, unless others
+/// changed them again. `None` if nothing is restored.
+fn restore_rules<T: Eq + Hash + Clone>(
+    current: &Rules<T>,
+    before: &Rules<T>,
+    after: &Rules<T>,
+) -> Option<Rules<T>> {
+    let mut rules = current.clone();
+    if before.default != after.default && current.default == after.default {
+        rules.default = before.default.clone();
+    }
+    for user_id in before.users.keys().chain(after.users.keys()) {
+        let (before, after) = (before.users.get(user_id), after.users.get(user_id));
+        if before != after && current.users.get(user_id) == after {
+            match before {
+                Some(operations) => rules.users.insert(user_id.clone(), operations.clone()),
+                None => rules.users.remove(user_id),
+            };
+        }
+    }
+    (rules != *current).then_some(rules)
+}
+
+/// Reverts of the event, or nothing if the event changes nothing or cannot be reverted.
+fn inverse(snapshot: &Snapshot, event: &Event) -> Vec<Revert> {
+    match event {
+        Event::AddOwner { user_id } if !snapshot.owners.contains(user_id) => {
+            vec![Revert::Event(Event::RemoveOwner {
+                user_id: user_id.clone(),
+            })]
+        }
+        Event::RemoveOwner { user_id } if snapshot.owners.contains(user_id) => {
+            vec![Revert::Event(Event::AddOwner {
+                user_id: user_id.clone(),
+            })]
+        }
+        Event::AddOwner { .. } | Event::RemoveOwner { .. } => vec![],
+        Event::UpdateSpaceRules { rules } => vec![Revert::SpaceRules {
+            before: snapshot.rules.clone(),
+            after: rules.clone(),
+        }],
+        Event::CreateNode { node_id, .. } => vec![Revert::Event(Event::RemoveNode {
+            node_id: node_id.clone(),
+        })],
+        Event::RemoveNode { node_id } => {
+            let node = snapshot.flat_nodes.get(node_id).unwrap();
+            let mut events = vec![Event::CreateNode {
+                node_id: node_id.clone(),
+                content: node.content.clone(),
+            }];
+            if node.rules != Rules::default() {
+                events.push(Event::UpdateNodeRules {
+                    node_id: node_id.clone(),
+                    rules: node.rules.clone(),
+                });
+            }
+            if node.operand_rules != Rules::default() {
+                events.push(Event::UpdateOperandRules {
+                    node_id: node_id.clone(),
+                    rules: node.operand_rules.clone(),
+                });
+            }
+            events.extend(
+                node.attributes
+                    .iter()
+                    .map(|(key, value)| Event::PatchAttribute {
+                        node_id: node_id.clone(),
+                        patch: AttributePatch::Update {
+                            key: key.clone(),
+                            value: Box::new(value.clone()),
+                        },
+                    }),
+            );
+            events.extend(node.operands.iter().enumerate().map(|(index, operand_id)| {
+                Event::PatchOperand {
+                    node_id: node_id.clone(),
+                    patch: OperandPatch::Insert {
+                        index,
+                        node_id: operand_id.clone(),
+                    },
+                }
+            }));
+            events.into_iter().map(Revert::Event).collect()
+        }
+        Event::PatchContent { node_id, patch } => {
+            let node = snapshot.flat_nodes.get(node_id).unwrap();
+            let mut patched = node.clone();
+            if patched.patch_content(patch).is_err() {
+                return vec![];
+            }
+            vec![Revert::Event(Event::PatchContent {
+                node_id: node_id.clone(),
+                patch: inverse_content_patch(patch, &node.content, &patched.content),
+            })]
+        }
+        Event::PatchOperand { node_id, patch } => {
+            let operands = &snapshot.flat_nodes.get(node_id).unwrap().operands;
+            let node_id = node_id.clone();
+            let preceding =
+                |index: usize| index.checked_sub(1).map(|index| operands[index].clone());
+            let revert = match patch {
+                OperandPatch::Insert {
+                    node_id: operand_id,
+                    ..
+                } => Revert::RemoveOperand {
+                    node_id,
+                    operand_id: operand_id.clone(),
+                },
+                OperandPatch::Remove { index } => Revert::InsertOperand {
+                    node_id,
+                    operand_id: operands[*index].clone(),
+                    after: preceding(*index),
+                },
+                OperandPatch::Move { from, .. } => Revert::MoveOperand {
+                    node_id,
+                    operand_id: operands[*from].clone(),
+                    after: preceding(*from),
+                },
+            };
+            vec![revert]
+        }
+        Event::PatchAttribute { node_id, patch } => {
+            let (key, after) = match patch {
+                AttributePatch::Update { key, value } => (key, Some(value.clone())),
+                AttributePatch::Remove { key } => (key, None),
+            };
+            let before = snapshot
+                .flat_nodes
+                .get(node_id)
+                .unwrap()
+                .attributes
+                .get(key)
+                .cloned()
+                .map(Box::new);
+            if before == after {
+                return vec![];
+            }
+            vec![Revert::Attribute {
+                node_id: node_id.clone(),
+                key: key.clone(),
+                before,
+                after,
+            }]
+        }
+        Event::UpdateNodeRules { node_id, rules } => vec![Revert::NodeRules {
+            node_id: node_id.clone(),
+            before: snapshot.flat_nodes.get(node_id).unwrap().rules.clone(),
+            after: rules.clone(),
+        }],
+        Event::UpdateOperandRules { node_id, rules } => vec![Revert::OperandRules {
+            node_id: node_id.clone(),
+            before: snapshot
+                .flat_nodes
+                .get(node_id)
+                .unwrap()
+                .operand_rules
+                .clone(),
+            after: rules.clone(),
+        }],
+        // Snapshots are not a change by a user.
+        Event::AddSnapshot { .. } => vec![],
+    }
+}
+
+/// The same kind of patch as the original if possible, so undo needs no other operation than the change.
+fn inverse_content_patch(patch: &ContentPatch, before: &Content, after: &Content) -> ContentPatch {
+    match (patch, before, after) {
+        (
+            ContentPatch::ChangeSourceCodeSyntax { .. },
+            Content::SourceCode { syntax, source },
+            _,
+        ) => ContentPatch::ChangeSourceCodeSyntax {
+            syntax: syntax.clone(),
+            source: source.clone(),
+        },
+        // Diffs apply to the text edited by others after the change.
+        (
+            ContentPatch::PatchSourceCode(_),
+            Content::SourceCode { source: before, .. },
+            Content::SourceCode { source: after, .. },
+        ) => ContentPatch::PatchSourceCode(StringPatch::diff(after, before)),
+        (ContentPatch::PatchString(_), Content::String(before), Content::String(after)) => {
+            ContentPatch::PatchString(StringPatch::diff(after, before))
+        }
+        (ContentPatch::UpdateInteger(_), Content::Integer(integer), _) if *integer >= 0 => {
+            ContentPatch::UpdateInteger(*integer as u64)
+        }
+        (ContentPatch::UpdateFloat(_), Content::Float(float), _) => {
+            ContentPatch::UpdateFloat(*float)
+        }
+        (ContentPatch::UpdateRational(_, _), Content::Rational(numerator, denominator), _)
+            if *numerator >= 0 && *denominator > 0 =>
+        {
+            ContentPatch::UpdateRational(*numerator as u64, *denominator as u64)
+        }
+        (ContentPatch::UpdateApply { .. }, Content::Apply { ty, link_name }, _) => {
+            ContentPatch::UpdateApply {
+                ty: ty.clone(),
+                link_name: link_name.clone(),
+            }
+        }
+        // Replace, or numbers which an update patch cannot have like negative integers.
+        (_, before, _) => ContentPatch::Replace(before.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use components::{code::SyntaxKind, flat_node::FlatNode};
+    use deskc_hir::expr::Literal;
+
+    use super::*;
+
+    fn entry(user_id: &str, event: Event) -> EventEntry {
+        EventEntry {
+            index: 0,
+            user_id: UserId(user_id.into()),
+            event,
+        }
+    }
+
+    #[test]
+    fn inverse_of_remove_node() {
+        let node_id = NodeId::new();
+        let operand_id = NodeId::new();
+        let mut snapshot = Snapshot::default();
+        snapshot.flat_nodes.insert(
+            node_id.clone(),
+            FlatNode::new(Content::Integer(1)).operands(vec![operand_id.clone()]),
+        );
+        assert_eq!(
+            inverse(
+                &snapshot,
+                &Event::RemoveNode {
+                    node_id: node_id.clone()
+                }
+            ),
+            vec![
+                Revert::Event(Event::CreateNode {
+                    node_id: node_id.clone(),
+                    content: Content::Integer(1),
+                }),
+                Revert::Event(Event::PatchOperand {
+                    node_id,
+                    patch: OperandPatch::Insert {
+                        index: 0,
+                        node_id: operand_id,
+                    },
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn inverse_of_patch_source_code() {
+        let node_id = NodeId::new();
+        let mut snapshot = Snapshot::default();
+        snapshot.flat_nodes.insert(
+            node_id.clone(),
+            FlatNode::new(Content::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: "* 1, 2".into(),
+            }),
+        );
+        assert_eq!(
+            inverse(
+                &snapshot,
+                &Event::PatchContent {
+                    node_id: node_id.clone(),
+                    patch: ContentPatch::PatchSourceCode(StringPatch::diff("* 1, 2", "* 1, 3")),
+                }
+            ),
+            vec![Revert::Event(Event::PatchContent {
+                node_id,
+                patch: ContentPatch::PatchSourceCode(StringPatch::diff("* 1, 3", "* 1, 2")),
+            })]
+        );
+    }
+
+    #[test]
+    fn inverse_of_negative_integer() {
+        let node_id = NodeId::new();
+        let mut snapshot = Snapshot::default();
+        snapshot
+            .flat_nodes
+            .insert(node_id.clone(), FlatNode::new(Content::Integer(-1)));
+        assert_eq!(
+            inverse(
+                &snapshot,
+                &Event::PatchContent {
+                    node_id: node_id.clone(),
+                    patch: ContentPatch::UpdateInteger(1),
+                }
+            ),
+            vec![Revert::Event(Event::PatchContent {
+                node_id,
+                patch: ContentPatch::Replace(Content::Integer(-1)),
+            })]
+        );
+    }
+
+    #[test]
+    fn undo_and_redo_only_own_changes() {
+        let mut history = History::default();
+        let snapshot = Snapshot::default();
+        let node_a = NodeId::new();
+        let node_b = NodeId::new();
+        let create_a = Event::CreateNode {
+            node_id: node_a.clone(),
+            content: Content::Integer(0),
+        };
+        let remove_a = Event::RemoveNode {
+            node_id: node_a.clone(),
+        };
+        history.handle_event(&snapshot, &entry("a", create_a.clone()));
+        history.handle_event(
+            &snapshot,
+            &entry(
+                "b",
+                Event::CreateNode {
+                    node_id: node_b,
+                    content: Content::Integer(0),
+                },
+            ),
+        );
+
+        assert_eq!(
+            history.undo(&Snapshot::default(), &UserId("a".into())),
+            Some(vec![remove_a.clone()])
+        );
+        assert_eq!(
+            history.undo(&Snapshot::default(), &UserId("a".into())),
+            None
+        );
+
+        let mut snapshot = Snapshot::default();
+        snapshot
+            .flat_nodes
+            .insert(node_a, FlatNode::new(Content::Integer(0)));
+        history.handle_event(&snapshot, &entry("a", remove_a.clone()));
+        assert_eq!(
+            history.redo(&Snapshot::default(), &UserId("a".into())),
+            Some(vec![create_a.clone()])
+        );
+
+        history.handle_event(&Snapshot::default(), &entry("a", create_a));
+        assert_eq!(
+            history.undo(&Snapshot::default(), &UserId("a".into())),
+            Some(vec![remove_a])
+        );
+    }
+
+    #[test]
+    fn new_change_clears_redo() {
+        let mut history = History::default();
+        let node_id = NodeId::new();
+        let create = Event::CreateNode {
+            node_id: node_id.clone(),
+            content: Content::Integer(0),
+        };
+        history.handle_event(&Snapshot::default(), &entry("a", create));
+        history.undo(&Snapshot::default(), &UserId("a".into()));
+        let mut snapshot = Snapshot::default();
+        snapshot
+            .flat_nodes
+            .insert(node_id.clone(), FlatNode::new(Content::Integer(0)));
+        history.handle_event(&snapshot, &entry("a", Event::RemoveNode { node_id }));
+
+        history.handle_event(
+            &Snapshot::default(),
+            &entry(
+                "a",
+                Event::CreateNode {
+                    node_id: NodeId::new(),
+                    content: Content::Integer(1),
+                },
+            ),
+        );
+        assert_eq!(
+            history.redo(&Snapshot::default(), &UserId("a".into())),
+            None
+        );
+    }
+
+    #[test]
+    fn cancelled_undo_is_taken_back() {
+        let mut history = History::default();
+        let node_id = NodeId::new();
+        history.handle_event(
+            &Snapshot::default(),
+            &entry(
+                "a",
+                Event::CreateNode {
+                    node_id: node_id.clone(),
+                    content: Content::Integer(0),
+                },
+            ),
+        );
+        let remove = Event::RemoveNode { node_id };
+        history.undo(&Snapshot::default(), &UserId("a".into()));
+        history.cancel(&UserId("a".into()), 0);
+
+        assert_eq!(
+            history.undo(&Snapshot::default(), &UserId("a".into())),
+            Some(vec![remove])
+        );
+    }
+
+    #[test]
+    fn rejected_undo_is_given_up() {
+        let mut history = History::default();
+        let node_id = NodeId::new();
+        history.handle_event(
+            &Snapshot::default(),
+            &entry(
+                "a",
+                Event::CreateNode {
+                    node_id: node_id.clone(),
+                    content: Content::Integer(0),
+                },
+            ),
+        );
+        history.undo(&Snapshot::default(), &UserId("a".into()));
+        history.handle_rejected(&entry("a", Event::RemoveNode { node_id }));
+
+        assert_eq!(
+            history.redo(&Snapshot::default(), &UserId("a".into())),
+            None
+        );
+        assert_eq!(
+            history.undo(&Snapshot::default(), &UserId("a".into())),
+            None
+        );
+    }
+
+    fn patch_operand(node_id: &NodeId, patch: OperandPatch) -> Event {
+        Event::PatchOperand {
+            node_id: node_id.clone(),
+            patch,
+        }
+    }
+
+    /// Handles the event as the workspace does.
+    fn handle(history: &mut History, snapshot: &mut Snapshot, user_id: &str, event: Event) {
+        history.handle_event(snapshot, &entry(user_id, event.clone()));
+        snapshot.handle_event(&event);
+    }
+
+    #[test]
+    fn undo_insert_after_others_insert() {
+        let mut history = History::default();
+        let mut snapshot = Snapshot::default();
+        let node_id = NodeId::new();
+        let operand_a = NodeId::new();
+        snapshot
+            .flat_nodes
+            .insert(node_id.clone(), FlatNode::new(Content::Integer(0)));
+        let insert = |operand_id: &NodeId| {
+            patch_operand(
+                &node_id,
+                OperandPatch::Insert {
+                    index: 0,
+                    node_id: operand_id.clone(),
+                },
+            )
+        };
+        handle(&mut history, &mut snapshot, "a", insert(&operand_a));
+        handle(&mut history, &mut snapshot, "b", insert(&NodeId::new()));
+
+        assert_eq!(
+            history.undo(&snapshot, &UserId("a".into())),
+            Some(vec![patch_operand(
+                &node_id,
+                OperandPatch::Remove { index: 1 }
+            )])
+        );
+    }
+
+    #[test]
+    fn undo_move_after_others_insert() {
+        let mut history = History::default();
+        let mut snapshot = Snapshot::default();
+        let node_id = NodeId::new();
+        let (x, y) = (NodeId::new(), NodeId::new());
+        snapshot.flat_nodes.insert(
+            node_id.clone(),
+            FlatNode::new(Content::Integer(0)).operands(vec![x.clone(), y.clone()]),
+        );
+        handle(
+            &mut history,
+            &mut snapshot,
+            "a",
+            patch_operand(&node_id, OperandPatch::Move { from: 1, to: 0 }),
+        );
+        handle(
+            &mut history,
+            &mut snapshot,
+            "b",
+            patch_operand(
+                &node_id,
+                OperandPatch::Insert {
+                    index: 0,
+                    node_id: NodeId::new(),
+                },
+            ),
+        );
+
+        // y goes back after x.
+        assert_eq!(
+            history.undo(&snapshot, &UserId("a".into())),
+            Some(vec![patch_operand(
+                &node_id,
+                OperandPatch::Move { from: 1, to: 2 }
+            )])
+        );
+    }
+
+    #[test]
+    fn undo_attribute_changed_by_others_conflicts() {
+        let mut history = History::default();
+        let mut snapshot = Snapshot::default();
+        let node_id = NodeId::new();
+        snapshot
+            .flat_nodes
+            .insert(node_id.clone(), FlatNode::new(Content::Integer(0)));
+        let update = |value: i64| Event::PatchAttribute {
+            node_id: node_id.clone(),
+            patch: AttributePatch::Update {
+                key: Type::Number,
+                value: Box::new(Expr::Literal(Literal::Integer(value))),
+            },
+        };
+        handle(&mut history, &mut snapshot, "a", update(1));
+        handle(&mut history, &mut snapshot, "b", update(2));
+        assert_eq!(history.undo(&snapshot, &UserId("a".into())), Some(vec![]));
+
+        handle(&mut history, &mut snapshot, "a", update(3));
+        assert_eq!(
+            history.undo(&snapshot, &UserId("a".into())),
+            Some(vec![update(2)])
+        );
+    }
+
+    #[test]
+    fn undo_rules_keeps_others_changes() {
+        let mut history = History::default();
+        let mut snapshot = Snapshot::default();
+        let operations = |user_ids: &[&str]| Rules {
+            default: Default::default(),
+            users: user_ids
+                .iter()
+                .map(|user_id| {
+                    (
+                        UserId(user_id.to_string()),
+                        [SpaceOperation::CreateNode].into_iter().collect(),
+                    )
+                })
+                .collect(),
+        };
+        handle(
+            &mut history,
+            &mut snapshot,
+            "a",
+            Event::UpdateSpaceRules {
+                rules: operations(&["a"]),
+            },
+        );
+        handle(
+            &mut history,
+            &mut snapshot,
+            "b",
+            Event::UpdateSpaceRules {
+                rules: operations(&["a", "b"]),
+            },
+        );
+
+        assert_eq!(
+            history.undo(&snapshot, &UserId("a".into())),
+            Some(vec![Event::UpdateSpaceRules {
+                rules: operations(&["b"]),
+            }])
+        );
+    }
 }
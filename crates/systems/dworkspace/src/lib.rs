@@ -13,12 +13,16 @@ pub mod state;
 use std::{any::TypeId, collections::HashMap};
 
 use bevy_ecs::prelude::Component;
-use components::{event::Event, snapshot::Snapshot};
+use components::{
+    event::{Event, EventEntry},
+    snapshot::Snapshot,
+    user::UserId,
+};
 use history::History;
 use loop_detector::LoopDetector;
 use nodes::Nodes;
 use parking_lot::Mutex;
-use repository::Repository;
+use repository::{Repository, RepositoryError};
 use state::State;
 
 #[derive(Component)]
@@ -30,6 +34,12 @@ pub struct Workspace {
     references: Mutex<references::References>,
     loop_detector: LoopDetector,
     pub snapshot: Snapshot,
+    /// The index of the last handled event, at which a snapshot is taken.
+    latest_index: Option<usize>,
+    /// The index of the latest applied snapshot, before which the log can be compacted.
+    snapshot_index: Option<usize>,
+    /// Whether the first entry of the log is processed.
+    started: bool,
     history: History,
     states: HashMap<TypeId, Box<dyn State + Send + Sync + 'static>>,
 }
@@ -42,28 +52,103 @@ impl Workspace {
             references: Default::default(),
             loop_detector: Default::default(),
             snapshot: Default::default(),
+            latest_index: None,
+            snapshot_index: None,
+            started: false,
             history: Default::default(),
             states: Default::default(),
         }
     }
 
-    pub fn commit(&mut self, event: Event) {
-        self.repository.commit(event);
+    pub fn commit(&mut self, event: Event) -> Result<(), RepositoryError> {
+        self.repository.commit(event)
     }
 
     pub fn process(&mut self) {
         let entries = self.repository.poll();
         for entry in entries {
-            if self.audit(&entry).is_ok() {
-                self.handle_event(&entry.event);
+            let start = !std::mem::replace(&mut self.started, true);
+            // A compacted log starts with the snapshot of the events before it.
+            let compacted = matches!(
+                &entry.event,
+                Event::AddSnapshot { index, .. } if index + 1 == entry.index
+            );
+            if start && compacted || self.audit(&entry).is_ok() {
+                self.apply(&entry);
+            } else {
+                self.history.handle_rejected(&entry);
             }
         }
     }
 
+    fn apply(&mut self, entry: &EventEntry) {
+        self.history.handle_event(&self.snapshot, entry);
+        let owners = self.snapshot.owners.clone();
+        self.handle_event(&entry.event);
+        self.latest_index = Some(entry.index);
+        if let Event::AddSnapshot { .. } = entry.event {
+            self.snapshot_index = Some(entry.index);
+        }
+        // Repositories may restrict operations by owners, which are also replaced by snapshots.
+        for user_id in owners.difference(&self.snapshot.owners) {
+            self.repository.remove_owner(user_id.clone());
+        }
+        for user_id in self.snapshot.owners.difference(&owners) {
+            self.repository.add_owner(user_id.clone());
+        }
+    }
+
+    /// Drops the events before the latest snapshot which this workspace applied.
+    pub fn compact(&mut self) -> Result<(), RepositoryError> {
+        match self.snapshot_index {
+            Some(index) => self.repository.compact(index),
+            None => Ok(()),
+        }
+    }
+
+    /// Commits the current snapshot, so the workspace can be loaded without the events before it.
+    pub fn commit_snapshot(&mut self) -> Result<(), RepositoryError> {
+        if let Some(index) = self.latest_index {
+            self.commit(Event::AddSnapshot {
+                index,
+                snapshot: Box::new(self.snapshot.clone()),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Commits events which revert the latest change of the user of the repository, even if others
+    /// changed after it.
+    pub fn undo(&mut self) -> Result<(), RepositoryError> {
+        let user_id = self.repository.user_id();
+        let events = self.history.undo(&self.snapshot, &user_id);
+        self.commit_history(&user_id, events.unwrap_or_default())
+    }
+
+    /// Commits events which revert the latest undo of the user of the repository.
+    pub fn redo(&mut self) -> Result<(), RepositoryError> {
+        let user_id = self.repository.user_id();
+        let events = self.history.redo(&self.snapshot, &user_id);
+        self.commit_history(&user_id, events.unwrap_or_default())
+    }
+
+    fn commit_history(
+        &mut self,
+        user_id: &UserId,
+        events: Vec<Event>,
+    ) -> Result<(), RepositoryError> {
+        for (committed, event) in events.into_iter().enumerate() {
+            if let Err(err) = self.repository.commit(event) {
+                self.history.cancel(user_id, committed);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
     fn handle_event(&mut self, event: &Event) {
         self.nodes.lock().handle_event(event);
         self.references.lock().handle_event(&self.snapshot, event);
-        self.history.handle_event(&self.snapshot, event);
         for state in self.states.values_mut() {
             state.handle_event(&self.snapshot, event);
         }
@@ -91,6 +176,8 @@ impl Workspace {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
     use components::event::{Event, EventEntry};
     use components::rules::{NodeOperation, Rules, SpaceOperation};
     use components::user::UserId;
@@ -200,6 +287,8 @@ mod tests {
             },
         ]);
 
+        repository.mock_add_owner(mry::Any).returns(());
+
         let mut test_state = TestState::default();
         test_state.mock_handle_event(mry::Any, mry::Any).returns(());
 
@@ -254,4 +343,289 @@ mod tests {
             .mock_handle_event(Snapshot::default(), Event::AddOwner { user_id: user_a })
             .assert_called(1);
     }
+
+    fn repository_with_node(user_id: &UserId, node_id: &NodeId) -> TestRepository {
+        let mut repository = TestRepository::default();
+        let user_a = UserId("a".into());
+        repository.mock_user_id().returns(user_id.clone());
+        repository.mock_poll().returns(vec![
+            EventEntry {
+                index: 0,
+                user_id: user_a.clone(),
+                event: Event::AddOwner {
+                    user_id: user_a.clone(),
+                },
+            },
+            EventEntry {
+                index: 1,
+                user_id: user_a,
+                event: Event::CreateNode {
+                    node_id: node_id.clone(),
+                    content: Content::Integer(1),
+                },
+            },
+        ]);
+        repository.mock_add_owner(mry::Any).returns(());
+        repository
+    }
+
+    #[test]
+    fn undo_commits_inverse_events() {
+        let node_id = NodeId::new();
+        let mut repository = repository_with_node(&UserId("a".into()), &node_id);
+        let committed = Arc::new(Mutex::new(vec![]));
+        let committed_clone = committed.clone();
+        repository.mock_commit(mry::Any).returns_with(move |event| {
+            committed_clone.lock().push(event);
+            Ok(())
+        });
+
+        let mut kernel = Workspace::new(repository);
+        kernel.process();
+        kernel.undo().unwrap();
+
+        assert_eq!(*committed.lock(), vec![Event::RemoveNode { node_id }]);
+    }
+
+    #[test]
+    fn undo_only_changes_of_repository_user() {
+        let mut repository = repository_with_node(&UserId("b".into()), &NodeId::new());
+        repository.mock_commit(mry::Any).returns_with(|_| panic!());
+
+        let mut kernel = Workspace::new(repository);
+        kernel.process();
+        kernel.undo().unwrap();
+    }
+
+    #[test]
+    fn failed_undo_can_be_retried() {
+        let node_id = NodeId::new();
+        let mut repository = repository_with_node(&UserId("a".into()), &node_id);
+        let committed = Arc::new(Mutex::new(vec![]));
+        let committed_clone = committed.clone();
+        let failed = Arc::new(Mutex::new(false));
+        repository.mock_commit(mry::Any).returns_with(move |event| {
+            // Only the first commit fails.
+            if !std::mem::replace(&mut *failed.lock(), true) {
+                return Err("disk full".into());
+            }
+            committed_clone.lock().push(event);
+            Ok(())
+        });
+
+        let mut kernel = Workspace::new(repository);
+        kernel.process();
+        assert!(kernel.undo().is_err());
+        kernel.undo().unwrap();
+
+        assert_eq!(*committed.lock(), vec![Event::RemoveNode { node_id }]);
+    }
+
+    #[test]
+    fn commit_snapshot_at_latest_index() {
+        let mut repository = TestRepository::default();
+        let user_a = UserId("a".into());
+        repository.mock_poll().returns(vec![EventEntry {
+            index: 3,
+            user_id: user_a.clone(),
+            event: Event::AddOwner {
+                user_id: user_a.clone(),
+            },
+        }]);
+        repository.mock_add_owner(mry::Any).returns(());
+        let committed = Arc::new(Mutex::new(vec![]));
+        let committed_clone = committed.clone();
+        repository.mock_commit(mry::Any).returns_with(move |event| {
+            committed_clone.lock().push(event);
+            Ok(())
+        });
+
+        let mut kernel = Workspace::new(repository);
+        kernel.commit_snapshot().unwrap();
+        kernel.process();
+        kernel.commit_snapshot().unwrap();
+
+        assert_eq!(
+            *committed.lock(),
+            vec![Event::AddSnapshot {
+                index: 3,
+                snapshot: Box::new(kernel.snapshot.clone()),
+            }]
+        );
+    }
+
+    #[test]
+    fn snapshot_requires_latest_index() {
+        let mut repository = TestRepository::default();
+        let user_a = UserId("a".into());
+        let snapshot = Snapshot {
+            owners: [user_a.clone()].into_iter().collect(),
+            ..Default::default()
+        };
+        repository.mock_poll().returns(vec![EventEntry {
+            index: 0,
+            user_id: user_a.clone(),
+            event: Event::AddSnapshot {
+                index: 0,
+                snapshot: Box::new(snapshot.clone()),
+            },
+        }]);
+        let mut kernel = Workspace::new(repository);
+        kernel.process();
+        // No event is before the first one.
+        assert_eq!(kernel.snapshot, Snapshot::default());
+
+        let mut repository = TestRepository::default();
+        repository.mock_poll().returns(vec![EventEntry {
+            index: 3,
+            user_id: user_a,
+            event: Event::AddSnapshot {
+                index: 2,
+                snapshot: Box::new(snapshot.clone()),
+            },
+        }]);
+        repository.mock_add_owner(mry::Any).returns(());
+        let mut kernel = Workspace::new(repository);
+        kernel.process();
+        // A compacted log starts from the snapshot of the events before it.
+        assert_eq!(kernel.snapshot, snapshot);
+    }
+
+    #[test]
+    fn compacts_only_at_applied_snapshot() {
+        let mut repository = TestRepository::default();
+        let user_a = UserId("a".into());
+        let entry = |index, event| EventEntry {
+            index,
+            user_id: user_a.clone(),
+            event,
+        };
+        let add_owner = Event::AddOwner {
+            user_id: user_a.clone(),
+        };
+        let mut snapshot = Snapshot::default();
+        snapshot.handle_event(&add_owner);
+        repository.mock_poll().returns(vec![
+            entry(0, add_owner),
+            // Not the current snapshot.
+            entry(
+                1,
+                Event::AddSnapshot {
+                    index: 0,
+                    snapshot: Box::new(Snapshot {
+                        owners: [UserId("b".into())].into_iter().collect(),
+                        ..Default::default()
+                    }),
+                },
+            ),
+            entry(
+                2,
+                Event::AddSnapshot {
+                    index: 0,
+                    snapshot: Box::new(snapshot.clone()),
+                },
+            ),
+        ]);
+        repository.mock_add_owner(mry::Any).returns(());
+        repository.mock_compact(2).returns_with(|_| Ok(()));
+
+        let mut kernel = Workspace::new(repository);
+        kernel.process();
+        kernel.compact().unwrap();
+
+        assert_eq!(kernel.snapshot, snapshot);
+    }
+
+    #[test]
+    fn rotate_owners() {
+        let mut repository = TestRepository::default();
+        let user_a = UserId("a".into());
+        let user_b = UserId("b".into());
+        let entry = |index, user_id: &UserId, event| EventEntry {
+            index,
+            user_id: user_id.clone(),
+            event,
+        };
+        repository.mock_poll().returns(vec![
+            entry(
+                0,
+                &user_a,
+                Event::AddOwner {
+                    user_id: user_a.clone(),
+                },
+            ),
+            entry(
+                1,
+                &user_a,
+                Event::AddOwner {
+                    user_id: user_b.clone(),
+                },
+            ),
+            entry(
+                2,
+                &user_b,
+                Event::RemoveOwner {
+                    user_id: user_a.clone(),
+                },
+            ),
+            // The last owner cannot be removed.
+            entry(
+                3,
+                &user_b,
+                Event::RemoveOwner {
+                    user_id: user_b.clone(),
+                },
+            ),
+        ]);
+        let owners = Arc::new(Mutex::new(vec![]));
+        let added = owners.clone();
+        repository
+            .mock_add_owner(mry::Any)
+            .returns_with(move |user_id| added.lock().push(user_id));
+        let removed = owners.clone();
+        repository
+            .mock_remove_owner(mry::Any)
+            .returns_with(move |user_id| removed.lock().retain(|owner| *owner != user_id));
+
+        let mut kernel = Workspace::new(repository);
+        kernel.process();
+
+        assert_eq!(
+            kernel.snapshot.owners,
+            [user_b.clone()].into_iter().collect()
+        );
+        assert_eq!(*owners.lock(), vec![user_b]);
+    }
+
+    #[test]
+    fn sync_owners_from_snapshot() {
+        let mut repository = TestRepository::default();
+        let user_b = UserId("b".into());
+        // A compacted log.
+        repository.mock_poll().returns(vec![EventEntry {
+            index: 3,
+            user_id: UserId("a".into()),
+            event: Event::AddSnapshot {
+                index: 2,
+                snapshot: Box::new(Snapshot {
+                    owners: [user_b.clone()].into_iter().collect(),
+                    ..Default::default()
+                }),
+            },
+        }]);
+        let owners = Arc::new(Mutex::new(vec![]));
+        let added = owners.clone();
+        repository
+            .mock_add_owner(mry::Any)
+            .returns_with(move |user_id| added.lock().push(user_id));
+        let removed = owners.clone();
+        repository
+            .mock_remove_owner(mry::Any)
+            .returns_with(move |user_id| removed.lock().retain(|owner| *owner != user_id));
+
+        let mut kernel = Workspace::new(repository);
+        kernel.process();
+
+        assert_eq!(*owners.lock(), vec![user_b]);
+    }
 }
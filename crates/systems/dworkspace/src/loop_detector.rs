@@ -59,6 +59,19 @@ impl LoopDetector {
                     .lock()
                     .set_node(node_id.clone(), Default::default());
             }
+            Event::AddSnapshot {
+                index: _,
+                snapshot: added,
+            } => {
+                let mut lock = self.operand.lock();
+                for node_id in snapshot.flat_nodes.keys() {
+                    lock.set_node(node_id.clone(), Default::default());
+                }
+                for (node_id, flat_node) in &added.flat_nodes {
+                    let operands = flat_node.operands.iter().cloned().collect();
+                    lock.set_node(node_id.clone(), Arc::new(operands));
+                }
+            }
             _ => {}
         }
     }
@@ -182,4 +195,47 @@ mod tests {
             Arc::new([node_c].into_iter().collect())
         );
     }
+
+    #[test]
+    fn handle_add_snapshot() {
+        let mut detector = LoopDetector::default();
+        let node_a = NodeId::new();
+        let node_b = NodeId::new();
+        let removed = NodeId::new();
+        let mut snapshot = Snapshot::default();
+        snapshot.flat_nodes.insert(
+            removed.clone(),
+            FlatNode::new(Content::Integer(1)).operands(vec![node_a.clone()]),
+        );
+        detector.handle_event(
+            &Snapshot::default(),
+            &Event::AddSnapshot {
+                index: 0,
+                snapshot: Box::new(snapshot.clone()),
+            },
+        );
+        let mut added = Snapshot::default();
+        added.flat_nodes.insert(
+            node_a.clone(),
+            FlatNode::new(Content::Integer(1)).operands(vec![node_b.clone()]),
+        );
+        added
+            .flat_nodes
+            .insert(node_b.clone(), FlatNode::new(Content::Integer(2)));
+        detector.handle_event(
+            &snapshot,
+            &Event::AddSnapshot {
+                index: 1,
+                snapshot: Box::new(added),
+            },
+        );
+        assert_eq!(
+            detector.operand.lock().descendants(node_a),
+            Arc::new([node_b].into_iter().collect())
+        );
+        assert_eq!(
+            detector.operand.lock().descendants(removed),
+            Default::default()
+        );
+    }
 }
@@ -36,8 +36,10 @@ impl Nodes {
             }
             Event::PatchContent { node_id, patch } => {
                 let mut flat_node = self.flat_node(node_id.clone()).as_ref().clone();
-                flat_node.patch_content(patch);
-                self.set_flat_node(node_id.clone(), Arc::new(flat_node));
+                // Patches which do not apply are rejected by the audit, and leave the node unchanged.
+                if flat_node.patch_content(patch).is_ok() {
+                    self.set_flat_node(node_id.clone(), Arc::new(flat_node));
+                }
             }
             Event::PatchOperand { node_id, patch } => {
                 let mut flat_node = self.flat_node(node_id.clone()).as_ref().clone();
@@ -49,7 +51,13 @@ impl Nodes {
                 flat_node.patch_attribute(patch);
                 self.set_flat_node(node_id.clone(), Arc::new(flat_node));
             }
-            Event::AddSnapshot { .. } => todo!(),
+            // Rebuilt from the snapshot, so nodes which are not in it are dropped.
+            Event::AddSnapshot { index: _, snapshot } => {
+                *self = Nodes::default();
+                for (node_id, flat_node) in &snapshot.flat_nodes {
+                    self.set_flat_node(node_id.clone(), Arc::new(flat_node.clone()));
+                }
+            }
             _ => {}
         }
     }
@@ -58,9 +66,12 @@ impl Nodes {
 #[cfg(test)]
 mod tests {
     use components::{
+        code::SyntaxKind,
         content::Content,
         patch::{AttributePatch, ContentPatch, OperandPatch},
+        snapshot::Snapshot,
     };
+    use deskc_ast::remove_span::remove_span;
     use deskc_hir::expr::{Expr, Literal};
     use deskc_types::Type;
 
@@ -95,6 +106,33 @@ mod tests {
         assert_eq!(db.flat_node(node_id).content, Content::String("b".into()));
     }
 
+    #[test]
+    fn change_source_code_syntax() {
+        let mut db = Nodes::default();
+        let node_id = NodeId::new();
+        db.handle_event(&Event::CreateNode {
+            node_id: node_id.clone(),
+            content: Content::SourceCode {
+                syntax: SyntaxKind::Hacker,
+                source: "* 1, \"a\"".into(),
+            },
+        });
+        let mut hacker = db.ast(node_id.clone()).unwrap().as_ref().clone();
+
+        db.handle_event(&Event::PatchContent {
+            node_id: node_id.clone(),
+            patch: ContentPatch::ChangeSourceCodeSyntax {
+                syntax: SyntaxKind::RustLike,
+                source: "(1, \"a\")".into(),
+            },
+        });
+        let mut rust_like = db.ast(node_id).unwrap().as_ref().clone();
+
+        remove_span(&mut hacker);
+        remove_span(&mut rust_like);
+        assert_eq!(rust_like, hacker);
+    }
+
     #[test]
     fn patch_children() {
         let mut db = Nodes::default();
@@ -133,6 +171,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn add_snapshot_drops_other_nodes() {
+        let mut db = Nodes::default();
+        let removed = handle_add_node(&mut db);
+        let node_id = NodeId::new();
+        let mut snapshot = Snapshot::default();
+        snapshot
+            .flat_nodes
+            .insert(node_id.clone(), FlatNode::new(Content::Integer(1)));
+
+        db.handle_event(&Event::AddSnapshot {
+            index: 0,
+            snapshot: Box::new(snapshot),
+        });
+
+        assert_eq!(db.flat_node(node_id).content, Content::Integer(1));
+        // Querying an input which is not set panics.
+        assert!(
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| db.flat_node(removed)))
+                .is_err()
+        );
+    }
+
     fn handle_add_node(db: &mut Nodes) -> NodeId {
         let node_id = NodeId::new();
         db.handle_event(&Event::CreateNode {
@@ -7,8 +7,6 @@ use deskc_ast::{
     ty::{Effect, EffectExpr, Type},
 };
 use deskc_ids::NodeId;
-use deskc_lexer::scan;
-use deskc_parser::parse;
 
 use crate::query_result::{QueryError, QueryResult};
 
@@ -22,10 +20,7 @@ pub(super) fn ast(db: &dyn NodeQueries, node_id: NodeId) -> QueryResult<WithSpan
 
 fn genast(node: &Node) -> Result<WithSpan<Expr>, QueryError> {
     let expr = match &node.content {
-        Content::SourceCode { syntax: _, source } => {
-            let tokens = scan(source)?;
-            return Ok(parse(tokens)?);
-        }
+        Content::SourceCode { syntax, source } => return deskc::syntax::parse(syntax, source),
         Content::String(string) => Expr::Literal(Literal::String(string.clone())),
         Content::Integer(integer) => Expr::Literal(Literal::Integer(*integer)),
         Content::Rational(a, b) => Expr::Literal(Literal::Rational(*a, *b)),
@@ -61,7 +56,12 @@ fn from_types(ty: &deskc_types::Type) -> WithSpan<Type> {
         Vector(ty) => Type::Vector(Box::new(from_types(ty))),
         Set(ty) => Type::Set(Box::new(from_types(ty))),
         Variable(ident) => Type::Variable(ident.clone()),
-        ForAll { .. } => todo!(),
+        ForAll { variable, body } => Type::Let {
+            variable: variable.clone(),
+            body: Box::new(from_types(body)),
+        },
+        // Let it be inferred again.
+        Error => Type::Infer,
         Effectful { ty, effects } => Type::Effectful {
             ty: Box::new(from_types(ty)),
             effects: from_types_effects(effects),
@@ -74,6 +74,8 @@ fn from_types(ty: &deskc_types::Type) -> WithSpan<Type> {
             brand: label.clone(),
             item: Box::new(from_types(item)),
         },
+        Trait(types) => Type::Trait(types.iter().map(from_types).collect()),
+        This => Type::This,
     };
     WithSpan {
         id: NodeId::new(),
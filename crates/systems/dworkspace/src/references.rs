@@ -1,4 +1,7 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use components::{
     event::Event,
@@ -83,6 +86,41 @@ impl References {
             Event::UpdateOperandRules { node_id, rules } => {
                 self.set_operand_rules(node_id.clone(), Arc::new(rules.clone()));
             }
+            // Rebuilt from the snapshot, so nodes which are not in it are dropped.
+            Event::AddSnapshot {
+                index: _,
+                snapshot: added,
+            } => {
+                *self = References::default();
+                let mut references: HashMap<NodeId, HashSet<NodeId>> = added
+                    .flat_nodes
+                    .keys()
+                    .map(|node_id| (node_id.clone(), HashSet::new()))
+                    .collect();
+                for (node_id, flat_node) in &added.flat_nodes {
+                    for operand_id in &flat_node.operands {
+                        references
+                            .entry(operand_id.clone())
+                            .or_default()
+                            .insert(node_id.clone());
+                    }
+                    self.set_operand_rules(
+                        node_id.clone(),
+                        Arc::new(flat_node.operand_rules.clone()),
+                    );
+                }
+                for (node_id, references) in references {
+                    self.set_node(node_id, Arc::new(references));
+                }
+            }
+            // Operands of a removed node can be inserted again, for example by undo.
+            Event::RemoveNode { node_id } => {
+                for operand_id in &snapshot.flat_nodes.get(node_id).unwrap().operands {
+                    let mut references = self.node(operand_id.clone()).as_ref().clone();
+                    references.remove(node_id);
+                    self.set_node(operand_id.clone(), Arc::new(references));
+                }
+            }
             _ => {}
         }
     }
@@ -252,6 +290,51 @@ mod tests {
         assert_eq!(db.node(operand_id), Arc::new([].into_iter().collect()));
     }
 
+    #[test]
+    fn handle_event_add_snapshot() {
+        let mut db = References::default();
+        let node_id = NodeId::new();
+        let operand_id = NodeId::new();
+        let mut snapshot = Snapshot::default();
+        snapshot.flat_nodes.insert(
+            node_id.clone(),
+            FlatNode::new(Content::Integer(1)).operands(vec![operand_id.clone()]),
+        );
+        snapshot
+            .flat_nodes
+            .insert(operand_id.clone(), FlatNode::new(Content::Integer(2)));
+        db.handle_event(
+            &Snapshot::default(),
+            &Event::AddSnapshot {
+                index: 0,
+                snapshot: Box::new(snapshot),
+            },
+        );
+        assert_eq!(
+            db.node(operand_id),
+            Arc::new([node_id.clone()].into_iter().collect())
+        );
+        assert_eq!(db.node(node_id), Arc::new([].into_iter().collect()));
+    }
+
+    #[test]
+    fn handle_event_remove_node() {
+        let mut db = References::default();
+        let mut snapshot = Snapshot::default();
+        let node_id = NodeId::new();
+        let operand_id = NodeId::new();
+        snapshot.flat_nodes.insert(
+            node_id.clone(),
+            FlatNode::new(Content::Integer(1)).operands(vec![operand_id.clone()]),
+        );
+        db.set_node(
+            operand_id.clone(),
+            Arc::new([node_id.clone()].into_iter().collect()),
+        );
+        db.handle_event(&snapshot, &Event::RemoveNode { node_id });
+        assert_eq!(db.node(operand_id), Arc::new([].into_iter().collect()));
+    }
+
     #[test]
     fn handle_event_update_operand_rules() {
         let mut db = References::default();
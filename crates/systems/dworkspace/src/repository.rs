@@ -2,12 +2,28 @@ use components::{
     event::{Event, EventEntry},
     user::UserId,
 };
+use thiserror::Error;
+
+/// Errors of repositories, which depend on where the log is stored.
+pub type RepositoryError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A user who is not an owner tried to compact a space which has owners.
+#[derive(Error, Debug, PartialEq)]
+#[error("{0:?} is not an owner of the space")]
+pub struct NotOwnerError(pub UserId);
 
 pub trait Repository {
+    /// The user who commits events to this repository.
+    fn user_id(&self) -> UserId;
     fn poll(&mut self) -> Vec<EventEntry>;
-    fn commit(&mut self, event: Event);
+    fn commit(&mut self, event: Event) -> Result<(), RepositoryError>;
     fn add_owner(&mut self, user_id: UserId);
     fn remove_owner(&mut self, user_id: UserId);
+    /// Drops the events before the snapshot at the index, which a workspace applied, so they are
+    /// not needed to load the workspace.
+    ///
+    /// Only owners can compact a space which has owners.
+    fn compact(&mut self, index: usize) -> Result<(), RepositoryError>;
 }
 
 #[cfg(test)]
@@ -18,10 +34,13 @@ pub struct TestRepository {}
 #[cfg(test)]
 #[mry::mry]
 impl Repository for TestRepository {
+    fn user_id(&self) -> UserId {
+        panic!()
+    }
     fn poll(&mut self) -> Vec<EventEntry> {
         panic!()
     }
-    fn commit(&mut self, log: Event) {
+    fn commit(&mut self, log: Event) -> Result<(), RepositoryError> {
         panic!()
     }
     fn add_owner(&mut self, user_id: UserId) {
@@ -30,4 +49,7 @@ impl Repository for TestRepository {
     fn remove_owner(&mut self, user_id: UserId) {
         panic!()
     }
+    fn compact(&mut self, index: usize) -> Result<(), RepositoryError> {
+        panic!()
+    }
 }
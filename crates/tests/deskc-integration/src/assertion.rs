@@ -1,9 +1,11 @@
 use dprocess::value::Value;
 use serde::{Deserialize, Serialize};
-use types::Type;
+use types::{Effect, Type};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum Assertion {
     RunSuccess { result: Value },
+    RunPerformed { input: Value, effect: Effect },
     Typed(Vec<(usize, Type)>),
+    TypeError { code: String },
 }
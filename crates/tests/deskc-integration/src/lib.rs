@@ -33,7 +33,7 @@ macro_rules! test {
                     .into_iter()
                     .fold(
                         report,
-                        |report, textual_diagnostics::Report { span, text }| {
+                        |report, textual_diagnostics::Report { span, text, .. }| {
                             report.with_label(Label::new(span).with_message(text))
                         },
                     )
@@ -51,11 +51,37 @@ macro_rules! test {
                 Err(errors) => print_errors(input, errors),
             };
             let (genhir, hir) = hirgen::gen_cards(&ast).unwrap();
-            let entrypoint = hir.entrypoint.unwrap();
-            let ctx = match typeinfer::synth(genhir.next_id(), &entrypoint) {
-                Ok((ctx, _ty)) => ctx,
-                Err(error) => print_errors(input, error),
+            let entrypoint = match &test_case.entrypoint_card {
+                Some(uuid) => hir
+                    .cards
+                    .iter()
+                    .find(|(card_id, _)| card_id.0.to_string() == *uuid)
+                    .map(|(_, card)| card.clone())
+                    .unwrap(),
+                None => hir.entrypoint.unwrap(),
             };
+            let type_error = test_case
+                .assertions
+                .iter()
+                .find_map(|assertion| match assertion {
+                    Assertion::TypeError { code } => Some(code),
+                    _ => None,
+                });
+            let ctx = match (typeinfer::synth(genhir.next_id(), &entrypoint), type_error) {
+                (Ok((ctx, _ty)), None) => ctx,
+                (Ok(_), Some(code)) => panic!("{} is not reported", code),
+                (Err(error), Some(code)) => {
+                    assert_eq!(error.error.code(), code);
+                    passes("TypeError");
+                    return;
+                }
+                (Err(error), None) => print_errors(input, error),
+            };
+            if let Err(errors) =
+                thirgen::exhaustiveness::check_matches(&ctx.get_types(), &entrypoint)
+            {
+                print_errors::<()>(input, errors);
+            }
 
             for assertion in test_case.assertions.iter() {
                 match assertion {
@@ -90,26 +116,61 @@ macro_rules! test {
 
             let thir = thirgen::gen_typed_hir(ctx.next_id(), ctx.get_types(), &entrypoint);
             let mir = mirgen::gen_mir(&thir).unwrap();
+            // compile cards to be linked
+            let cards: std::collections::HashMap<_, _> = hir
+                .cards
+                .iter()
+                .map(|(card_id, card)| {
+                    let ctx = match typeinfer::synth(genhir.next_id(), card) {
+                        Ok((ctx, _ty)) => ctx,
+                        Err(error) => print_errors(input, error),
+                    };
+                    if let Err(errors) =
+                        thirgen::exhaustiveness::check_matches(&ctx.get_types(), card)
+                    {
+                        print_errors::<()>(input, errors);
+                    }
+                    let thir = thirgen::gen_typed_hir(ctx.next_id(), ctx.get_types(), card);
+                    (
+                        ids::LinkName::Card(card_id.0),
+                        mirgen::gen_mir(&thir).unwrap(),
+                    )
+                })
+                .collect();
+            let mir = match linker::link(mir, &cards) {
+                Ok(mir) => mir,
+                Err(errors) => {
+                    let spans = std::iter::once(&entrypoint)
+                        .chain(hir.cards.iter().map(|(_, card)| card))
+                        .flat_map(hir::helper::link_spans)
+                        .collect();
+                    print_errors(input, errors.with_spans(&spans))
+                }
+            };
             let mut miri = miri::eval_mir(mir);
             use dprocess::interpreter::Interpreter;
-            let value = loop {
+            let output = loop {
                 match miri.reduce(&std::time::Duration::from_secs(1)).unwrap() {
-                    dprocess::interpreter_output::InterpreterOutput::Returned(ret) => break ret,
+                    dprocess::interpreter_output::InterpreterOutput::Returned(ret) => {
+                        break Ok(ret)
+                    }
                     dprocess::interpreter_output::InterpreterOutput::Performed {
                         input,
                         effect,
-                    } => {
-                        panic!("perform {:?} {:?}", input, effect)
-                    }
+                    } => break Err((input, effect)),
                     dprocess::interpreter_output::InterpreterOutput::Running => continue,
                 }
             };
             for assertion in test_case.assertions.iter() {
                 match assertion {
                     Assertion::RunSuccess { result } => {
-                        assert_eq!(value, *result);
+                        assert_eq!(output, Ok(result.clone()));
                         passes("RunSuccess");
                     }
+                    Assertion::RunPerformed { input, effect } => {
+                        assert_eq!(output, Err((input.clone(), effect.clone())));
+                        passes("RunPerformed");
+                    }
                     _ => {}
                 }
             }
@@ -121,7 +182,23 @@ test!(case001, "../cases/001_literal.dson");
 test!(case002, "../cases/002_addition.dson");
 test!(case003, "../cases/003_match.dson");
 test!(case004, "../cases/004_let_function.dson");
-// test!(case005, "../cases/005_division_by_zero.dson");
+test!(case005, "../cases/005_division_by_zero.dson");
 test!(case006, "../cases/006_continuation.dson");
 test!(case007, "../cases/007_fibonacci.dson");
-// test!(case008, "../cases/008_cards.dson");
+test!(case008, "../cases/008_cards.dson");
+test!(case009, "../cases/009_hole.dson");
+test!(case010, "../cases/010_trait.dson");
+test!(case011, "../cases/011_vector.dson");
+test!(case012, "../cases/012_set.dson");
+test!(case013, "../cases/013_exception.dson");
+test!(case014, "../cases/014_nondeterminism.dson");
+test!(case015, "../cases/015_generator.dson");
+test!(case016, "../cases/016_state.dson");
+test!(case017, "../cases/017_nested_handlers.dson");
+test!(case018, "../cases/018_brand.dson");
+test!(case019, "../cases/019_polymorphism.dson");
+test!(case020, "../cases/020_missing_implementation.dson");
+test!(case021, "../cases/021_overflow.dson");
+test!(case022, "../cases/022_continue.dson");
+test!(case023, "../cases/023_brand_mismatch.dson");
+test!(case024, "../cases/024_polymorphic_parameters.dson");
@@ -7,6 +7,9 @@ pub struct TestCase {
     pub files: Vec<File>,
     // file name
     pub entrypoint: String,
+    // uuid of the card which is run instead of the entrypoint of the file
+    #[serde(default)]
+    pub entrypoint_card: Option<String>,
     pub assertions: Vec<Assertion>,
 }
 